@@ -0,0 +1,54 @@
+/*
+ * A C API for logging through the Rust `log` crate's global logger.
+ *
+ * These functions are exported by the `log-ffi` crate. See its documentation
+ * for how to link it into a Rust library or executable.
+ */
+
+#ifndef LIBLOG_H
+#define LIBLOG_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Levels, with the same values as `log::Level` */
+#define LOG_LEVEL_ERROR 1
+#define LOG_LEVEL_WARN 2
+#define LOG_LEVEL_INFO 3
+#define LOG_LEVEL_DEBUG 4
+#define LOG_LEVEL_TRACE 5
+
+/* The record was logged, or filtered out by the maximum level or the logger */
+#define LOG_OK 0
+/* The level was out of range, or the target or message was null */
+#define LOG_ERR_INVALID_ARGUMENT -1
+/* The key-values weren't a valid JSON object; the record is still logged without them */
+#define LOG_ERR_INVALID_KV -2
+/* The logger panicked */
+#define LOG_ERR_PANIC -3
+
+/*
+ * Log a message.
+ *
+ * `target` and `msg` must be null-terminated strings. `kv_json` must be a
+ * null-terminated JSON object whose members become the record's key-values,
+ * or NULL if the record has no key-values. Strings that aren't valid UTF-8
+ * are converted lossily.
+ *
+ * Returns LOG_OK, or one of the LOG_ERR_* constants.
+ */
+int log_log(int level, const char *target, const char *msg, const char *kv_json);
+
+/*
+ * Whether a record at `level` for `target` would be logged.
+ *
+ * Returns 1 if it would, and 0 if it wouldn't or the arguments are invalid.
+ */
+int log_enabled(int level, const char *target);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LIBLOG_H */
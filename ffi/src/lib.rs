@@ -0,0 +1,269 @@
+//! A C API for logging through the global logger.
+//!
+//! This crate exports `extern "C"` functions that C and C++ code, or extensions
+//! written in other languages, can call to log through the logger installed with
+//! [`log::set_logger`], with the same levels and targets as Rust code in the same
+//! process. They're declared in `include/liblog.h`:
+//!
+//! ```c
+//! #include "liblog.h"
+//!
+//! if (log_enabled(LOG_LEVEL_INFO, "plugin::resize")) {
+//!     log_log(LOG_LEVEL_INFO, "plugin::resize", "resized image",
+//!             "{\"width\":640,\"height\":480}");
+//! }
+//! ```
+//!
+//! The functions are only exported from the final library or executable if this
+//! crate is linked into it. Add it as a dependency of the crate that builds it,
+//! and refer to it somewhere so it isn't left out:
+//!
+//! ```
+//! use log_ffi as _;
+//! ```
+//!
+//! The crate that builds the final artifact must also be the one that installs
+//! the logger. Each Rust `cdylib` has its own copy of `log`'s global logger, so a
+//! plugin built as a separate `cdylib` that depends on this crate logs through
+//! its own logger, not the host's.
+//!
+//! # Levels
+//!
+//! Levels are passed as integers with the same values as [`log::Level`]:
+//!
+//! | Level   | Value |
+//! | ------- | ----- |
+//! | `Error` | 1     |
+//! | `Warn`  | 2     |
+//! | `Info`  | 3     |
+//! | `Debug` | 4     |
+//! | `Trace` | 5     |
+//!
+//! # Key-values
+//!
+//! Key-values are passed to [`log_log`] as a JSON object, such as
+//! `{"width":640,"height":480}`. Each member of the object becomes a key-value
+//! of the record.
+//!
+//! [`log::set_logger`]: https://docs.rs/log/*/log/fn.set_logger.html
+//! [`log::Level`]: https://docs.rs/log/*/log/enum.Level.html
+
+#![warn(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+
+use log::{Level, Metadata, Record};
+
+/// The record was logged, or filtered out by the maximum level or the logger.
+pub const LOG_OK: c_int = 0;
+/// The level was out of range, or the target or message was null.
+pub const LOG_ERR_INVALID_ARGUMENT: c_int = -1;
+/// The key-values weren't a valid JSON object.
+///
+/// The record is still logged, without any key-values.
+pub const LOG_ERR_INVALID_KV: c_int = -2;
+/// The logger panicked.
+pub const LOG_ERR_PANIC: c_int = -3;
+
+/// Log a message.
+///
+/// `target` and `msg` must be null-terminated strings. `kv_json` must be a
+/// null-terminated JSON object, or null if the record has no key-values. Strings
+/// that aren't valid UTF-8 are converted lossily.
+///
+/// Returns [`LOG_OK`], or one of the `LOG_ERR_*` constants if the record couldn't
+/// be logged.
+///
+/// # Safety
+///
+/// `target`, `msg`, and `kv_json` must each be null or point to a null-terminated
+/// string that's valid for reads until this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn log_log(
+    level: c_int,
+    target: *const c_char,
+    msg: *const c_char,
+    kv_json: *const c_char,
+) -> c_int {
+    let (level, target, msg) = match (to_level(level), to_str(target), to_str(msg)) {
+        (Some(level), Some(target), Some(msg)) => (level, target, msg),
+        _ => return LOG_ERR_INVALID_ARGUMENT,
+    };
+
+    if level > log::max_level() {
+        return LOG_OK;
+    }
+
+    // Invalid key-values aren't a reason to lose the message
+    let (kvs, result) = match to_str(kv_json).map(|kv_json| serde_json::from_str(&kv_json)) {
+        None => (serde_json::Map::new(), LOG_OK),
+        Some(Ok(kvs)) => (kvs, LOG_OK),
+        Some(Err(_)) => (serde_json::Map::new(), LOG_ERR_INVALID_KV),
+    };
+
+    catch_unwind(|| {
+        log::log_record(
+            &Record::builder()
+                .args(format_args!("{}", msg))
+                .level(level)
+                .target(&target)
+                .key_values(&kvs)
+                .build(),
+        );
+
+        result
+    })
+}
+
+/// Whether a record at `level` for `target` would be logged.
+///
+/// Returns 1 if it would, and 0 if it wouldn't or the arguments are invalid.
+///
+/// # Safety
+///
+/// `target` must be null or point to a null-terminated string that's valid for
+/// reads until this function returns.
+#[no_mangle]
+pub unsafe extern "C" fn log_enabled(level: c_int, target: *const c_char) -> c_int {
+    let (level, target) = match (to_level(level), to_str(target)) {
+        (Some(level), Some(target)) => (level, target),
+        _ => return 0,
+    };
+
+    if level > log::max_level() {
+        return 0;
+    }
+
+    catch_unwind(|| {
+        log::logger().enabled(&Metadata::builder().level(level).target(&target).build()) as c_int
+    })
+    .max(0)
+}
+
+fn to_level(level: c_int) -> Option<Level> {
+    Level::try_from(usize::try_from(level).ok()?).ok()
+}
+
+unsafe fn to_str<'a>(s: *const c_char) -> Option<Cow<'a, str>> {
+    if s.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(s).to_string_lossy())
+    }
+}
+
+// Panics can't unwind into C
+fn catch_unwind(f: impl FnOnce() -> c_int) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(LOG_ERR_PANIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ffi::CString;
+    use std::ptr;
+    use std::sync::Mutex;
+
+    use log::kv::Key;
+    use log::{LevelFilter, Log};
+
+    struct Logger(Mutex<Vec<String>>);
+
+    impl Log for Logger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() != "quiet"
+        }
+
+        fn log(&self, record: &Record) {
+            if record.target() == "panic" {
+                panic!("logger panicked");
+            }
+
+            let width = record
+                .key_values()
+                .get(Key::from_str("width"))
+                .map(|width| width.to_string());
+
+            self.0.lock().unwrap().push(format!(
+                "{} {} {} {:?}",
+                record.level(),
+                record.target(),
+                record.args(),
+                width
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: Logger = Logger(Mutex::new(Vec::new()));
+
+    // There's only one global logger, so everything is tested together
+    #[test]
+    fn ffi() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+
+        let target = CString::new("plugin").unwrap();
+        let msg = CString::new("resized").unwrap();
+        let kvs = CString::new(r#"{"width":640}"#).unwrap();
+        let invalid_kvs = CString::new("[640]").unwrap();
+
+        unsafe {
+            assert_eq!(1, log_enabled(3, target.as_ptr()));
+            assert_eq!(0, log_enabled(5, target.as_ptr()));
+            assert_eq!(0, log_enabled(0, target.as_ptr()));
+            assert_eq!(0, log_enabled(3, CString::new("quiet").unwrap().as_ptr()));
+
+            assert_eq!(
+                LOG_OK,
+                log_log(3, target.as_ptr(), msg.as_ptr(), kvs.as_ptr())
+            );
+            assert_eq!(
+                LOG_OK,
+                log_log(1, target.as_ptr(), msg.as_ptr(), ptr::null())
+            );
+            assert_eq!(
+                LOG_ERR_INVALID_KV,
+                log_log(2, target.as_ptr(), msg.as_ptr(), invalid_kvs.as_ptr())
+            );
+            // Filtered out by the maximum level
+            assert_eq!(
+                LOG_OK,
+                log_log(5, target.as_ptr(), msg.as_ptr(), ptr::null())
+            );
+
+            assert_eq!(
+                LOG_ERR_INVALID_ARGUMENT,
+                log_log(6, target.as_ptr(), msg.as_ptr(), ptr::null())
+            );
+            assert_eq!(
+                LOG_ERR_INVALID_ARGUMENT,
+                log_log(3, ptr::null(), msg.as_ptr(), ptr::null())
+            );
+            assert_eq!(
+                LOG_ERR_PANIC,
+                log_log(
+                    3,
+                    CString::new("panic").unwrap().as_ptr(),
+                    msg.as_ptr(),
+                    ptr::null()
+                )
+            );
+        }
+
+        assert_eq!(
+            vec![
+                "INFO plugin resized Some(\"640\")",
+                "ERROR plugin resized None",
+                "WARN plugin resized None",
+            ],
+            *LOGGER.0.lock().unwrap()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Formatting records as JSON.
+//!
+//! A record is formatted as a single JSON object with `level`, `target`, and `message`
+//! fields, followed by each of its key-values in the order they're visited.
+//!
+//! Values are formatted according to their type:
+//!
+//! - `null` values as `null`.
+//! - Booleans as `true` or `false`.
+//! - Integers as numbers.
+//! - Finite floating point numbers as numbers, and other floating point numbers as strings.
+//! - Strings and characters as strings.
+//! - Anything else as a string using its `Display` implementation.
+
+use std::fmt::{self, Write};
+
+use log::kv::{self, Key, Value, VisitSource, VisitValue};
+use log::Record;
+
+/// Format a record as a JSON object.
+pub fn to_string(record: &Record) -> String {
+    let mut buf = String::new();
+    write(&mut buf, record).expect("a formatting trait implementation returned an error");
+    buf
+}
+
+/// Write a record as a JSON object.
+pub fn write(w: &mut impl Write, record: &Record) -> fmt::Result {
+    w.write_str("{\"level\":")?;
+    write_str(w, record.level().as_str())?;
+
+    w.write_str(",\"target\":")?;
+    write_str(w, record.target())?;
+
+    w.write_str(",\"message\":")?;
+    write_display(w, record.args())?;
+
+    record
+        .key_values()
+        .visit(&mut Pairs(&mut *w))
+        .map_err(|_| fmt::Error)?;
+
+    w.write_char('}')
+}
+
+/// Write a single value as JSON.
+pub fn write_value(w: &mut impl Write, value: &Value) -> fmt::Result {
+    value.visit(Values(w)).map_err(|_| fmt::Error)
+}
+
+/// Write a string as a quoted and escaped JSON string.
+pub fn write_str(w: &mut impl Write, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    Escape(&mut *w).write_str(s)?;
+    w.write_char('"')
+}
+
+/// Write a `Display` implementation as a quoted and escaped JSON string.
+pub fn write_display(w: &mut impl Write, v: impl fmt::Display) -> fmt::Result {
+    w.write_char('"')?;
+    write!(Escape(&mut *w), "{v}")?;
+    w.write_char('"')
+}
+
+struct Escape<W>(W);
+
+impl<W: Write> Write for Escape<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{08}' => "\\b",
+                '\u{0C}' => "\\f",
+                c if (c as u32) < 0x20 => "",
+                _ => continue,
+            };
+
+            self.0.write_str(&s[start..i])?;
+            if escaped.is_empty() {
+                write!(self.0, "\\u{:04x}", c as u32)?;
+            } else {
+                self.0.write_str(escaped)?;
+            }
+            start = i + c.len_utf8();
+        }
+
+        self.0.write_str(&s[start..])
+    }
+}
+
+struct Pairs<W>(W);
+
+impl<'kvs, W: Write> VisitSource<'kvs> for Pairs<W> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.write_char(',')?;
+        write_str(&mut self.0, key.as_str())?;
+        self.0.write_char(':')?;
+        value.visit(Values(&mut self.0))
+    }
+}
+
+struct Values<W>(W);
+
+impl<'v, W: Write> VisitValue<'v> for Values<W> {
+    fn visit_any(&mut self, value: Value) -> Result<(), kv::Error> {
+        Ok(write_display(&mut self.0, value)?)
+    }
+
+    fn visit_null(&mut self) -> Result<(), kv::Error> {
+        Ok(self.0.write_str("null")?)
+    }
+
+    fn visit_u64(&mut self, value: u64) -> Result<(), kv::Error> {
+        Ok(write!(self.0, "{value}")?)
+    }
+
+    fn visit_i64(&mut self, value: i64) -> Result<(), kv::Error> {
+        Ok(write!(self.0, "{value}")?)
+    }
+
+    fn visit_u128(&mut self, value: u128) -> Result<(), kv::Error> {
+        Ok(write!(self.0, "{value}")?)
+    }
+
+    fn visit_i128(&mut self, value: i128) -> Result<(), kv::Error> {
+        Ok(write!(self.0, "{value}")?)
+    }
+
+    fn visit_f64(&mut self, value: f64) -> Result<(), kv::Error> {
+        if value.is_finite() {
+            Ok(write!(self.0, "{value}")?)
+        } else {
+            Ok(write_display(&mut self.0, value)?)
+        }
+    }
+
+    fn visit_bool(&mut self, value: bool) -> Result<(), kv::Error> {
+        Ok(write!(self.0, "{value}")?)
+    }
+
+    fn visit_str(&mut self, value: &str) -> Result<(), kv::Error> {
+        Ok(write_str(&mut self.0, value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use log::Level;
+
+    #[test]
+    fn escape() {
+        let mut buf = String::new();
+        write_str(&mut buf, "a \"quoted\"\\path\nnew line\u{01}⛰").unwrap();
+
+        assert_eq!(r#""a \"quoted\"\\path\nnew line\u0001⛰""#, buf);
+    }
+
+    #[test]
+    fn record() {
+        let kvs: &[(&str, Value)] = &[
+            ("null", Value::null()),
+            ("bool", Value::from(true)),
+            ("int", Value::from(-1i32)),
+            ("uint", Value::from(1u8)),
+            ("big", Value::from(u128::MAX)),
+            ("float", Value::from(1.5f64)),
+            ("nan", Value::from(f64::NAN)),
+            ("char", Value::from('c')),
+            ("str", Value::from("a \"str\"")),
+            ("debug", Value::from_debug(&Some(1))),
+        ];
+
+        let format = |args| {
+            to_string(
+                &Record::builder()
+                    .args(args)
+                    .level(Level::Warn)
+                    .target("my \"target\"")
+                    .key_values(&kvs)
+                    .build(),
+            )
+        };
+
+        assert_eq!(
+            concat!(
+                r#"{"level":"WARN","target":"my \"target\"","message":"a formatted message","#,
+                r#""null":null,"bool":true,"int":-1,"uint":1,"#,
+                r#""big":340282366920938463463374607431768211455,"float":1.5,"nan":"NaN","#,
+                r#""char":"c","str":"a \"str\"","debug":"Some(1)"}"#,
+            ),
+            format(format_args!("a {} message", "formatted"))
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Utilities for formatting log records.
+//!
+//! This crate renders a [`Record`] to a string as either [JSON](json) or [logfmt](logfmt)
+//! using only the public `Record` and `kv` APIs of `log`. It also exposes the escapers
+//! used for keys and values so sinks that produce these formats themselves don't need
+//! to reimplement them.
+//!
+//! # Examples
+//!
+//! ```
+//! use log::{Level, Record};
+//!
+//! let kvs = [("user", "ferris"), ("outcome", "ok")];
+//! let record = Record::builder()
+//!     .args(format_args!("logged in"))
+//!     .level(Level::Info)
+//!     .target("auth")
+//!     .key_values(&kvs)
+//!     .build();
+//!
+//! assert_eq!(
+//!     r#"{"level":"INFO","target":"auth","message":"logged in","user":"ferris","outcome":"ok"}"#,
+//!     log_fmt::json::to_string(&record),
+//! );
+//!
+//! assert_eq!(
+//!     r#"level=INFO target=auth msg="logged in" user=ferris outcome=ok"#,
+//!     log_fmt::logfmt::to_string(&record),
+//! );
+//! ```
+//!
+//! [`Record`]: https://docs.rs/log/*/log/struct.Record.html
+
+#![warn(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+pub mod json;
+pub mod logfmt;
@@ -0,0 +1,168 @@
+//! Formatting records as logfmt.
+//!
+//! A record is formatted as a single line of space-separated `key=value` pairs,
+//! starting with `level`, `target`, and `msg`, followed by each of its key-values
+//! in the order they're visited.
+//!
+//! Values are quoted if they're empty or contain whitespace, `=`, `"`, or control
+//! characters. Within quotes, `"` and `\` are escaped with a backslash and control
+//! characters are escaped the same way as in Rust strings. Keys are never quoted;
+//! any characters in them that would need quoting are replaced with `_`.
+
+use std::fmt::{self, Write};
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::Record;
+
+/// Format a record as a logfmt line.
+pub fn to_string(record: &Record) -> String {
+    let mut buf = String::new();
+    write(&mut buf, record).expect("a formatting trait implementation returned an error");
+    buf
+}
+
+/// Write a record as a logfmt line.
+///
+/// The line isn't terminated with a newline.
+pub fn write(w: &mut impl Write, record: &Record) -> fmt::Result {
+    w.write_str("level=")?;
+    write_str(w, record.level().as_str())?;
+
+    w.write_str(" target=")?;
+    write_str(w, record.target())?;
+
+    w.write_str(" msg=")?;
+    write_display(w, record.args())?;
+
+    record
+        .key_values()
+        .visit(&mut Pairs(&mut *w))
+        .map_err(|_| fmt::Error)
+}
+
+/// Write a key, replacing any characters that aren't valid in a logfmt key with `_`.
+pub fn write_key(w: &mut impl Write, key: &str) -> fmt::Result {
+    if key.is_empty() {
+        return w.write_char('_');
+    }
+
+    for c in key.chars() {
+        w.write_char(if needs_quotes(c) { '_' } else { c })?;
+    }
+
+    Ok(())
+}
+
+/// Write a string, quoting and escaping it if needed.
+pub fn write_str(w: &mut impl Write, s: &str) -> fmt::Result {
+    if !s.is_empty() && !s.chars().any(needs_quotes) {
+        return w.write_str(s);
+    }
+
+    w.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            c if c.is_control() => write!(w, "{}", c.escape_default())?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// Write a `Display` implementation, quoting and escaping it if needed.
+pub fn write_display(w: &mut impl Write, v: impl fmt::Display) -> fmt::Result {
+    // The whole value needs to be seen before we know whether it needs quoting
+    write_str(w, &v.to_string())
+}
+
+fn needs_quotes(c: char) -> bool {
+    c.is_whitespace() || c.is_control() || c == '=' || c == '"'
+}
+
+struct Pairs<W>(W);
+
+impl<'kvs, W: Write> VisitSource<'kvs> for Pairs<W> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.write_char(' ')?;
+        write_key(&mut self.0, key.as_str())?;
+        self.0.write_char('=')?;
+
+        match value.to_borrowed_str() {
+            Some(s) => write_str(&mut self.0, s)?,
+            None => write_display(&mut self.0, value)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use log::Level;
+
+    #[test]
+    fn quoting() {
+        let cases = [
+            ("plain", "plain"),
+            ("", r#""""#),
+            ("two words", r#""two words""#),
+            ("a=b", r#""a=b""#),
+            ("a \"quote\"", r#""a \"quote\"""#),
+            ("back\\slash", "back\\slash"),
+            ("new\nline", r#""new\nline""#),
+        ];
+
+        for (input, expected) in cases {
+            let mut buf = String::new();
+            write_str(&mut buf, input).unwrap();
+
+            assert_eq!(expected, buf, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn keys() {
+        let mut buf = String::new();
+        write_key(&mut buf, "a key=\"x\"").unwrap();
+        assert_eq!("a_key__x_", buf);
+
+        let mut buf = String::new();
+        write_key(&mut buf, "").unwrap();
+        assert_eq!("_", buf);
+    }
+
+    #[test]
+    fn record() {
+        let kvs: &[(&str, Value)] = &[
+            ("null", Value::null()),
+            ("bool", Value::from(true)),
+            ("int", Value::from(-1i32)),
+            ("float", Value::from(1.5f64)),
+            ("str", Value::from("two words")),
+            ("debug", Value::from_debug(&Some("x"))),
+        ];
+
+        let format = |args| {
+            to_string(
+                &Record::builder()
+                    .args(args)
+                    .level(Level::Debug)
+                    .target("my_target")
+                    .key_values(&kvs)
+                    .build(),
+            )
+        };
+
+        assert_eq!(
+            concat!(
+                r#"level=DEBUG target=my_target msg="a formatted message" "#,
+                r#"null=None bool=true int=-1 float=1.5 str="two words" debug="Some(\"x\")""#,
+            ),
+            format(format_args!("a {} message", "formatted"))
+        );
+    }
+}
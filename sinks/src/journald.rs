@@ -0,0 +1,201 @@
+//! Logging to the systemd journal.
+//!
+//! Records are sent to journald using its [native protocol], as a set of fields:
+//!
+//! - `PRIORITY`: the record's level as a syslog severity. See [`syslog::severity`].
+//! - `MESSAGE`: the record's message.
+//! - `TARGET`: the record's target.
+//! - `CODE_FILE` and `CODE_LINE`: the record's source location, if it has one.
+//!
+//! Each key-value becomes a field of its own. Journal field names may only contain
+//! uppercase ASCII letters, digits, and `_`, and can't start with a digit or `_`, so
+//! keys are converted using [`write_field_name`]. Values are formatted using their
+//! `Display` implementation.
+//!
+//! [native protocol]: https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
+//! [`syslog::severity`]: crate::syslog::severity
+
+use std::fmt::{self, Write};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Log, Metadata, Record};
+
+/// The path of journald's native protocol socket.
+pub const SOCKET_PATH: &str = "/run/systemd/journal/socket";
+
+// The maximum length of a journal field name
+const MAX_FIELD_NAME_LEN: usize = 64;
+
+/// A logger that sends records to journald.
+///
+/// Records that are too large to fit in a single datagram are discarded;
+/// passing them through a memfd isn't supported.
+///
+/// # Examples
+///
+/// ```no_run
+/// use log_sinks::journald::Journald;
+///
+/// let logger = Journald::connect()?;
+/// log::set_boxed_logger(Box::new(logger)).unwrap();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Journald {
+    socket: UnixDatagram,
+}
+
+impl Journald {
+    /// Connect to journald at [`SOCKET_PATH`].
+    pub fn connect() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(SOCKET_PATH)?;
+
+        Ok(Journald { socket })
+    }
+}
+
+impl Log for Journald {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        // There's nowhere to report a failed send; the logging macros
+        // can't return errors
+        let _ = self.socket.send(&to_vec(record));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Serialize a record as a journald native protocol message.
+pub fn to_vec(record: &Record) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write(&mut buf, record);
+    buf
+}
+
+/// Serialize a record as a journald native protocol message.
+pub fn write(buf: &mut Vec<u8>, record: &Record) {
+    write_field(
+        buf,
+        "PRIORITY",
+        &crate::syslog::severity(record.level()).to_string(),
+    );
+    write_field(buf, "MESSAGE", &record.args().to_string());
+    write_field(buf, "TARGET", record.target());
+
+    if let Some(file) = record.file() {
+        write_field(buf, "CODE_FILE", file);
+    }
+    if let Some(line) = record.line() {
+        write_field(buf, "CODE_LINE", &line.to_string());
+    }
+
+    let _ = record.key_values().visit(&mut Fields(buf));
+}
+
+/// Write a key as a journal field name.
+///
+/// Letters are uppercased, and any other characters that aren't valid in a field
+/// name are replaced with `_`. Names that would start with a digit or `_` are
+/// prefixed with `KV`, and names are truncated to 64 characters.
+pub fn write_field_name(w: &mut impl Write, key: &str) -> fmt::Result {
+    let mut len = 0;
+
+    if !key.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        w.write_str("KV")?;
+        len += 2;
+    }
+
+    for c in key.chars().take(MAX_FIELD_NAME_LEN - len) {
+        w.write_char(if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        })?;
+    }
+
+    Ok(())
+}
+
+fn write_field(buf: &mut Vec<u8>, name: &str, value: &str) {
+    buf.extend_from_slice(name.as_bytes());
+
+    // Values containing newlines are written with an explicit length instead
+    if value.contains('\n') {
+        buf.push(b'\n');
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buf.push(b'=');
+    }
+
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(b'\n');
+}
+
+struct Fields<'a>(&'a mut Vec<u8>);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let mut name = String::new();
+        write_field_name(&mut name, key.as_str())?;
+
+        write_field(self.0, &name, &value.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use log::Level;
+
+    #[test]
+    fn field_name() {
+        let cases = [
+            ("user", "USER"),
+            ("user.id", "USER_ID"),
+            ("_user", "KV_USER"),
+            ("1st", "KV1ST"),
+            ("", "KV"),
+        ];
+
+        for (key, expected) in cases {
+            let mut name = String::new();
+            write_field_name(&mut name, key).unwrap();
+
+            assert_eq!(expected, name, "{key}");
+        }
+
+        let mut name = String::new();
+        write_field_name(&mut name, &"a".repeat(100)).unwrap();
+        assert_eq!(MAX_FIELD_NAME_LEN, name.len());
+    }
+
+    #[test]
+    fn record() {
+        let kvs = [("user", "ferris"), ("note", "two\nlines")];
+
+        let buf = to_vec(
+            &Record::builder()
+                .args(format_args!("logged in"))
+                .level(Level::Warn)
+                .target("auth")
+                .file(Some("src/auth.rs"))
+                .line(Some(7))
+                .key_values(&kvs)
+                .build(),
+        );
+
+        let mut expected = b"PRIORITY=4\nMESSAGE=logged in\nTARGET=auth\nCODE_FILE=src/auth.rs\nCODE_LINE=7\nUSER=ferris\nNOTE\n".to_vec();
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        expected.extend_from_slice(b"two\nlines\n");
+
+        assert_eq!(expected, buf);
+    }
+}
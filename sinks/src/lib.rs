@@ -0,0 +1,26 @@
+//! Reference loggers for platform-native sinks.
+//!
+//! Each module defines how a [`Record`] and its key-values map onto a platform's
+//! own log format, and provides a minimal logger that writes records there:
+//!
+//! - [journald](journald): the systemd journal's native protocol, with key-values as
+//!   journal fields. Only available on Unix.
+//! - [syslog](syslog): RFC 5424 messages, with key-values as structured data. The
+//!   logger is only available on Unix, but the format can be written anywhere.
+//! - [windows](windows): the Windows debugger output, with key-values as logfmt pairs.
+//!   Only available on Windows.
+//!
+//! These loggers don't filter records; wrap them or check [`Metadata`] yourself if
+//! you need to.
+//!
+//! [`Record`]: https://docs.rs/log/*/log/struct.Record.html
+//! [`Metadata`]: https://docs.rs/log/*/log/struct.Metadata.html
+
+#![warn(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+#[cfg(unix)]
+pub mod journald;
+pub mod syslog;
+#[cfg(windows)]
+pub mod windows;
@@ -0,0 +1,322 @@
+//! Logging to syslog.
+//!
+//! Records are formatted as [RFC 5424] messages:
+//!
+//! ```text
+//! <12>1 - - my_app 4321 - [log@32473 target="auth" user="ferris"] logged in
+//! ```
+//!
+//! The priority combines the logger's [`Facility`] with the record's level as a
+//! [severity](severity). The timestamp and hostname are left for the syslog daemon
+//! to fill in, and the message ID isn't used.
+//!
+//! The record's target and each of its key-values are written as parameters of a
+//! single structured data element, `log@32473`. Parameter names can only contain
+//! printable ASCII characters other than `=`, ` `, `]`, and `"`, so keys are
+//! converted using [`write_param_name`]. Values are formatted using their `Display`
+//! implementation and escaped.
+//!
+//! The message is prefixed with a byte order mark, as RFC 5424 requires for UTF-8
+//! messages.
+//!
+//! [RFC 5424]: https://datatracker.ietf.org/doc/html/rfc5424
+
+use std::fmt::{self, Write};
+#[cfg(unix)]
+use std::{io, os::unix::net::UnixDatagram};
+
+use log::kv::{self, Key, Value, VisitSource};
+use log::{Level, Record};
+#[cfg(unix)]
+use log::{Log, Metadata};
+
+/// The path of the local syslog daemon's socket.
+#[cfg(unix)]
+pub const SOCKET_PATH: &str = "/dev/log";
+
+/// The ID of the structured data element key-values are written to.
+///
+/// 32473 is the private enterprise number reserved for documentation by RFC 5612.
+pub const SD_ID: &str = "log@32473";
+
+// Field lengths from RFC 5424
+const MAX_APP_NAME_LEN: usize = 48;
+const MAX_PARAM_NAME_LEN: usize = 32;
+
+// The byte order mark that starts a UTF-8 message
+const BOM: char = '\u{FEFF}';
+
+/// The syslog facility to log to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Facility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// The syslog severity of a level.
+///
+/// | Level   | Severity          |
+/// | ------- | ----------------- |
+/// | `Error` | 3 (error)         |
+/// | `Warn`  | 4 (warning)       |
+/// | `Info`  | 6 (informational) |
+/// | `Debug` | 7 (debug)         |
+/// | `Trace` | 7 (debug)         |
+pub fn severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Options for formatting records as syslog messages.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, Record};
+/// use log_sinks::syslog::{Facility, Format};
+///
+/// let format = Format::new("my_app").facility(Facility::Local0);
+///
+/// let record = Record::builder()
+///     .args(format_args!("logged in"))
+///     .level(Level::Info)
+///     .target("auth")
+///     .build();
+///
+/// let message = format.to_string(&record);
+/// assert!(message.starts_with("<134>1 - - my_app "));
+/// assert!(message.ends_with("[log@32473 target=\"auth\"] \u{FEFF}logged in"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Format {
+    facility: Facility,
+    app_name: String,
+    proc_id: u32,
+}
+
+impl Format {
+    /// Create a format for the given application name, using the [`Facility::User`]
+    /// facility.
+    ///
+    /// The application name is truncated to 48 characters, and any characters
+    /// that aren't printable ASCII are replaced with `_`.
+    pub fn new(app_name: &str) -> Self {
+        let app_name = app_name
+            .chars()
+            .take(MAX_APP_NAME_LEN)
+            .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+            .collect::<String>();
+
+        Format {
+            facility: Facility::User,
+            app_name: if app_name.is_empty() {
+                "-".into()
+            } else {
+                app_name
+            },
+            proc_id: std::process::id(),
+        }
+    }
+
+    /// The facility to log to.
+    pub fn facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Format a record as a syslog message.
+    pub fn to_string(&self, record: &Record) -> String {
+        let mut buf = String::new();
+        self.write(&mut buf, record)
+            .expect("a formatting trait implementation returned an error");
+        buf
+    }
+
+    /// Write a record as a syslog message.
+    pub fn write(&self, w: &mut impl Write, record: &Record) -> fmt::Result {
+        let pri = self.facility as u8 * 8 + severity(record.level());
+
+        write!(
+            w,
+            "<{}>1 - - {} {} - [{}",
+            pri, self.app_name, self.proc_id, SD_ID
+        )?;
+
+        w.write_str(" target=")?;
+        write_param_value(w, record.target())?;
+
+        record
+            .key_values()
+            .visit(&mut Params(&mut *w))
+            .map_err(|_| fmt::Error)?;
+
+        write!(w, "] {}{}", BOM, record.args())
+    }
+}
+
+/// A logger that sends records to the local syslog daemon.
+///
+/// The daemon needs to accept RFC 5424 messages on its socket. Both rsyslog
+/// and syslog-ng do by default.
+///
+/// # Examples
+///
+/// ```no_run
+/// use log_sinks::syslog::{Format, Syslog};
+///
+/// let logger = Syslog::connect(Format::new("my_app"))?;
+/// log::set_boxed_logger(Box::new(logger)).unwrap();
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct Syslog {
+    format: Format,
+    socket: UnixDatagram,
+}
+
+#[cfg(unix)]
+impl Syslog {
+    /// Connect to the local syslog daemon at [`SOCKET_PATH`].
+    pub fn connect(format: Format) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(SOCKET_PATH)?;
+
+        Ok(Syslog { format, socket })
+    }
+}
+
+#[cfg(unix)]
+impl Log for Syslog {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        // There's nowhere to report a failed send; the logging macros
+        // can't return errors
+        let _ = self.socket.send(self.format.to_string(record).as_bytes());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Write a key as a structured data parameter name.
+///
+/// Characters that aren't valid in a parameter name are replaced with `_`,
+/// and names are truncated to 32 characters.
+pub fn write_param_name(w: &mut impl Write, key: &str) -> fmt::Result {
+    if key.is_empty() {
+        return w.write_char('_');
+    }
+
+    for c in key.chars().take(MAX_PARAM_NAME_LEN) {
+        w.write_char(match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Write a structured data parameter value, quoting it and escaping `"`, `\`, and `]`.
+pub fn write_param_value(w: &mut impl Write, value: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in value.chars() {
+        if let '"' | '\\' | ']' = c {
+            w.write_char('\\')?;
+        }
+        w.write_char(c)?;
+    }
+    w.write_char('"')
+}
+
+struct Params<W>(W);
+
+impl<'kvs, W: Write> VisitSource<'kvs> for Params<W> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.write_char(' ')?;
+        write_param_name(&mut self.0, key.as_str())?;
+        self.0.write_char('=')?;
+        write_param_value(&mut self.0, &value.to_string())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_name() {
+        let cases = [
+            ("user", "user"),
+            ("user id", "user_id"),
+            ("a=b", "a_b"),
+            ("", "_"),
+        ];
+
+        for (key, expected) in cases {
+            let mut name = String::new();
+            write_param_name(&mut name, key).unwrap();
+
+            assert_eq!(expected, name, "{key}");
+        }
+    }
+
+    #[test]
+    fn param_value() {
+        let mut value = String::new();
+        write_param_value(&mut value, r#"a "quoted" [value]\"#).unwrap();
+
+        assert_eq!(r#""a \"quoted\" [value\]\\""#, value);
+    }
+
+    #[test]
+    fn record() {
+        let kvs = [("user", "ferris"), ("path", "/a]b")];
+
+        let mut format = Format::new("my app");
+        format.proc_id = 4321;
+
+        let message = format.to_string(
+            &Record::builder()
+                .args(format_args!("logged in"))
+                .level(Level::Warn)
+                .target("auth")
+                .key_values(&kvs)
+                .build(),
+        );
+
+        assert_eq!(
+            "<12>1 - - my_app 4321 - [log@32473 target=\"auth\" user=\"ferris\" path=\"/a\\]b\"] \u{FEFF}logged in",
+            message
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Logging to the Windows debugger output.
+//!
+//! Records are formatted as logfmt lines using [`log_fmt::logfmt`], and passed to
+//! [`OutputDebugStringW`]. They can be seen in an attached debugger, or with tools
+//! like DebugView.
+//!
+//! [`OutputDebugStringW`]: https://learn.microsoft.com/en-us/windows/win32/api/debugapi/nf-debugapi-outputdebugstringw
+
+use std::iter;
+
+use log::{Log, Metadata, Record};
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn OutputDebugStringW(output: *const u16);
+}
+
+/// A logger that writes records to the debugger output.
+///
+/// # Examples
+///
+/// ```no_run
+/// use log_sinks::windows::DebugOutput;
+///
+/// log::set_boxed_logger(Box::new(DebugOutput)).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DebugOutput;
+
+impl Log for DebugOutput {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut line = log_fmt::logfmt::to_string(record);
+        line.push('\n');
+
+        let wide: Vec<u16> = line.encode_utf16().chain(iter::once(0)).collect();
+
+        // SAFETY: `wide` is a nul-terminated UTF-16 string that outlives the call
+        unsafe { OutputDebugStringW(wide.as_ptr()) }
+    }
+
+    fn flush(&self) {}
+}
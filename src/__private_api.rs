@@ -1,10 +1,15 @@
-//! WARNING: this is not part of the crate's public API and is subject to change at any time
+//! WARNING: this is not part of the crate's public API and is subject to change at any time.
+//! Crates defining their own logging macros should use `crate::macro_support` instead.
 
-use self::sealed::KVs;
-use crate::{Level, Metadata, Record};
+use self::sealed::{KVs, Target};
+use crate::{
+    AtomicUsize, Level, Metadata, MetadataExtras, Ordering, Record, RecordKind, SourceLocation,
+    TargetHandle,
+};
 use std::fmt::Arguments;
 use std::panic::Location;
-pub use std::{format_args, module_path, stringify};
+pub use std::result::Result::Err;
+pub use std::{column, file, format_args, line, module_path, stringify};
 
 #[cfg(not(feature = "kv"))]
 pub type Value<'a> = &'a str;
@@ -14,6 +19,12 @@ mod sealed {
     pub trait KVs<'a> {
         fn into_kvs(self) -> Option<&'a [(&'a str, super::Value<'a>)]>;
     }
+
+    /// Types for the `target` argument.
+    pub trait Target {
+        fn name(&self) -> &str;
+        fn handle(&self) -> Option<crate::TargetHandle>;
+    }
 }
 
 // Types for the `kv` argument.
@@ -32,48 +43,506 @@ impl<'a> KVs<'a> for () {
     }
 }
 
+// Types for the `target` argument.
+
+impl<T: AsRef<str> + ?Sized> Target for &T {
+    #[inline]
+    fn name(&self) -> &str {
+        (**self).as_ref()
+    }
+
+    #[inline]
+    fn handle(&self) -> Option<TargetHandle> {
+        None
+    }
+}
+
+impl Target for TargetHandle {
+    #[inline]
+    fn name(&self) -> &str {
+        TargetHandle::name(self)
+    }
+
+    #[inline]
+    fn handle(&self) -> Option<TargetHandle> {
+        Some(*self)
+    }
+}
+
+// The location passed by the macros when they don't capture one.
+#[cfg(feature = "no_location")]
+pub static NO_LOCATION: SourceLocation<'static> = SourceLocation::new("", "", 0);
+
+// The kind, verbosity, de-duplication key, and message id of a record, as
+// given to the macros.
+#[derive(Debug)]
+pub struct Kind<'a> {
+    kind: RecordKind,
+    verbosity: u8,
+    dedup_key: Option<&'a str>,
+    msg_id: Option<&'static str>,
+}
+
+// A de-duplication key given to the macros with `dedup:`.
+#[derive(Debug)]
+pub struct DedupKey<'a>(pub &'a str);
+
+// A message id given to the macros with `id:`.
+#[derive(Debug)]
+pub struct MsgId(pub &'static str);
+
+impl<'a> From<RecordKind> for Kind<'a> {
+    #[inline]
+    fn from(kind: RecordKind) -> Self {
+        Kind {
+            kind,
+            verbosity: 0,
+            dedup_key: None,
+            msg_id: None,
+        }
+    }
+}
+
+impl<'a> From<(RecordKind, u8)> for Kind<'a> {
+    #[inline]
+    fn from((kind, verbosity): (RecordKind, u8)) -> Self {
+        Kind {
+            kind,
+            verbosity,
+            dedup_key: None,
+            msg_id: None,
+        }
+    }
+}
+
+impl<'a> From<(RecordKind, DedupKey<'a>)> for Kind<'a> {
+    #[inline]
+    fn from((kind, DedupKey(key)): (RecordKind, DedupKey<'a>)) -> Self {
+        Kind {
+            kind,
+            verbosity: 0,
+            dedup_key: Some(key),
+            msg_id: None,
+        }
+    }
+}
+
+impl<'a> From<(RecordKind, MsgId)> for Kind<'a> {
+    #[inline]
+    fn from((kind, MsgId(id)): (RecordKind, MsgId)) -> Self {
+        Kind {
+            kind,
+            verbosity: 0,
+            dedup_key: None,
+            msg_id: Some(id),
+        }
+    }
+}
+
 // Log implementation.
+//
+// The `outline_callsites` feature stops these functions from being inlined into
+// the macros' callsites. With LTO, all of `log_impl` can otherwise end up
+// copied into each one.
 
+#[cfg_attr(feature = "outline_callsites", inline(never))]
 fn log_impl(
     args: Arguments,
     level: Level,
-    &(target, module_path, loc): &(&str, &'static str, &'static Location),
+    (target, target_handle): (&str, Option<TargetHandle>),
+    loc: &SourceLocation<'static>,
     kvs: Option<&[(&str, Value)]>,
+    (kind, sample_rate): (Kind, Option<f64>),
+    audit: Option<&str>,
 ) {
-    #[cfg(not(feature = "kv"))]
-    if kvs.is_some() {
-        panic!("key-value support is experimental and must be enabled using the `kv` feature")
+    // Captured records never reach the logger, so they can still be captured
+    // after it's shut down
+    #[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+    if crate::shutdown::is_shutdown() && !crate::capture::is_capturing() {
+        crate::shutdown::discard_late_record();
+        return;
+    }
+
+    // The current thread's maximum level may be lower than the global one the
+    // macros checked. Audit records aren't subject to either
+    #[cfg(feature = "std")]
+    if audit.is_none() && crate::thread_level::allows(level) == Some(false) {
+        return;
     }
 
-    let mut builder = Record::builder();
+    if crate::diagnostics::is_enabled() {
+        crate::diagnostics::submit();
+    }
+
+    #[cfg(feature = "std")]
+    let capturing = crate::capture::is_capturing();
+    #[cfg(not(feature = "std"))]
+    let capturing = false;
+
+    // Once the logger is installed, this is the only check for the pre-init policy
+    let buffer = if crate::STATE.load(Ordering::Acquire) == crate::INITIALIZED {
+        false
+    } else {
+        match crate::pre_init::before_init(args, level, target, capturing) {
+            Some(buffer) => buffer,
+            None => return,
+        }
+    };
+    #[cfg(not(feature = "early_buffer"))]
+    let _ = buffer;
+
+    // Records that go over the limits are cut to fit before anyone sees them
+    let limited = crate::limits::is_limited();
+
+    let kvs_truncated = kvs
+        .filter(|_| limited)
+        .and_then(|kvs| crate::limits::truncate_kvs(kvs.len()).map(|len| &kvs[..len]));
+    let kvs = kvs_truncated.or(kvs);
+
+    let mut extras = MetadataExtras::new();
+    extras
+        .target_handle(target_handle)
+        .kind(kind.kind)
+        .verbosity(kind.verbosity)
+        .audit(audit)
+        .sample_rate(sample_rate)
+        .dedup_key(kind.dedup_key)
+        .msg_id(kind.msg_id);
+
+    let emit = |args: Arguments, truncated: bool| {
+        let mut builder = Record::builder();
+
+        builder
+            .args(args)
+            .level(level)
+            .target(target)
+            .extras(Some(&extras).filter(|extras| !extras.is_empty()));
+
+        #[cfg(not(feature = "no_location"))]
+        builder.source_location_static(loc);
+        #[cfg(feature = "no_location")]
+        let _ = loc;
 
-    builder
-        .args(args)
-        .level(level)
-        .target(target)
-        .module_path_static(Some(module_path))
-        .file_static(Some(loc.file()))
-        .line(Some(loc.line()));
+        #[cfg(feature = "correlation")]
+        builder.correlation_id(crate::correlation_id());
 
-    #[cfg(feature = "kv")]
-    builder.key_values(&kvs);
+        #[cfg(feature = "nesting")]
+        builder.nesting(crate::nesting());
 
-    crate::logger().log(&builder.build());
+        #[cfg(feature = "timestamps")]
+        builder.timestamp(crate::clock::now());
+
+        #[cfg(feature = "seq")]
+        builder.seq(Some(crate::next_seq()));
+
+        // Key-values are stripped by the macros when the `kv` feature isn't
+        // enabled, so there's nowhere to mark truncated records
+        #[cfg(feature = "kv")]
+        let truncated = Some((crate::kv::keys::TRUNCATED, Value::from(true))).filter(|_| truncated);
+        #[cfg(not(feature = "kv"))]
+        let _ = (kvs, truncated);
+
+        // Build info is only attached with the `global-fields` feature
+        #[cfg(feature = "global-fields")]
+        let build_info = crate::build_info();
+        #[cfg(all(feature = "kv", not(feature = "global-fields")))]
+        let build_info = None::<crate::BuildInfo>;
+
+        #[cfg(all(feature = "kv", not(feature = "backtrace")))]
+        let kvs: [&dyn crate::kv::Source; 3] = [&kvs, &build_info, &truncated];
+        #[cfg(all(feature = "kv", not(feature = "backtrace")))]
+        builder.key_values(&kvs);
+
+        // Error records carry a backtrace of their callsite. Symbols aren't
+        // resolved until the backtrace is formatted. `std::backtrace` is newer
+        // than the crate's MSRV, so the `backtrace` feature requires Rust 1.65
+        #[cfg(feature = "backtrace")]
+        #[allow(clippy::incompatible_msrv)]
+        let backtrace = if level == Level::Error {
+            Some(std::backtrace::Backtrace::capture())
+                .filter(|b| b.status() == std::backtrace::BacktraceStatus::Captured)
+        } else {
+            None
+        };
+        #[cfg(feature = "backtrace")]
+        let backtrace = backtrace
+            .as_ref()
+            .map(|b| (crate::kv::keys::BACKTRACE, Value::from_display(b)));
+        #[cfg(feature = "backtrace")]
+        let kvs: [&dyn crate::kv::Source; 4] = [&kvs, &backtrace, &build_info, &truncated];
+        #[cfg(feature = "backtrace")]
+        builder.key_values(&kvs);
+
+        #[cfg(feature = "std")]
+        if capturing && crate::capture::try_capture(&builder.build()) {
+            return;
+        }
+
+        #[cfg(feature = "early_buffer")]
+        if buffer {
+            crate::early_buffer::push(&builder.build());
+            return;
+        }
+
+        // Audit records must never be dropped, so they aren't de-duplicated
+        #[cfg(feature = "std")]
+        if crate::dedup::is_enabled() && audit.is_none() {
+            crate::dedup::log(crate::logger(), &builder.build());
+            return;
+        }
+
+        crate::dispatch(crate::logger(), &builder.build());
+    };
+
+    let message_truncated = if limited {
+        crate::limits::truncate_message(args)
+    } else {
+        None
+    };
+
+    match message_truncated {
+        Some(message) => emit(format_args!("{}", message), true),
+        None => emit(args, kvs_truncated.is_some()),
+    }
+}
+
+#[cfg_attr(feature = "outline_callsites", inline(never))]
+pub fn log<'a, K, T>(
+    args: Arguments,
+    level: Level,
+    (target, loc): &(T, &SourceLocation<'static>),
+    kvs: K,
+    kind: Kind,
+) where
+    K: KVs<'a>,
+    T: Target,
+{
+    log_impl(
+        args,
+        level,
+        (target.name(), target.handle()),
+        loc,
+        kvs.into_kvs(),
+        (kind, None),
+        None,
+    )
 }
 
-pub fn log<'a, K>(
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "outline_callsites", inline(never))]
+pub fn log_sampled<'a, K, T>(
     args: Arguments,
     level: Level,
-    target_module_path_and_loc: &(&str, &'static str, &'static Location),
+    (target, loc): &(T, &SourceLocation<'static>),
     kvs: K,
+    kind: Kind,
+    sampler: &Sampler,
+    rate: f64,
 ) where
     K: KVs<'a>,
+    T: Target,
 {
-    log_impl(args, level, target_module_path_and_loc, kvs.into_kvs())
+    if !sampler.sample(rate) {
+        return;
+    }
+
+    log_impl(
+        args,
+        level,
+        (target.name(), target.handle()),
+        loc,
+        kvs.into_kvs(),
+        (kind, Some(rate)),
+        None,
+    )
+}
+
+#[cfg_attr(feature = "outline_callsites", inline(never))]
+pub fn log_metadata(args: Arguments, metadata: &Metadata, loc: &SourceLocation<'static>) {
+    log_impl(
+        args,
+        metadata.level(),
+        (metadata.target(), metadata.target_handle()),
+        loc,
+        None,
+        (
+            Kind {
+                kind: metadata.kind(),
+                verbosity: metadata.verbosity(),
+                dedup_key: metadata.dedup_key(),
+                msg_id: metadata.msg_id(),
+            },
+            metadata.sample_rate(),
+        ),
+        metadata.audit_name(),
+    )
+}
+
+#[cfg(feature = "kv")]
+#[cfg_attr(feature = "outline_callsites", inline(never))]
+pub fn audit<T: Target>(
+    args: Arguments,
+    (target, loc): &(T, &SourceLocation<'static>),
+    name: &str,
+    kvs: &[(&str, Value)],
+) {
+    log_impl(
+        args,
+        Level::Info,
+        (target.name(), target.handle()),
+        loc,
+        Some(kvs),
+        (RecordKind::Log.into(), None),
+        Some(name),
+    )
+}
+
+// Whether any thread has overridden its maximum level. The logging macros
+// check this before `thread_max_level_allows`, so records below the global
+// maximum level only cost an atomic load until an override is set.
+#[inline]
+pub fn thread_max_level_used() -> bool {
+    #[cfg(feature = "std")]
+    return crate::thread_level::used();
+
+    #[cfg(not(feature = "std"))]
+    {
+        false
+    }
+}
+
+// Whether the current thread's maximum level allows a record the global
+// maximum level doesn't.
+#[inline]
+pub fn thread_max_level_allows(level: Level) -> bool {
+    #[cfg(feature = "std")]
+    return crate::thread_level::allows(level) == Some(true);
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = level;
+        false
+    }
+}
+
+// Whether the maximum level for the current thread allows a record.
+#[inline]
+pub fn max_level_allows(level: Level) -> bool {
+    #[cfg(feature = "std")]
+    if let Some(allows) = crate::thread_level::allows(level) {
+        return allows;
+    }
+
+    level <= crate::max_level_relaxed()
+}
+
+// Whether records with the given metadata pass the static and current maximum
+// levels. Audit records aren't subject to either.
+#[inline]
+pub fn metadata_allows(metadata: &Metadata) -> bool {
+    metadata.is_audit()
+        || (metadata.level() <= crate::STATIC_MAX_LEVEL && max_level_allows(metadata.level()))
+}
+
+// Whether a level given as a constant passes the static maximum level.
+#[derive(Debug)]
+pub struct StaticLevel<const LEVEL: usize>;
+
+impl<const LEVEL: usize> StaticLevel<LEVEL> {
+    pub const ENABLED: bool = LEVEL <= crate::STATIC_MAX_LEVEL as usize;
+}
+
+// Fails compilation if the keys given to a logging macro are empty or repeated.
+pub const fn check_keys(keys: &[&str]) {
+    let mut i = 0;
+    while i < keys.len() {
+        if keys[i].is_empty() {
+            panic!("key-values in logging macros can't have empty keys");
+        }
+
+        let mut j = i + 1;
+        while j < keys.len() {
+            if str_eq(keys[i], keys[j]) {
+                panic!("key-values in logging macros can't have duplicate keys");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
 }
 
-pub fn enabled(level: Level, target: &str) -> bool {
-    crate::logger().enabled(&Metadata::builder().level(level).target(target).build())
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+pub fn enabled<T: Target>(level: Level, target: T, kind: Kind) -> bool {
+    let mut extras = MetadataExtras::new();
+    extras
+        .target_handle(target.handle())
+        .kind(kind.kind)
+        .verbosity(kind.verbosity);
+
+    crate::logger().enabled(
+        &Metadata::builder()
+            .level(level)
+            .target(target.name())
+            .extras(Some(&extras).filter(|extras| !extras.is_empty()))
+            .build(),
+    )
+}
+
+// The sampling state of a callsite, using a SplitMix generator.
+pub struct Sampler {
+    state: AtomicUsize,
+}
+
+impl std::fmt::Debug for Sampler {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Sampler").finish_non_exhaustive()
+    }
+}
+
+impl Sampler {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Sampler {
+            state: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn sample(&self, rate: f64) -> bool {
+        if rate >= 1.0 {
+            return true;
+        }
+        if rate <= 0.0 || rate.is_nan() {
+            return false;
+        }
+
+        // Concurrent callers may see the same state and make the same
+        // decision, which only skews the sampling slightly. Each callsite is
+        // seeded with its address so they aren't correlated
+        const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+        let state = self.state.fetch_add(GAMMA as usize, Ordering::Relaxed);
+        let mut z = (state as u64 ^ self as *const Sampler as u64).wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        // The top 53 bits as a float in [0, 1)
+        ((z >> 11) as f64) * (1.0 / (1u64 << 53) as f64) < rate
+    }
 }
 
 #[track_caller]
@@ -108,6 +577,47 @@ mod kv_support {
         Value::from_dyn_error(v)
     }
 
+    // The error in a `Result` given to `log_err!`. Boxed errors, like
+    // `Box<dyn Error + Send + Sync>`, don't implement `Error` themselves, so
+    // they're borrowed through the box instead. Method resolution tries
+    // `ErrRef` before `&ErrRef`, so boxes pick their own impl.
+    #[cfg(feature = "kv_std")]
+    #[derive(Debug)]
+    pub struct ErrRef<'a, E: ?Sized>(pub &'a E);
+
+    #[cfg(feature = "kv_std")]
+    pub trait AsDynError<'a> {
+        fn as_dyn_error(&self) -> &'a (dyn std::error::Error + 'static);
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl<'a, E: std::error::Error + 'static> AsDynError<'a> for &ErrRef<'a, E> {
+        fn as_dyn_error(&self) -> &'a (dyn std::error::Error + 'static) {
+            self.0
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    macro_rules! impl_as_dyn_error_boxed {
+        ($($error:ty),*) => {
+            $(
+                impl<'a> AsDynError<'a> for ErrRef<'a, Box<$error>> {
+                    fn as_dyn_error(&self) -> &'a (dyn std::error::Error + 'static) {
+                        &**self.0
+                    }
+                }
+            )*
+        };
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl_as_dyn_error_boxed![
+        dyn std::error::Error,
+        dyn std::error::Error + Send,
+        dyn std::error::Error + Sync,
+        dyn std::error::Error + Send + Sync
+    ];
+
     #[cfg(feature = "kv_sval")]
     pub fn capture_sval<'a, V: sval::Value + ?Sized>(v: &'a &'a V) -> Value<'a> {
         Value::from_sval(v)
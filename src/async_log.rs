@@ -0,0 +1,376 @@
+//! Attaching asynchronous loggers to the synchronous facade.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use crate::wire::OwnedRecord;
+use crate::{Log, Metadata, Record};
+
+/// A logger that handles records asynchronously.
+///
+/// Sinks that ship records over the network, like HTTP shippers or gRPC
+/// exporters, are usually written for an async runtime. `AsyncLog` is the
+/// async counterpart of [`Log`], and [`bridge_async`] turns an `AsyncLog` into
+/// a `Log` that can be installed as the global logger.
+///
+/// Records are passed as an [`OwnedRecord`], since they're handled after the
+/// logging macro that created them has returned.
+///
+/// Implementations can use `async fn` for [`log`](#tymethod.log) and
+/// [`flush`](#tymethod.flush), as long as the futures they return are `Send`.
+///
+/// Requires the `async_log` feature and Rust 1.75.
+///
+/// [`Log`]: trait.Log.html
+/// [`bridge_async`]: fn.bridge_async.html
+/// [`OwnedRecord`]: wire/struct.OwnedRecord.html
+pub trait AsyncLog: Send + Sync + 'static {
+    /// Determines if a log message with the specified metadata would be logged.
+    ///
+    /// This is called synchronously by the logging macros, before the record
+    /// is copied, so it should be cheap. See [`Log::enabled`] for details.
+    ///
+    /// The default implementation returns `true`.
+    ///
+    /// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let _ = metadata;
+        true
+    }
+
+    /// Logs the `OwnedRecord`.
+    fn log(&self, record: OwnedRecord) -> impl Future<Output = ()> + Send;
+
+    /// Flushes any buffered records.
+    fn flush(&self) -> impl Future<Output = ()> + Send;
+}
+
+/// Bridge an [`AsyncLog`] to the synchronous [`Log`] trait.
+///
+/// The returned logger copies each enabled record into an [`OwnedRecord`] and
+/// queues it. `spawn` is called once with a task that passes queued records
+/// to `logger` in the order they were logged, so it should spawn the task on
+/// the async runtime the logger runs on, like `|task| { tokio::spawn(task); }`.
+///
+/// Records are queued in memory until the task gets to them, so a logger that
+/// can't keep up will use more and more memory.
+///
+/// Calling [`Log::flush`] on the returned logger queues a call to
+/// [`AsyncLog::flush`] after the records logged before it, and blocks until
+/// that call completes. It returns early if the task is dropped. It mustn't
+/// be called on a thread that the task needs to run, like in a task on a
+/// single-threaded runtime, or it will never return.
+///
+/// Requires the `async_log` feature and Rust 1.75.
+///
+/// # Examples
+///
+/// ```
+/// # use std::future::Future;
+/// # use std::pin::Pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake};
+/// # use std::thread::{self, Thread};
+/// # struct Unpark(Thread);
+/// # impl Wake for Unpark {
+/// #     fn wake(self: Arc<Self>) {
+/// #         self.0.unpark();
+/// #     }
+/// # }
+/// # // A minimal executor that runs each task on its own thread
+/// # fn spawn(mut task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+/// #     thread::spawn(move || {
+/// #         let waker = Arc::new(Unpark(thread::current())).into();
+/// #         while task.as_mut().poll(&mut Context::from_waker(&waker)).is_pending() {
+/// #             thread::park();
+/// #         }
+/// #     });
+/// # }
+/// use log::wire::OwnedRecord;
+/// use log::{info, AsyncLog, Log};
+///
+/// struct Shipper;
+///
+/// impl AsyncLog for Shipper {
+///     async fn log(&self, record: OwnedRecord) {
+///         // Send the record to a collector
+///         # let _ = record;
+///     }
+///
+///     async fn flush(&self) {}
+/// }
+///
+/// let logger = log::bridge_async(Shipper, spawn);
+///
+/// log::set_boxed_logger(Box::new(logger))?;
+/// log::set_max_level(log::LevelFilter::Info);
+///
+/// info!("shipped asynchronously");
+///
+/// // Wait for the record to be shipped
+/// log::logger().flush();
+/// # Ok::<(), log::SetLoggerError>(())
+/// ```
+///
+/// [`AsyncLog`]: trait.AsyncLog.html
+/// [`Log`]: trait.Log.html
+/// [`OwnedRecord`]: wire/struct.OwnedRecord.html
+/// [`Log::flush`]: trait.Log.html#tymethod.flush
+/// [`AsyncLog::flush`]: trait.AsyncLog.html#tymethod.flush
+pub fn bridge_async<L, S>(logger: L, spawn: S) -> impl Log
+where
+    L: AsyncLog,
+    S: FnOnce(Pin<Box<dyn Future<Output = ()> + Send>>),
+{
+    let logger = Arc::new(logger);
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            waker: None,
+            flushes_requested: 0,
+            flushes_done: 0,
+            closed: false,
+        }),
+        flushed: Condvar::new(),
+    });
+
+    spawn(Box::pin(run(logger.clone(), shared.clone())));
+
+    Bridge { logger, shared }
+}
+
+struct Bridge<L> {
+    logger: Arc<L>,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    flushed: Condvar,
+}
+
+struct State {
+    queue: VecDeque<Message>,
+    waker: Option<Waker>,
+    flushes_requested: u64,
+    flushes_done: u64,
+    closed: bool,
+}
+
+enum Message {
+    Record(OwnedRecord),
+    Flush(u64),
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, mut state: MutexGuard<'_, State>, message: Message) {
+        state.queue.push_back(message);
+        let waker = state.waker.take();
+        drop(state);
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<L: AsyncLog> Log for Bridge<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.logger.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = OwnedRecord::from(record);
+
+        let state = self.shared.lock();
+        if !state.closed {
+            self.shared.push(state, Message::Record(record));
+        }
+    }
+
+    fn flush(&self) {
+        let mut state = self.shared.lock();
+        if state.closed {
+            return;
+        }
+
+        state.flushes_requested += 1;
+        let id = state.flushes_requested;
+        self.shared.push(state, Message::Flush(id));
+
+        let mut state = self.shared.lock();
+        while state.flushes_done < id && !state.closed {
+            state = self
+                .shared
+                .flushed
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+// The task that passes queued records to the async logger.
+fn run<L: AsyncLog>(logger: Arc<L>, shared: Arc<Shared>) -> impl Future<Output = ()> + Send {
+    // Anything waiting on a flush is released if the task is dropped, even
+    // if it was never polled
+    struct Close(Arc<Shared>);
+
+    impl Drop for Close {
+        fn drop(&mut self) {
+            let mut state = self.0.lock();
+            state.closed = true;
+            state.queue.clear();
+            drop(state);
+
+            self.0.flushed.notify_all();
+        }
+    }
+
+    let close = Close(shared);
+
+    async move {
+        loop {
+            match Next(&close.0).await {
+                Message::Record(record) => logger.log(record).await,
+                Message::Flush(id) => {
+                    logger.flush().await;
+
+                    let mut state = close.0.lock();
+                    state.flushes_done = id;
+                    drop(state);
+
+                    close.0.flushed.notify_all();
+                }
+            }
+        }
+    }
+}
+
+// Waits for the next queued message.
+struct Next<'a>(&'a Shared);
+
+impl<'a> Future for Next<'a> {
+    type Output = Message;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Message> {
+        let mut state = self.0.lock();
+
+        match state.queue.pop_front() {
+            Some(message) => Poll::Ready(message),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    use crate::Level;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn spawn(mut task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        thread::spawn(move || {
+            let waker = Arc::new(Unpark(thread::current())).into();
+            while task
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending()
+            {
+                thread::park();
+            }
+        });
+    }
+
+    struct Collect {
+        records: Mutex<Vec<String>>,
+        sender: Mutex<mpsc::Sender<Vec<String>>>,
+    }
+
+    impl AsyncLog for Collect {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= Level::Info
+        }
+
+        async fn log(&self, record: OwnedRecord) {
+            self.records.lock().unwrap().push(record.message().into());
+        }
+
+        async fn flush(&self) {
+            let records = self.records.lock().unwrap().clone();
+            self.sender.lock().unwrap().send(records).unwrap();
+        }
+    }
+
+    #[test]
+    fn bridge_logs_in_order_and_flushes() {
+        let (sender, receiver) = mpsc::channel();
+        let logger = bridge_async(
+            Collect {
+                records: Mutex::new(Vec::new()),
+                sender: Mutex::new(sender),
+            },
+            spawn,
+        );
+
+        for (level, message) in [
+            (Level::Info, "first"),
+            (Level::Debug, "filtered"),
+            (Level::Warn, "second"),
+        ] {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .args(format_args!("{}", message))
+                    .build(),
+            );
+        }
+
+        // Flushing waits for the async logger, so its records are available
+        logger.flush();
+        assert_eq!(
+            vec!["first".to_owned(), "second".to_owned()],
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn flush_returns_when_task_is_dropped() {
+        let (sender, _receiver) = mpsc::channel();
+        let logger = bridge_async(
+            Collect {
+                records: Mutex::new(Vec::new()),
+                sender: Mutex::new(sender),
+            },
+            drop,
+        );
+
+        logger.log(&Record::builder().args(format_args!("lost")).build());
+        logger.flush();
+    }
+}
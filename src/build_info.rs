@@ -0,0 +1,206 @@
+//! Build metadata attached to records.
+
+use std::fmt;
+
+use crate::{AtomicUsize, Ordering, INITIALIZED, INITIALIZING, UNINITIALIZED};
+
+// The BUILD_INFO static holds the build metadata. It is protected by the
+// BUILD_INFO_STATE static in the same way LOGGER is protected by STATE.
+static mut BUILD_INFO: Option<BuildInfo> = None;
+
+static BUILD_INFO_STATE: AtomicUsize = AtomicUsize::new(UNINITIALIZED);
+
+static SET_BUILD_INFO_ERROR: &str =
+    "attempted to set the build info after it was already initialized";
+
+/// Metadata about the build of the program that's logging.
+///
+/// Build info is set once, early in `main`, with [`set_build_info`], so that
+/// sinks can report which build a record came from without each one being
+/// configured with it. When the `global-fields` feature is enabled, records
+/// created by the logging macros carry it under the [`build.version`],
+/// [`build.git_sha`], and [`build.profile`] keys.
+///
+/// # Examples
+///
+/// ```
+/// use log::BuildInfo;
+///
+/// # fn main() {
+/// log::set_build_info(BuildInfo {
+///     version: env!("CARGO_PKG_VERSION"),
+///     git_sha: option_env!("GIT_SHA"),
+///     profile: Some(if cfg!(debug_assertions) { "debug" } else { "release" }),
+/// })
+/// .unwrap();
+///
+/// assert_eq!(env!("CARGO_PKG_VERSION"), log::build_info().unwrap().version);
+/// # }
+/// ```
+///
+/// [`set_build_info`]: fn.set_build_info.html
+/// [`build.version`]: kv/keys/constant.BUILD_VERSION.html
+/// [`build.git_sha`]: kv/keys/constant.BUILD_GIT_SHA.html
+/// [`build.profile`]: kv/keys/constant.BUILD_PROFILE.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BuildInfo {
+    /// The version of the program, like `CARGO_PKG_VERSION`.
+    pub version: &'static str,
+    /// The commit the program was built from.
+    pub git_sha: Option<&'static str>,
+    /// The profile the program was built with, like `release`.
+    pub profile: Option<&'static str>,
+}
+
+/// Sets the global build info.
+///
+/// This function may only be called once in the lifetime of a program. Any
+/// records created before the call to `set_build_info` completes won't carry
+/// build info.
+///
+/// # Errors
+///
+/// An error is returned if the build info has already been set.
+#[cfg(target_has_atomic = "ptr")]
+pub fn set_build_info(build_info: BuildInfo) -> Result<(), SetBuildInfoError> {
+    match BUILD_INFO_STATE.compare_exchange(
+        UNINITIALIZED,
+        INITIALIZING,
+        Ordering::Acquire,
+        Ordering::Relaxed,
+    ) {
+        Ok(UNINITIALIZED) => {
+            unsafe {
+                BUILD_INFO = Some(build_info);
+            }
+            BUILD_INFO_STATE.store(INITIALIZED, Ordering::Release);
+            Ok(())
+        }
+        Err(INITIALIZING) => {
+            while BUILD_INFO_STATE.load(Ordering::Relaxed) == INITIALIZING {
+                std::hint::spin_loop();
+            }
+            Err(SetBuildInfoError(()))
+        }
+        _ => Err(SetBuildInfoError(())),
+    }
+}
+
+/// A thread-unsafe version of [`set_build_info`].
+///
+/// This function is available on all platforms, even those that do not have
+/// support for atomics that is needed by [`set_build_info`].
+///
+/// In almost all cases, [`set_build_info`] should be preferred.
+///
+/// # Safety
+///
+/// This function is only safe to call when it cannot race with any other
+/// calls to `set_build_info` or `set_build_info_racy`.
+///
+/// This can be upheld by (for example) making sure that **there are no other
+/// threads**, and (on embedded) that **interrupts are disabled**.
+///
+/// [`set_build_info`]: fn.set_build_info.html
+pub unsafe fn set_build_info_racy(build_info: BuildInfo) -> Result<(), SetBuildInfoError> {
+    match BUILD_INFO_STATE.load(Ordering::Acquire) {
+        UNINITIALIZED => {
+            BUILD_INFO = Some(build_info);
+            BUILD_INFO_STATE.store(INITIALIZED, Ordering::Release);
+            Ok(())
+        }
+        INITIALIZING => {
+            // This is just plain UB, since we were racing another initialization function
+            unreachable!("set_build_info_racy must not be used with other initialization functions")
+        }
+        _ => Err(SetBuildInfoError(())),
+    }
+}
+
+/// Returns the build info, if it has been set.
+pub fn build_info() -> Option<BuildInfo> {
+    if BUILD_INFO_STATE.load(Ordering::Acquire) != INITIALIZED {
+        None
+    } else {
+        unsafe { BUILD_INFO }
+    }
+}
+
+#[cfg(feature = "kv")]
+impl crate::kv::Source for BuildInfo {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn crate::kv::VisitSource<'kvs>,
+    ) -> Result<(), crate::kv::Error> {
+        use crate::kv::keys::{BUILD_GIT_SHA, BUILD_PROFILE, BUILD_VERSION};
+
+        visitor.visit_pair(BUILD_VERSION, self.version.into())?;
+        if let Some(git_sha) = self.git_sha {
+            visitor.visit_pair(BUILD_GIT_SHA, git_sha.into())?;
+        }
+        if let Some(profile) = self.profile {
+            visitor.visit_pair(BUILD_PROFILE, profile.into())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The type returned by [`set_build_info`] if [`set_build_info`] has already
+/// been called.
+///
+/// [`set_build_info`]: fn.set_build_info.html
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub struct SetBuildInfoError(());
+
+impl fmt::Display for SetBuildInfoError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(SET_BUILD_INFO_ERROR)
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl std::error::Error for SetBuildInfoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_has_atomic = "ptr")]
+    fn set_build_info_once() {
+        let build_info = BuildInfo {
+            version: "1.2.3",
+            git_sha: Some("0123abc"),
+            profile: None,
+        };
+
+        set_build_info(build_info).unwrap();
+        assert!(set_build_info(build_info).is_err());
+
+        assert_eq!(Some(build_info), super::build_info());
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn build_info_source() {
+        use crate::kv::{keys, Source};
+
+        let build_info = BuildInfo {
+            version: "1.2.3",
+            git_sha: None,
+            profile: Some("release"),
+        };
+
+        assert_eq!(2, build_info.count());
+        assert_eq!(
+            Some("1.2.3"),
+            build_info
+                .get(keys::BUILD_VERSION)
+                .and_then(|v| v.to_borrowed_str())
+        );
+        assert!(build_info.get(keys::BUILD_GIT_SHA).is_none());
+    }
+}
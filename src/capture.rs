@@ -0,0 +1,233 @@
+//! Capturing records on the current thread, for tests.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Level, LevelFilter, Record};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Vec<CapturedRecord>>> = const { RefCell::new(None) };
+}
+
+// Set once any thread has captured records, so the logging macros don't need
+// to check thread-local state until then
+static USED: AtomicBool = AtomicBool::new(false);
+
+/// Run `f`, capturing the records logged on the current thread while it runs.
+///
+/// While `f` runs, records logged on the current thread are collected instead
+/// of being passed to the global logger, and the thread's maximum log level is
+/// raised to `Trace`, like with [`set_thread_max_level`], so every record is
+/// captured. Other threads log as usual, so this doesn't interfere with the
+/// global logger or with tests running in parallel.
+///
+/// Calls can be nested; records are captured by the innermost call. Records
+/// logged on other threads spawned by `f` aren't captured.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, warn, Level};
+///
+/// fn connect(retries: u32) {
+///     for attempt in 1..=retries {
+///         warn!("connection refused, attempt {}", attempt);
+///     }
+///     info!("giving up");
+/// }
+///
+/// let records = log::capture(|| connect(2));
+///
+/// assert_eq!(3, records.len());
+/// assert_eq!(Level::Warn, records[0].level());
+/// assert_eq!("connection refused, attempt 1", records[0].message());
+/// assert_eq!("giving up", records[2].message());
+/// ```
+///
+/// [`set_thread_max_level`]: fn.set_thread_max_level.html
+pub fn capture(f: impl FnOnce()) -> Vec<CapturedRecord> {
+    struct Restore {
+        records: Option<Vec<CapturedRecord>>,
+        max_level: Option<LevelFilter>,
+    }
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let records = self.records.take();
+            CURRENT.with(|current| *current.borrow_mut() = records);
+
+            match self.max_level {
+                Some(level) => crate::set_thread_max_level(level),
+                None => crate::clear_thread_max_level(),
+            }
+        }
+    }
+
+    USED.store(true, Ordering::Relaxed);
+
+    let _restore = Restore {
+        records: CURRENT.with(|current| current.borrow_mut().replace(Vec::new())),
+        max_level: crate::thread_max_level(),
+    };
+    crate::set_thread_max_level(LevelFilter::Trace);
+
+    f();
+
+    // The previous records are restored when `_restore` is dropped
+    CURRENT
+        .with(|current| current.borrow_mut().take())
+        .unwrap_or_default()
+}
+
+/// Whether records logged on the current thread are being captured.
+#[inline]
+pub(crate) fn is_capturing() -> bool {
+    if !USED.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    // The thread-local may already be destroyed if records are logged while
+    // the thread exits
+    CURRENT
+        .try_with(|current| current.borrow().is_some())
+        .unwrap_or(false)
+}
+
+/// Capture a record if records on the current thread are being captured.
+///
+/// Returns `false` if the record wasn't captured, and should be logged.
+pub(crate) fn try_capture(record: &Record) -> bool {
+    if !is_capturing() {
+        return false;
+    }
+
+    let record = CapturedRecord::new(record);
+
+    // The record is formatted before borrowing the captured records, in case
+    // formatting it logs
+    CURRENT
+        .try_with(|current| match &mut *current.borrow_mut() {
+            Some(records) => {
+                records.push(record);
+                true
+            }
+            None => false,
+        })
+        .unwrap_or(false)
+}
+
+/// An owned copy of a record captured by [`capture`].
+///
+/// [`capture`]: fn.capture.html
+#[derive(Clone, Debug)]
+pub struct CapturedRecord {
+    level: Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    #[cfg(feature = "kv_std")]
+    key_values: Vec<(String, crate::kv::OwnedValue)>,
+}
+
+impl CapturedRecord {
+    fn new(record: &Record) -> Self {
+        CapturedRecord {
+            level: record.level(),
+            target: record.target().into(),
+            message: record.render().into_owned(),
+            module_path: record.module_path().map(Into::into),
+            file: record.file().map(Into::into),
+            line: record.line(),
+            #[cfg(feature = "kv_std")]
+            key_values: crate::kv::Source::to_owned_vec(record.key_values()),
+        }
+    }
+
+    /// The level of the record.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The target of the record.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The formatted message of the record.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The module path the record was created in, if it's known.
+    pub fn module_path(&self) -> Option<&str> {
+        self.module_path.as_deref()
+    }
+
+    /// The source file the record was created in, if it's known.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// The line the record was created on, if it's known.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// The key-values of the record.
+    ///
+    /// The returned slice implements [`Source`], so values can be looked up
+    /// by their key.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// [`Source`]: kv/trait.Source.html
+    #[cfg(feature = "kv_std")]
+    pub fn key_values(&self) -> &[(String, crate::kv::OwnedValue)] {
+        &self.key_values
+    }
+}
+
+impl fmt::Display for CapturedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_nested() {
+        let records = capture(|| {
+            crate::info!("outer");
+
+            let inner = capture(|| crate::trace!("inner"));
+            assert_eq!(1, inner.len());
+            assert_eq!(Level::Trace, inner[0].level());
+        });
+
+        assert_eq!(1, records.len());
+        assert_eq!("outer", records[0].message());
+        assert_eq!(Some(module_path!()), records[0].module_path());
+
+        assert!(!is_capturing());
+        assert_eq!(None, crate::thread_max_level());
+    }
+
+    #[test]
+    fn other_threads_are_not_captured() {
+        let records = capture(|| {
+            std::thread::spawn(|| assert!(!is_capturing()))
+                .join()
+                .unwrap();
+        });
+
+        assert!(records.is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+//! A pluggable clock used to timestamp records.
+
+use std::fmt;
+
+use crate::{AtomicUsize, Ordering, INITIALIZED, INITIALIZING, UNINITIALIZED};
+
+// The CLOCK static holds a pointer to the global clock. It is protected by
+// the CLOCK_STATE static in the same way LOGGER is protected by STATE.
+static mut CLOCK: Option<&'static dyn Clock> = None;
+
+static CLOCK_STATE: AtomicUsize = AtomicUsize::new(UNINITIALIZED);
+
+static SET_CLOCK_ERROR: &str = "attempted to set a clock after the clock was already initialized";
+
+/// A source of timestamps for log records.
+///
+/// The clock is called once for each record created by the logging macros, so
+/// that all loggers observe the same timestamp for a given record. What a tick
+/// represents is up to the clock; it may be a wall-clock time, or a monotonic
+/// counter on targets that don't have one.
+///
+/// Requires the `timestamps` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// struct Counter(AtomicU64);
+///
+/// impl log::Clock for Counter {
+///     fn now(&self) -> u64 {
+///         self.0.fetch_add(1, Ordering::Relaxed)
+///     }
+/// }
+///
+/// static CLOCK: Counter = Counter(AtomicU64::new(0));
+///
+/// # fn main() {
+/// log::set_clock(&CLOCK).unwrap();
+/// # }
+/// ```
+pub trait Clock: Sync + Send {
+    /// Get the current time in ticks.
+    fn now(&self) -> u64;
+}
+
+impl<T> Clock for &'_ T
+where
+    T: ?Sized + Clock,
+{
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+/// Sets the global clock to a `&'static Clock`.
+///
+/// This function may only be called once in the lifetime of a program. Any records
+/// created before the call to `set_clock` completes won't have a timestamp.
+///
+/// # Errors
+///
+/// An error is returned if a clock has already been set.
+#[cfg(target_has_atomic = "ptr")]
+pub fn set_clock(clock: &'static dyn Clock) -> Result<(), SetClockError> {
+    match CLOCK_STATE.compare_exchange(
+        UNINITIALIZED,
+        INITIALIZING,
+        Ordering::Acquire,
+        Ordering::Relaxed,
+    ) {
+        Ok(UNINITIALIZED) => {
+            unsafe {
+                CLOCK = Some(clock);
+            }
+            CLOCK_STATE.store(INITIALIZED, Ordering::Release);
+            Ok(())
+        }
+        Err(INITIALIZING) => {
+            while CLOCK_STATE.load(Ordering::Relaxed) == INITIALIZING {
+                std::hint::spin_loop();
+            }
+            Err(SetClockError(()))
+        }
+        _ => Err(SetClockError(())),
+    }
+}
+
+/// A thread-unsafe version of [`set_clock`].
+///
+/// This function is available on all platforms, even those that do not have
+/// support for atomics that is needed by [`set_clock`].
+///
+/// In almost all cases, [`set_clock`] should be preferred.
+///
+/// # Safety
+///
+/// This function is only safe to call when it cannot race with any other
+/// calls to `set_clock` or `set_clock_racy`.
+///
+/// This can be upheld by (for example) making sure that **there are no other
+/// threads**, and (on embedded) that **interrupts are disabled**.
+///
+/// [`set_clock`]: fn.set_clock.html
+pub unsafe fn set_clock_racy(clock: &'static dyn Clock) -> Result<(), SetClockError> {
+    match CLOCK_STATE.load(Ordering::Acquire) {
+        UNINITIALIZED => {
+            CLOCK = Some(clock);
+            CLOCK_STATE.store(INITIALIZED, Ordering::Release);
+            Ok(())
+        }
+        INITIALIZING => {
+            // This is just plain UB, since we were racing another initialization function
+            unreachable!("set_clock_racy must not be used with other initialization functions")
+        }
+        _ => Err(SetClockError(())),
+    }
+}
+
+/// Returns a reference to the clock, if one has been set.
+pub fn clock() -> Option<&'static dyn Clock> {
+    if CLOCK_STATE.load(Ordering::Acquire) != INITIALIZED {
+        None
+    } else {
+        unsafe { CLOCK }
+    }
+}
+
+/// Get the current time from the global clock, if one has been set.
+pub(crate) fn now() -> Option<u64> {
+    clock().map(|clock| clock.now())
+}
+
+/// The type returned by [`set_clock`] if [`set_clock`] has already been called.
+///
+/// [`set_clock`]: fn.set_clock.html
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub struct SetClockError(());
+
+impl fmt::Display for SetClockError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(SET_CLOCK_ERROR)
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl std::error::Error for SetClockError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_has_atomic = "ptr")]
+    fn set_clock_once() {
+        struct Fixed;
+
+        impl Clock for Fixed {
+            fn now(&self) -> u64 {
+                42
+            }
+        }
+
+        static FIXED: Fixed = Fixed;
+
+        set_clock(&FIXED).unwrap();
+        assert!(set_clock(&FIXED).is_err());
+
+        assert_eq!(Some(42), now());
+    }
+}
@@ -0,0 +1,94 @@
+//! A thread-local correlation id captured by the logging macros.
+
+use std::cell::Cell;
+
+use crate::{AtomicUsize, Ordering};
+
+thread_local! {
+    static CURRENT: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+// Set to 1 once any thread has set a correlation id, so the logging macros
+// don't need to check thread-local state until then
+static USED: AtomicUsize = AtomicUsize::new(0);
+
+/// Run `f` with `id` as the current thread's correlation id.
+///
+/// Records created by the logging macros while `f` runs carry `id` in
+/// [`Record::correlation_id`]. Calls can be nested; the previous id is
+/// restored when `f` returns or panics.
+///
+/// The id isn't propagated to other threads. Code that hands work off to
+/// another thread can capture [`correlation_id`] and call
+/// `with_correlation_id` again on that thread.
+///
+/// Requires the `correlation` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::info;
+///
+/// # fn main() {
+/// log::with_correlation_id(42, || {
+///     // This record has a correlation id of 42
+///     info!("handling request");
+/// });
+/// # }
+/// ```
+///
+/// [`Record::correlation_id`]: struct.Record.html#method.correlation_id
+/// [`correlation_id`]: fn.correlation_id.html
+pub fn with_correlation_id<R>(id: u64, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<u64>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| current.set(self.0));
+        }
+    }
+
+    USED.store(1, Ordering::Relaxed);
+    let _restore = Restore(CURRENT.with(|current| current.replace(Some(id))));
+
+    f()
+}
+
+/// Get the current thread's correlation id, if one is set.
+///
+/// Requires the `correlation` feature.
+#[inline]
+pub fn correlation_id() -> Option<u64> {
+    if USED.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+
+    CURRENT.with(|current| current.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_ids_are_restored() {
+        assert_eq!(None, correlation_id());
+
+        with_correlation_id(1, || {
+            assert_eq!(Some(1), correlation_id());
+
+            with_correlation_id(2, || assert_eq!(Some(2), correlation_id()));
+
+            assert_eq!(Some(1), correlation_id());
+        });
+
+        assert_eq!(None, correlation_id());
+    }
+
+    #[test]
+    fn id_is_restored_on_panic() {
+        let _ = std::panic::catch_unwind(|| with_correlation_id(1, || panic!()));
+
+        assert_eq!(None, correlation_id());
+    }
+}
@@ -0,0 +1,370 @@
+//! De-duplication of identical consecutive log messages.
+//!
+//! When de-duplication is enabled, a record logged through the logging macros
+//! that has the same level, target, message, and key-values as the record logged
+//! immediately before it is suppressed if it arrives within the configured window.
+//! When a different record arrives, or the window elapses, a summary record saying
+//! how many times the message was repeated is logged before it.
+//!
+//! De-duplication is disabled by default. It only applies to records created by
+//! the logging macros. Records passed directly to the [`logger`] aren't
+//! de-duplicated. Records logged while another thread is checking its own
+//! record are logged without being checked, rather than waiting for it.
+//!
+//! Requires the `std` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! log::dedup::enable(Duration::from_secs(5));
+//!
+//! for _ in 0..10 {
+//!     // Only the first of these is logged
+//!     log::warn!("connection refused");
+//! }
+//!
+//! // Logs "last message repeated 9 times" before the message
+//! log::info!("connection established");
+//! ```
+//!
+//! [`logger`]: ../fn.logger.html
+
+use std::fmt::{self, Write};
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, TryLockError};
+use std::time::{Duration, Instant};
+
+use crate::lazy_global::LazyGlobal;
+use crate::{Level, Log, MetadataExtras, Record, TargetHandle};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// The de-duplication state is allocated the first time it's needed and then
+// lives for the rest of the program.
+static STATE: LazyGlobal<Mutex<Dedup>> = LazyGlobal::new();
+
+/// Enable de-duplication of consecutive identical messages within `window`.
+///
+/// If de-duplication is already enabled then its window is replaced.
+pub fn enable(window: Duration) {
+    state().lock().unwrap_or_else(|e| e.into_inner()).window = window;
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Disable de-duplication.
+///
+/// If any messages have been suppressed then a summary record for them is logged.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Release);
+
+    let repeated = state()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last
+        .take()
+        .and_then(Last::into_repeated);
+
+    if let Some(repeated) = repeated {
+        repeated.log(crate::logger());
+    }
+}
+
+/// Whether de-duplication is currently enabled.
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn state() -> &'static Mutex<Dedup> {
+    STATE.get_or_init(|| Mutex::new(Dedup::new(Duration::from_secs(0))))
+}
+
+/// Log `record` to `logger`, subject to de-duplication.
+pub(crate) fn log(logger: &dyn Log, record: &Record) {
+    // The state is also locked if formatting the record logs another one
+    let (log_record, repeated) = match state().try_lock() {
+        Ok(mut state) => state.check(record, Instant::now()),
+        Err(TryLockError::Poisoned(e)) => e.into_inner().check(record, Instant::now()),
+        Err(TryLockError::WouldBlock) => (true, None),
+    };
+
+    // Call into the logger without holding the lock, in case it logs itself
+    if let Some(repeated) = repeated {
+        repeated.log(logger);
+    }
+
+    if log_record {
+        crate::dispatch(logger, record);
+    }
+}
+
+struct Dedup {
+    window: Duration,
+    last: Option<Last>,
+}
+
+struct Last {
+    level: Level,
+    target: String,
+    target_handle: Option<TargetHandle>,
+    // The message and key-values, written by `write_key`
+    key: String,
+    first_logged: Instant,
+    repeated: usize,
+}
+
+struct Repeated {
+    level: Level,
+    target: String,
+    target_handle: Option<TargetHandle>,
+    count: usize,
+}
+
+impl Dedup {
+    fn new(window: Duration) -> Self {
+        Dedup { window, last: None }
+    }
+
+    // Returns whether `record` should be logged, and a summary of any suppressed
+    // records that should be logged before it.
+    fn check(&mut self, record: &Record, now: Instant) -> (bool, Option<Repeated>) {
+        if let Some(last) = &mut self.last {
+            if last.level == record.level()
+                && last.target == record.target()
+                && now.saturating_duration_since(last.first_logged) <= self.window
+                && last.matches(record)
+            {
+                last.repeated += 1;
+                return (false, None);
+            }
+        }
+
+        // Reuse the last record's buffer, so a new record doesn't allocate one
+        let mut key = match &mut self.last {
+            Some(last) => mem::take(&mut last.key),
+            None => String::new(),
+        };
+        key.clear();
+        let _ = write_key(record, &mut key);
+
+        let last = self.last.replace(Last {
+            level: record.level(),
+            target: record.target().into(),
+            target_handle: record.metadata().target_handle(),
+            key,
+            first_logged: now,
+            repeated: 0,
+        });
+
+        (true, last.and_then(Last::into_repeated))
+    }
+}
+
+impl Last {
+    // Compares the record's message and key-values as they're formatted,
+    // without allocating
+    fn matches(&self, record: &Record) -> bool {
+        struct Compare<'a>(&'a str);
+
+        impl<'a> Write for Compare<'a> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                match self.0.strip_prefix(s) {
+                    Some(rest) => {
+                        self.0 = rest;
+                        Ok(())
+                    }
+                    // Formatting stops at the first difference
+                    None => Err(fmt::Error),
+                }
+            }
+        }
+
+        let mut compare = Compare(&self.key);
+        write_key(record, &mut compare).is_ok() && compare.0.is_empty()
+    }
+
+    fn into_repeated(self) -> Option<Repeated> {
+        if self.repeated > 0 {
+            Some(Repeated {
+                level: self.level,
+                target: self.target,
+                target_handle: self.target_handle,
+                count: self.repeated,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+// Write the parts of `record` that are compared besides its level and target.
+fn write_key(record: &Record, w: &mut impl Write) -> fmt::Result {
+    w.write_fmt(*record.args())?;
+
+    #[cfg(feature = "kv")]
+    {
+        use crate::kv::{Error, Key, Value, VisitSource};
+
+        struct Visitor<'a, W>(&'a mut W);
+
+        impl<'a, 'kvs, W: Write> VisitSource<'kvs> for Visitor<'a, W> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                // Resolving a backtrace's symbols is slow, so they aren't compared
+                #[cfg(feature = "backtrace")]
+                if key == crate::kv::keys::BACKTRACE {
+                    return Ok(());
+                }
+
+                write!(self.0, "\0{}\0{}", key, value)?;
+                Ok(())
+            }
+        }
+
+        record
+            .key_values()
+            .visit(&mut Visitor(w))
+            .map_err(|_| fmt::Error)?;
+    }
+
+    Ok(())
+}
+
+impl Repeated {
+    fn log(&self, logger: &dyn Log) {
+        let mut extras = MetadataExtras::new();
+        extras.target_handle(self.target_handle);
+
+        crate::dispatch(
+            logger,
+            &Record::builder()
+                .args(format_args!("last message repeated {} times", self.count))
+                .level(self.level)
+                .target(&self.target)
+                .extras(Some(&extras).filter(|extras| !extras.is_empty()))
+                .build(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suppresses_consecutive_duplicates() {
+        let mut dedup = Dedup::new(Duration::from_secs(5));
+        let start = Instant::now();
+
+        let a = Record::builder()
+            .args(format_args!("a"))
+            .level(Level::Warn)
+            .build();
+        let b = Record::builder()
+            .args(format_args!("b"))
+            .level(Level::Warn)
+            .build();
+
+        let (logged, repeated) = dedup.check(&a, start);
+        assert!(logged);
+        assert!(repeated.is_none());
+
+        for _ in 0..3 {
+            let (logged, repeated) = dedup.check(&a, start + Duration::from_secs(1));
+            assert!(!logged);
+            assert!(repeated.is_none());
+        }
+
+        let (logged, repeated) = dedup.check(&b, start + Duration::from_secs(2));
+        assert!(logged);
+        let repeated = repeated.expect("missing summary");
+        assert_eq!(3, repeated.count);
+        assert_eq!(Level::Warn, repeated.level);
+
+        let (logged, repeated) = dedup.check(&a, start + Duration::from_secs(3));
+        assert!(logged);
+        assert!(repeated.is_none());
+    }
+
+    #[test]
+    fn logs_duplicates_outside_window() {
+        let mut dedup = Dedup::new(Duration::from_secs(5));
+        let start = Instant::now();
+
+        let a = Record::builder().args(format_args!("a")).build();
+
+        assert!(dedup.check(&a, start).0);
+        assert!(!dedup.check(&a, start + Duration::from_secs(1)).0);
+
+        let (logged, repeated) = dedup.check(&a, start + Duration::from_secs(6));
+        assert!(logged);
+        assert_eq!(1, repeated.expect("missing summary").count);
+    }
+
+    #[test]
+    fn distinguishes_level_and_target() {
+        let mut dedup = Dedup::new(Duration::from_secs(5));
+        let now = Instant::now();
+
+        let a = Record::builder()
+            .args(format_args!("a"))
+            .level(Level::Warn)
+            .build();
+        let a_info = Record::builder()
+            .args(format_args!("a"))
+            .level(Level::Info)
+            .build();
+        let a_target = Record::builder()
+            .args(format_args!("a"))
+            .level(Level::Info)
+            .target("other")
+            .build();
+
+        assert!(dedup.check(&a, now).0);
+        assert!(dedup.check(&a_info, now).0);
+        assert!(dedup.check(&a_target, now).0);
+    }
+
+    #[test]
+    fn distinguishes_prefixes() {
+        let mut dedup = Dedup::new(Duration::from_secs(5));
+        let now = Instant::now();
+
+        let a = Record::builder().args(format_args!("a")).build();
+        let ab = Record::builder()
+            .args(format_args!("{}{}", "a", "b"))
+            .build();
+
+        assert!(dedup.check(&ab, now).0);
+        assert!(dedup.check(&a, now).0);
+        assert!(dedup.check(&ab, now).0);
+        assert!(!dedup.check(&ab, now).0);
+    }
+
+    #[test]
+    #[cfg(feature = "kv")]
+    fn distinguishes_key_values() {
+        let mut dedup = Dedup::new(Duration::from_secs(5));
+        let now = Instant::now();
+
+        let kvs_1 = [("id", 1)];
+        let kvs_2 = [("id", 2)];
+
+        let a_1 = Record::builder()
+            .args(format_args!("a"))
+            .key_values(&kvs_1)
+            .build();
+        let a_2 = Record::builder()
+            .args(format_args!("a"))
+            .key_values(&kvs_2)
+            .build();
+        let a = Record::builder().args(format_args!("a")).build();
+
+        assert!(dedup.check(&a_1, now).0);
+        assert!(!dedup.check(&a_1, now).0);
+        assert!(dedup.check(&a_2, now).0);
+        assert!(dedup.check(&a, now).0);
+    }
+}
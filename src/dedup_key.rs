@@ -0,0 +1,186 @@
+//! Stable de-duplication keys derived from a set of values.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str;
+
+/// A de-duplication key derived by hashing a set of values.
+///
+/// Messages can be given a [`Metadata::dedup_key`] with the `dedup:` argument
+/// of the logging macros. When the event a message describes is identified by
+/// several values, like a check name and a device, `DedupKey` hashes them into
+/// a short key that can be used instead of formatting them together.
+///
+/// The hash doesn't depend on the process or the platform, so the same values
+/// give the same key wherever they're logged, as long as their `Hash`
+/// implementations don't change. The key is 16 lowercase hex digits.
+///
+/// # Examples
+///
+/// ```
+/// use log::{warn, DedupKey};
+///
+/// # fn main() {
+/// let device = "/dev/sda1";
+/// let key = DedupKey::from_values(&("disk_full", device));
+///
+/// warn!(dedup: key.as_str(), "disk {} is full", device);
+///
+/// assert_eq!(key, DedupKey::from_values(&("disk_full", "/dev/sda1")));
+/// assert_eq!(16, key.as_str().len());
+/// # }
+/// ```
+///
+/// [`Metadata::dedup_key`]: struct.Metadata.html#method.dedup_key
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DedupKey {
+    hash: u64,
+    hex: [u8; 16],
+}
+
+impl DedupKey {
+    /// Derive a key by hashing `values`.
+    ///
+    /// Several values can be hashed together by passing them as a tuple.
+    pub fn from_values<T: Hash + ?Sized>(values: &T) -> DedupKey {
+        let mut hasher = Fnv1a::new();
+        values.hash(&mut hasher);
+
+        DedupKey::from_u64(hasher.finish())
+    }
+
+    /// Create a key from a hash that's already been computed.
+    pub fn from_u64(hash: u64) -> DedupKey {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let mut hex = [0; 16];
+        for (i, digit) in hex.iter_mut().enumerate() {
+            *digit = DIGITS[(hash >> (60 - i * 4)) as usize & 0xf];
+        }
+
+        DedupKey { hash, hex }
+    }
+
+    /// The hash of the key.
+    pub fn to_u64(&self) -> u64 {
+        self.hash
+    }
+
+    /// The key as a string of hex digits.
+    pub fn as_str(&self) -> &str {
+        // The key is only ever made of ASCII hex digits
+        str::from_utf8(&self.hex).unwrap()
+    }
+}
+
+impl AsRef<str> for DedupKey {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for DedupKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DedupKey").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for DedupKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+// The 64-bit FNV-1a hash.
+//
+// Integers are always hashed as little-endian, and `usize`s and `isize`s as
+// 64 bits, so hashes are the same on every platform.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_values_is_stable() {
+        // FNV-1a of the empty input is its offset basis
+        assert_eq!("cbf29ce484222325", DedupKey::from_values(&()).as_str());
+
+        assert_eq!(
+            DedupKey::from_values(&("disk_full", 1usize)),
+            DedupKey::from_values(&("disk_full", 1usize)),
+        );
+        assert_ne!(
+            DedupKey::from_values(&("disk_full", 1usize)),
+            DedupKey::from_values(&("disk_full", 2usize)),
+        );
+    }
+
+    #[test]
+    fn from_u64() {
+        let key = DedupKey::from_u64(0x0123_4567_89ab_cdef);
+
+        assert_eq!("0123456789abcdef", key.as_str());
+        assert_eq!("0123456789abcdef", key.to_string());
+        assert_eq!(0x0123_4567_89ab_cdef, key.to_u64());
+    }
+}
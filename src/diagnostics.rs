@@ -0,0 +1,131 @@
+//! Counters maintained by the facade about records created by the logging macros.
+
+use crate::{AtomicUsize, Ordering};
+
+// Submitted records are only counted once diagnostics are enabled, so the
+// logging macros don't all write to the same counter by default
+static ENABLED: AtomicUsize = AtomicUsize::new(0);
+
+static SUBMITTED: AtomicUsize = AtomicUsize::new(0);
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+static BEFORE_INIT: AtomicUsize = AtomicUsize::new(0);
+
+static LOGGER_PANICS: AtomicUsize = AtomicUsize::new(0);
+
+/// Start counting the records submitted by the logging macros.
+///
+/// Counting submitted records adds a write to a counter shared by every thread
+/// to each record that's logged, so [`Diagnostics::submitted`] stays at zero
+/// until this is called. The other counters only change when something goes
+/// wrong, so they're always maintained.
+///
+/// [`Diagnostics::submitted`]: struct.Diagnostics.html#method.submitted
+pub fn enable_diagnostics() {
+    ENABLED.store(1, Ordering::Relaxed);
+}
+
+/// Returns a snapshot of the counters maintained by the facade.
+///
+/// The counters only cover records created by the logging macros that passed
+/// the maximum log level check. Records passed directly to a logger aren't counted.
+///
+/// # Examples
+///
+/// Report any records that were lost before a logger was installed:
+///
+/// ```
+/// # fn main() {
+/// let diagnostics = log::diagnostics();
+///
+/// if diagnostics.dropped() > 0 {
+///     eprintln!("{} records were logged before the logger was set", diagnostics.dropped());
+/// }
+/// # }
+/// ```
+pub fn diagnostics() -> Diagnostics {
+    Diagnostics {
+        submitted: SUBMITTED.load(Ordering::Relaxed),
+        dropped: DROPPED.load(Ordering::Relaxed),
+        before_init: BEFORE_INIT.load(Ordering::Relaxed),
+        logger_panics: LOGGER_PANICS.load(Ordering::Relaxed),
+    }
+}
+
+/// Whether records created by the logging macros should be counted with [`submit`].
+#[inline]
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) != 0
+}
+
+/// Count a record created by the logging macros.
+#[inline]
+pub(crate) fn submit() {
+    SUBMITTED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a record created by the logging macros before the logger was initialized.
+#[inline]
+pub(crate) fn record_before_init() {
+    BEFORE_INIT.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a record that was dropped because no logger was installed.
+#[inline]
+pub(crate) fn record_dropped() {
+    DROPPED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a panic raised by the logger that was caught.
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub(crate) fn record_logger_panic() {
+    LOGGER_PANICS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A snapshot of the counters maintained by the facade.
+///
+/// This type is returned by [`diagnostics`].
+///
+/// [`diagnostics`]: fn.diagnostics.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    submitted: usize,
+    dropped: usize,
+    before_init: usize,
+    logger_panics: usize,
+}
+
+impl Diagnostics {
+    /// The number of records submitted by the logging macros.
+    ///
+    /// Records are only counted after [`enable_diagnostics`] is called.
+    ///
+    /// [`enable_diagnostics`]: fn.enable_diagnostics.html
+    pub fn submitted(&self) -> usize {
+        self.submitted
+    }
+
+    /// The number of records that were dropped because no logger was installed.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// The number of records submitted before the logger was initialized.
+    ///
+    /// This includes records submitted while [`set_logger`] was still running.
+    ///
+    /// [`set_logger`]: fn.set_logger.html
+    pub fn before_init(&self) -> usize {
+        self.before_init
+    }
+
+    /// The number of panics raised by the logger that were caught.
+    ///
+    /// Panics are only caught once a handler is set with [`set_failure_handler`].
+    ///
+    /// [`set_failure_handler`]: fn.set_failure_handler.html
+    pub fn logger_panics(&self) -> usize {
+        self.logger_panics
+    }
+}
@@ -0,0 +1,355 @@
+//! Buffering of records created before a logger is installed.
+//!
+//! Records created by the logging macros before [`set_logger`] or
+//! [`set_boxed_logger`] is called would normally be discarded. With the
+//! `early_buffer` feature enabled, they're copied into a bounded buffer instead,
+//! and replayed to the logger when it's installed. If the buffer fills up then
+//! the oldest records are discarded to make room for new ones.
+//!
+//! Records are still subject to the maximum log level, which defaults to
+//! [`Off`], so it needs to be raised early for any records to be buffered.
+//!
+//! Buffered records are replayed in the order they were created. Records created
+//! on other threads while the buffer is being replayed may be logged before it
+//! finishes.
+//!
+//! Buffering can also be configured with [`set_pre_init_policy`].
+//!
+//! Requires the `early_buffer` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! # use log::{Log, Metadata, Record};
+//! # struct MyLogger;
+//! # impl Log for MyLogger {
+//! #     fn enabled(&self, _: &Metadata) -> bool { true }
+//! #     fn log(&self, _: &Record) {}
+//! #     fn flush(&self) {}
+//! # }
+//! # static MY_LOGGER: MyLogger = MyLogger;
+//! # fn main() {
+//! log::early_buffer::set_capacity(64);
+//! log::set_max_level(log::LevelFilter::Info);
+//!
+//! log::info!("starting up");
+//!
+//! // The "starting up" record is passed to `MY_LOGGER` now
+//! log::set_logger(&MY_LOGGER).unwrap();
+//! # }
+//! ```
+//!
+//! [`set_logger`]: ../fn.set_logger.html
+//! [`set_boxed_logger`]: ../fn.set_boxed_logger.html
+//! [`set_pre_init_policy`]: ../fn.set_pre_init_policy.html
+//! [`Off`]: ../enum.LevelFilter.html#variant.Off
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::lazy_global::LazyGlobal;
+use crate::{Level, Log, MetadataExtras, Record, RecordKind, TargetHandle};
+
+/// The number of records buffered by default.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+
+// Set once the buffer has been replayed, after which records are no longer buffered.
+static REPLAYED: AtomicBool = AtomicBool::new(false);
+
+// The buffer is allocated the first time it's needed and then lives for the
+// rest of the program.
+static BUFFER: LazyGlobal<Mutex<Buffer>> = LazyGlobal::new();
+
+/// Set the maximum number of records to buffer.
+///
+/// If more than `capacity` records are already buffered then the oldest ones
+/// are discarded. A capacity of `0` disables buffering.
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+
+    let mut buffer = lock();
+    while buffer.records.len() > capacity {
+        buffer.records.pop_front();
+        crate::diagnostics::record_dropped();
+    }
+}
+
+/// The maximum number of records to buffer.
+#[inline]
+pub(crate) fn capacity() -> usize {
+    CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Whether records created now would be buffered.
+#[inline]
+pub(crate) fn is_enabled() -> bool {
+    CAPACITY.load(Ordering::Relaxed) > 0 && !REPLAYED.load(Ordering::Relaxed)
+}
+
+/// Buffer a copy of `record` until a logger is installed.
+///
+/// If the buffer has already been replayed then `record` is logged immediately.
+pub(crate) fn push(record: &Record) {
+    let owned = OwnedRecord::new(record);
+
+    let mut buffer = lock();
+    if buffer.replayed {
+        drop(buffer);
+        crate::dispatch(crate::logger(), record);
+        return;
+    }
+
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        crate::diagnostics::record_dropped();
+        return;
+    }
+
+    while buffer.records.len() >= capacity {
+        buffer.records.pop_front();
+        crate::diagnostics::record_dropped();
+    }
+    buffer.records.push_back(owned);
+}
+
+/// Replay any buffered records to `logger`.
+pub(crate) fn replay(logger: &dyn Log) {
+    REPLAYED.store(true, Ordering::Relaxed);
+
+    let records = {
+        let mut buffer = lock();
+        buffer.replayed = true;
+        std::mem::take(&mut buffer.records)
+    };
+
+    // Call into the logger without holding the lock, in case it logs itself
+    for record in records {
+        record.log(logger);
+    }
+}
+
+fn lock() -> MutexGuard<'static, Buffer> {
+    buffer().lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn buffer() -> &'static Mutex<Buffer> {
+    BUFFER.get_or_init(|| {
+        Mutex::new(Buffer {
+            records: VecDeque::new(),
+            replayed: false,
+        })
+    })
+}
+
+struct Buffer {
+    records: VecDeque<OwnedRecord>,
+    replayed: bool,
+}
+
+struct OwnedRecord {
+    level: Level,
+    target: String,
+    target_handle: Option<TargetHandle>,
+    kind: RecordKind,
+    audit: Option<String>,
+    sample_rate: Option<f64>,
+    dedup_key: Option<String>,
+    msg_id: Option<&'static str>,
+    message: String,
+    module_path: Option<&'static str>,
+    file: Option<&'static str>,
+    line: Option<u32>,
+    column: Option<u32>,
+    #[cfg(feature = "correlation")]
+    correlation_id: Option<u64>,
+    #[cfg(feature = "timestamps")]
+    timestamp: Option<u64>,
+    #[cfg(feature = "seq")]
+    seq: Option<u64>,
+    #[cfg(feature = "kv")]
+    key_values: Vec<(String, String)>,
+}
+
+impl OwnedRecord {
+    fn new(record: &Record) -> Self {
+        OwnedRecord {
+            level: record.level(),
+            target: record.target().into(),
+            target_handle: record.metadata().target_handle(),
+            kind: record.metadata().kind(),
+            audit: record.metadata().audit_name().map(Into::into),
+            sample_rate: record.metadata().sample_rate(),
+            dedup_key: record.metadata().dedup_key().map(Into::into),
+            msg_id: record.metadata().msg_id(),
+            message: record.args().to_string(),
+            module_path: record.module_path_static(),
+            file: record.file_static(),
+            line: record.line(),
+            column: record.column(),
+            #[cfg(feature = "correlation")]
+            correlation_id: record.correlation_id(),
+            #[cfg(feature = "timestamps")]
+            timestamp: record.timestamp(),
+            #[cfg(feature = "seq")]
+            seq: record.seq(),
+            #[cfg(feature = "kv")]
+            key_values: kv_support::to_owned(record.key_values()),
+        }
+    }
+
+    fn log(&self, logger: &dyn Log) {
+        self.log_args(logger, format_args!("{}", self.message));
+    }
+
+    fn log_args(&self, logger: &dyn Log, args: fmt::Arguments) {
+        let mut extras = MetadataExtras::new();
+        extras
+            .target_handle(self.target_handle)
+            .kind(self.kind)
+            .audit(self.audit.as_deref())
+            .sample_rate(self.sample_rate)
+            .dedup_key(self.dedup_key.as_deref())
+            .msg_id(self.msg_id);
+
+        let mut builder = Record::builder();
+
+        builder
+            .args(args)
+            .level(self.level)
+            .target(&self.target)
+            .extras(Some(&extras).filter(|extras| !extras.is_empty()))
+            .module_path_static(self.module_path)
+            .file_static(self.file)
+            .line(self.line)
+            .column(self.column);
+
+        #[cfg(feature = "correlation")]
+        builder.correlation_id(self.correlation_id);
+
+        #[cfg(feature = "timestamps")]
+        builder.timestamp(self.timestamp);
+
+        #[cfg(feature = "seq")]
+        builder.seq(self.seq);
+
+        #[cfg(feature = "kv")]
+        let key_values = kv_support::Owned(&self.key_values);
+        #[cfg(feature = "kv")]
+        builder.key_values(&key_values);
+
+        crate::dispatch(logger, &builder.build());
+    }
+}
+
+#[cfg(feature = "kv")]
+mod kv_support {
+    use crate::kv::{Error, Key, Source, Value, VisitSource};
+
+    // Key-values are buffered as strings, since arbitrary values can't be owned
+    pub(super) fn to_owned(source: &dyn Source) -> Vec<(String, String)> {
+        struct Collect(Vec<(String, String)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key.as_str().into(), value.to_string()));
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        let _ = source.visit(&mut collect);
+        collect.0
+    }
+
+    // Replays buffered key-values. `String` only implements `ToKey` and
+    // `ToValue` with the `kv_std` feature, so they're borrowed as `str`s
+    pub(super) struct Owned<'a>(pub(super) &'a [(String, String)]);
+
+    impl<'a> Source for Owned<'a> {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            for (key, value) in self.0 {
+                visitor.visit_pair(Key::from_str(key), Value::from(&**value))?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Metadata;
+
+    // The level, target, message and line of each record
+    type Captured = (Level, String, String, Option<u32>);
+
+    struct Capture(Mutex<Vec<Captured>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push((
+                record.level(),
+                record.target().into(),
+                record.args().to_string(),
+                record.line(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn owned_record_round_trip() {
+        let owned = OwnedRecord::new(
+            &Record::builder()
+                .args(format_args!("hello {}", "world"))
+                .level(Level::Warn)
+                .target("myApp")
+                .line(Some(42))
+                .build(),
+        );
+
+        let logger = Capture(Mutex::new(Vec::new()));
+        owned.log(&logger);
+
+        assert_eq!(
+            vec![(Level::Warn, "myApp".into(), "hello world".into(), Some(42))],
+            *logger.0.lock().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(target_has_atomic = "ptr")]
+    fn replay_catches_logger_panics() {
+        struct Panicking;
+
+        impl Log for Panicking {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _: &Record) {
+                panic!("the logger failed");
+            }
+
+            fn flush(&self) {}
+        }
+
+        fn handler(_: &crate::LoggerFailure) {}
+
+        let owned = OwnedRecord::new(&Record::builder().build());
+
+        crate::set_failure_handler(handler);
+        owned.log(&Panicking);
+        crate::clear_failure_handler();
+    }
+}
@@ -0,0 +1,132 @@
+//! Typed data attached to records.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+type AnyMap = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+/// A map of typed data that can be attached to a [`Record`].
+///
+/// Loggers that wrap other loggers can use extensions to pass data like span
+/// contexts or sampling decisions to the loggers they wrap, without turning it
+/// into key-values. Each type can be stored once, and is retrieved by its type.
+///
+/// Requires the `extensions` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Extensions, Record};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct SpanId(u64);
+///
+/// let mut extensions = Extensions::new();
+/// extensions.insert(SpanId(42));
+///
+/// let record = Record::builder().extensions(&extensions).build();
+///
+/// assert_eq!(Some(&SpanId(42)), record.extensions().get::<SpanId>());
+/// ```
+///
+/// [`Record`]: struct.Record.html
+#[derive(Default)]
+pub struct Extensions {
+    // Boxed so an empty map can be created in a constant
+    map: Option<Box<AnyMap>>,
+}
+
+// Returned by records that don't have any extensions. `new` can't be a
+// `const fn` on the crate's MSRV, since the map holds trait objects
+pub(crate) static EMPTY: Extensions = Extensions { map: None };
+
+impl Extensions {
+    /// Create an empty map.
+    pub fn new() -> Self {
+        Extensions { map: None }
+    }
+
+    /// Insert a value, returning the previous value of the same type, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map
+            .get_or_insert_with(Default::default)
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.downcast().ok().map(|previous| *previous))
+    }
+
+    /// Get a reference to the value of type `T`, if there is one.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.map.as_ref()?.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Get a mutable reference to the value of type `T`, if there is one.
+    pub fn get_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.map
+            .as_mut()?
+            .get_mut(&TypeId::of::<T>())?
+            .downcast_mut()
+    }
+
+    /// Remove the value of type `T`, returning it if there was one.
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.map
+            .as_mut()?
+            .remove(&TypeId::of::<T>())?
+            .downcast()
+            .ok()
+            .map(|value| *value)
+    }
+
+    /// Remove all values.
+    pub fn clear(&mut self) {
+        if let Some(map) = &mut self.map {
+            map.clear();
+        }
+    }
+
+    /// Whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of values in the map.
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut extensions = Extensions::new();
+        assert!(extensions.is_empty());
+        assert_eq!(None, extensions.get::<u32>());
+
+        assert_eq!(None, extensions.insert(1u32));
+        assert_eq!(Some(1), extensions.insert(2u32));
+        extensions.insert("a");
+        assert_eq!(2, extensions.len());
+
+        *extensions.get_mut::<u32>().unwrap() += 1;
+        assert_eq!(Some(&3), extensions.get::<u32>());
+        assert_eq!(Some(&"a"), extensions.get::<&str>());
+
+        assert_eq!(Some(3), extensions.remove::<u32>());
+        assert_eq!(None, extensions.get::<u32>());
+
+        extensions.clear();
+        assert!(extensions.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Handling panics raised by the logger.
+
+use std::any::Any;
+use std::fmt;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::{Level, Log, Record};
+
+// The installed `fn(&LoggerFailure)`, or null if there isn't one
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Set a handler for panics raised by the logger.
+///
+/// By default, if [`Log::log`] panics the panic unwinds into the code that
+/// logged the record. Once a handler is set, records created by the logging
+/// macros and passed to [`log_record`] are instead logged inside
+/// `catch_unwind`, and a panic is passed to the handler and counted in
+/// [`Diagnostics::logger_panics`]. The code that logged the record carries on.
+/// This includes the summaries logged by [`dedup`] and records replayed from
+/// the early buffer.
+///
+/// The panic hook still runs before the panic is caught, so the panic message
+/// is printed as usual unless the hook is replaced. Panics aren't caught when
+/// the crate is compiled with `panic = "abort"`.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::LoggerFailure;
+///
+/// fn report(failure: &LoggerFailure) {
+///     eprintln!(
+///         "the logger panicked on a {} record for `{}`: {}",
+///         failure.level(),
+///         failure.target(),
+///         failure.message().unwrap_or("unknown panic"),
+///     );
+/// }
+///
+/// log::set_failure_handler(report);
+/// ```
+///
+/// [`Log::log`]: trait.Log.html#tymethod.log
+/// [`log_record`]: fn.log_record.html
+/// [`dedup`]: dedup/index.html
+/// [`Diagnostics::logger_panics`]: struct.Diagnostics.html#method.logger_panics
+pub fn set_failure_handler(handler: fn(&LoggerFailure)) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+/// Remove the handler set by [`set_failure_handler`], so panics raised by the
+/// logger unwind into the code that logged the record again.
+///
+/// Requires the `std` feature.
+///
+/// [`set_failure_handler`]: fn.set_failure_handler.html
+pub fn clear_failure_handler() {
+    HANDLER.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Pass a record to the logger, catching any panic if a handler is set.
+#[inline]
+pub(crate) fn log(logger: &dyn Log, record: &Record) {
+    let handler = HANDLER.load(Ordering::Acquire);
+    if handler.is_null() {
+        logger.log(record);
+        return;
+    }
+
+    // SAFETY: the only non-null values stored are `fn(&LoggerFailure)`s
+    let handler = unsafe { mem::transmute::<*mut (), fn(&LoggerFailure)>(handler) };
+
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| logger.log(record))) {
+        crate::diagnostics::record_logger_panic();
+
+        handler(&LoggerFailure {
+            payload: &*payload,
+            level: record.level(),
+            target: record.target(),
+        });
+    }
+}
+
+/// A panic raised by the logger, passed to the handler set by
+/// [`set_failure_handler`].
+///
+/// [`set_failure_handler`]: fn.set_failure_handler.html
+pub struct LoggerFailure<'a> {
+    payload: &'a (dyn Any + Send),
+    level: Level,
+    target: &'a str,
+}
+
+impl<'a> LoggerFailure<'a> {
+    /// The payload the logger panicked with.
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        self.payload
+    }
+
+    /// The panic message, if the logger panicked with a string.
+    ///
+    /// This is the case for panics raised by `panic!` and friends.
+    pub fn message(&self) -> Option<&str> {
+        if let Some(message) = self.payload.downcast_ref::<&'static str>() {
+            Some(message)
+        } else {
+            self.payload
+                .downcast_ref::<String>()
+                .map(|message| &**message)
+        }
+    }
+
+    /// The level of the record that was being logged.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The target of the record that was being logged.
+    pub fn target(&self) -> &str {
+        self.target
+    }
+}
+
+impl<'a> fmt::Debug for LoggerFailure<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LoggerFailure")
+            .field("message", &self.message())
+            .field("level", &self.level)
+            .field("target", &self.target)
+            .finish()
+    }
+}
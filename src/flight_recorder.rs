@@ -0,0 +1,337 @@
+//! A fixed-capacity ring buffer of recent records.
+//!
+//! A [`FlightRecorder`] is a logger that keeps owned copies of the last few
+//! records it's given, discarding the oldest ones as new ones arrive. Its
+//! contents can be retrieved with [`FlightRecorder::snapshot`], which is useful
+//! for finding out what happened right before a crash. [`dump_on_panic`]
+//! installs a panic hook that writes the recorded records to standard error
+//! before the panic message.
+//!
+//! Writers claim a slot in the buffer with an atomic counter, so they only
+//! contend with each other when the buffer wraps around before a write has
+//! finished.
+//!
+//! Requires the `flight_recorder` feature.
+//!
+//! # Examples
+//!
+//! The simplest way to get started is [`init`], which installs a recorder as
+//! the global logger and dumps it on panic:
+//!
+//! ```
+//! let recorder = log::flight_recorder::init(256).unwrap();
+//!
+//! log::info!("connecting to {}", "db.local");
+//! log::warn!("connection refused");
+//!
+//! let records = recorder.snapshot();
+//! assert_eq!("connection refused", records.last().unwrap().message());
+//! ```
+//!
+//! [`FlightRecorder::snapshot`]: struct.FlightRecorder.html#method.snapshot
+//! [`dump_on_panic`]: fn.dump_on_panic.html
+//! [`init`]: fn.init.html
+
+use std::fmt;
+use std::io::{self, Write};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// A logger that keeps the most recent records in a ring buffer.
+///
+/// See the [module documentation](index.html) for details.
+pub struct FlightRecorder {
+    next: AtomicUsize,
+    slots: Box<[Mutex<Option<Slot>>]>,
+}
+
+struct Slot {
+    index: usize,
+    record: FlightRecord,
+}
+
+impl FlightRecorder {
+    /// Create a recorder that keeps the last `capacity` records.
+    ///
+    /// A recorder with a capacity of `0` doesn't keep any records.
+    pub fn new(capacity: usize) -> Self {
+        FlightRecorder {
+            next: AtomicUsize::new(0),
+            slots: (0..capacity).map(|_| Mutex::new(None)).collect(),
+        }
+    }
+
+    /// The maximum number of records kept.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Get copies of the recorded records, from oldest to newest.
+    ///
+    /// Records that are being written while the snapshot is taken are left
+    /// out, so taking a snapshot never blocks. This makes it safe to call
+    /// from a panic hook.
+    pub fn snapshot(&self) -> Vec<FlightRecord> {
+        let mut slots = self
+            .slots
+            .iter()
+            .filter_map(|slot| match slot.try_lock() {
+                Ok(slot) => slot.as_ref().map(|slot| (slot.index, slot.record.clone())),
+                Err(std::sync::TryLockError::Poisoned(slot)) => slot
+                    .into_inner()
+                    .as_ref()
+                    .map(|slot| (slot.index, slot.record.clone())),
+                Err(std::sync::TryLockError::WouldBlock) => None,
+            })
+            .collect::<Vec<_>>();
+
+        slots.sort_by_key(|(index, _)| *index);
+        slots.into_iter().map(|(_, record)| record).collect()
+    }
+
+    /// Write the recorded records to `w`, one per line, from oldest to newest.
+    pub fn dump(&self, mut w: impl Write) -> io::Result<()> {
+        for record in self.snapshot() {
+            writeln!(w, "{}", record)?;
+        }
+
+        w.flush()
+    }
+}
+
+impl fmt::Debug for FlightRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlightRecorder")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Log for FlightRecorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        !self.slots.is_empty()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.slots.is_empty() {
+            return;
+        }
+
+        // Format the record before claiming a slot, so the slot is held as
+        // briefly as possible
+        let record = FlightRecord::new(record);
+
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut slot = self.slots[index % self.slots.len()]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // A writer that claimed the slot on an earlier lap may arrive after
+        // a newer one, in which case the newer record is kept
+        if slot.as_ref().map_or(true, |slot| slot.index < index) {
+            *slot = Some(Slot { index, record });
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// An owned copy of a record kept by a [`FlightRecorder`].
+///
+/// Only the parts of a record that are useful for diagnostics are kept. The
+/// message is formatted when the record is logged.
+///
+/// Formatting a record with `Display` produces a line like
+/// `WARN my_app::db: connection refused (src/db.rs:42)`.
+///
+/// [`FlightRecorder`]: struct.FlightRecorder.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlightRecord {
+    level: Level,
+    target: String,
+    message: String,
+    file: Option<String>,
+    line: Option<u32>,
+    #[cfg(feature = "kv")]
+    key_values: Vec<(String, String)>,
+}
+
+impl FlightRecord {
+    fn new(record: &Record) -> Self {
+        FlightRecord {
+            level: record.level(),
+            target: record.target().into(),
+            message: record.render().into_owned(),
+            file: record.file().map(Into::into),
+            line: record.line(),
+            #[cfg(feature = "kv")]
+            key_values: kv_support::to_owned(record.key_values()),
+        }
+    }
+
+    /// The level of the record.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The target of the record.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The formatted message of the record.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The source file the record was created in, if it's known.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// The line the record was created on, if it's known.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// The key-values of the record, formatted as strings.
+    #[cfg(feature = "kv")]
+    pub fn key_values(&self) -> &[(String, String)] {
+        &self.key_values
+    }
+}
+
+impl fmt::Display for FlightRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)?;
+
+        #[cfg(feature = "kv")]
+        for (key, value) in &self.key_values {
+            write!(f, " {}={}", key, value)?;
+        }
+
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, " ({}:{})", file, line),
+            (Some(file), None) => write!(f, " ({})", file),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Install a panic hook that writes the records kept by `recorder` to
+/// standard error.
+///
+/// The records are written before calling the previously installed hook, so
+/// they appear just above the panic message.
+pub fn dump_on_panic(recorder: &'static FlightRecorder) {
+    let previous = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        let records = recorder.snapshot();
+
+        let stderr = io::stderr();
+        let mut stderr = stderr.lock();
+
+        let _ = writeln!(stderr, "last {} log records:", records.len());
+        for record in records {
+            let _ = writeln!(stderr, "{}", record);
+        }
+        drop(stderr);
+
+        previous(info);
+    }));
+}
+
+/// Install a flight recorder keeping the last `capacity` records as the
+/// global logger.
+///
+/// This also sets the maximum log level to `Trace`, so every record is kept,
+/// and calls [`dump_on_panic`] with the recorder.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+///
+/// [`dump_on_panic`]: fn.dump_on_panic.html
+pub fn init(capacity: usize) -> Result<&'static FlightRecorder, SetLoggerError> {
+    let recorder: &'static FlightRecorder = Box::leak(Box::new(FlightRecorder::new(capacity)));
+
+    crate::set_logger(recorder)?;
+    crate::set_max_level(LevelFilter::Trace);
+    dump_on_panic(recorder);
+
+    Ok(recorder)
+}
+
+#[cfg(feature = "kv")]
+mod kv_support {
+    use crate::kv::{Error, Key, Source, Value, VisitSource};
+
+    // Key-values are kept as strings, since arbitrary values can't be owned
+    pub(super) fn to_owned(source: &dyn Source) -> Vec<(String, String)> {
+        struct Collect(Vec<(String, String)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key.as_str().into(), value.to_string()));
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        let _ = source.visit(&mut collect);
+        collect.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(recorder: &FlightRecorder, message: &str) {
+        recorder.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Info)
+                .target("flight")
+                .file(Some("src/main.rs"))
+                .line(Some(7))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn keeps_last_records() {
+        let recorder = FlightRecorder::new(3);
+
+        for message in ["a", "b", "c", "d", "e"] {
+            log(&recorder, message);
+        }
+
+        let messages = recorder
+            .snapshot()
+            .iter()
+            .map(|record| record.message().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["c", "d", "e"], messages);
+
+        let mut dump = Vec::new();
+        recorder.dump(&mut dump).unwrap();
+        assert!(String::from_utf8(dump)
+            .unwrap()
+            .starts_with("INFO flight: c (src/main.rs:7)\n"));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let recorder = FlightRecorder::new(0);
+
+        log(&recorder, "a");
+
+        assert!(!recorder.enabled(&Metadata::builder().build()));
+        assert!(recorder.snapshot().is_empty());
+    }
+}
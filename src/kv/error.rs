@@ -82,6 +82,47 @@ mod std_support {
                 inner: Inner::Boxed(err.into()),
             }
         }
+
+        /// Create an error from a formatted message.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use log::kv::Error;
+        ///
+        /// let err = Error::custom(format_args!("unexpected key `{}`", "id"));
+        ///
+        /// assert_eq!("unexpected key `id`", err.to_string());
+        /// ```
+        pub fn custom(msg: impl fmt::Display) -> Self {
+            Error::boxed(msg.to_string())
+        }
+
+        /// Convert this error into an `io::Error`.
+        ///
+        /// Errors that were created from an `io::Error` are converted back into
+        /// it, so its kind is preserved. Other errors have a kind of
+        /// `io::ErrorKind::Other`.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::io;
+        /// use log::kv::Error;
+        ///
+        /// let err = Error::from(io::Error::from(io::ErrorKind::BrokenPipe));
+        ///
+        /// assert_eq!(io::ErrorKind::BrokenPipe, err.into_io().kind());
+        /// ```
+        pub fn into_io(self) -> io::Error {
+            match self.inner {
+                Inner::Boxed(err) => match err.downcast::<io::Error>() {
+                    Ok(err) => *err,
+                    Err(err) => io::Error::new(io::ErrorKind::Other, err),
+                },
+                _ => io::Error::new(io::ErrorKind::Other, self),
+            }
+        }
     }
 
     impl error::Error for Error {}
@@ -91,4 +132,10 @@ mod std_support {
             Error::boxed(err)
         }
     }
+
+    impl From<Error> for io::Error {
+        fn from(err: Error) -> Self {
+            err.into_io()
+        }
+    }
 }
@@ -0,0 +1,580 @@
+//! Flattening nested key-values.
+
+use std::error;
+use std::fmt;
+
+use serde::ser::{self, Serialize, Serializer};
+
+use crate::kv::{Error, Key, Source, ToValue, Value, VisitSource};
+
+/// Flatten the key-values in a source, so values that are maps or structs
+/// are visited as one key-value per field.
+///
+/// The key of each field is the key of the value it belongs to, followed by
+/// the separator and the name of the field. Fields that are maps or structs
+/// themselves are flattened too. Other values, including sequences, are
+/// visited as they are.
+///
+/// Nested values are only detected if they were captured using `serde`.
+/// Values captured using `Display`, `Debug`, or `sval` are never flattened.
+///
+/// The keys of flattened fields are built while the source is visited, so
+/// they can't borrow from it. That means the returned [`Flatten`] isn't itself
+/// a [`Source`], and can only be visited by a [`VisitSource`] that accepts
+/// key-values of any lifetime.
+///
+/// Requires the `kv_serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), log::kv::Error> {
+/// use log::kv::{self, Key, Value, VisitSource};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: &'static str,
+///     id: u64,
+/// }
+///
+/// struct Collect(Vec<String>);
+///
+/// impl<'kvs> VisitSource<'kvs> for Collect {
+///     fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+///         self.0.push(format!("{key}={value}"));
+///         Ok(())
+///     }
+/// }
+///
+/// let user = User { name: "ferris", id: 7 };
+/// let source = [("user", Value::from_serde(&user)), ("active", Value::from(true))];
+///
+/// let mut collect = Collect(Vec::new());
+/// kv::flatten(&source, ".").visit(&mut collect)?;
+///
+/// assert_eq!(vec!["user.name=ferris", "user.id=7", "active=true"], collect.0);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Flatten`]: struct.Flatten.html
+/// [`Source`]: trait.Source.html
+/// [`VisitSource`]: trait.VisitSource.html
+pub fn flatten<'a, S: Source + ?Sized>(source: &'a S, separator: &'a str) -> Flatten<'a, S> {
+    Flatten { source, separator }
+}
+
+/// The key-values in a source, with nested values flattened.
+///
+/// This type is returned by [`flatten`]. See its documentation for details.
+///
+/// [`flatten`]: fn.flatten.html
+pub struct Flatten<'a, S: ?Sized> {
+    source: &'a S,
+    separator: &'a str,
+}
+
+impl<'a, S: Source + ?Sized> Flatten<'a, S> {
+    /// Visit the flattened key-values.
+    pub fn visit(&self, visitor: &mut dyn for<'v> VisitSource<'v>) -> Result<(), Error> {
+        self.source.visit(&mut Pairs {
+            separator: self.separator,
+            visitor,
+        })
+    }
+}
+
+impl<'a, S: ?Sized> fmt::Debug for Flatten<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Flatten")
+            .field("separator", &self.separator)
+            .finish_non_exhaustive()
+    }
+}
+
+struct Pairs<'a> {
+    separator: &'a str,
+    visitor: &'a mut dyn for<'v> VisitSource<'v>,
+}
+
+impl<'a, 'kvs> VisitSource<'kvs> for Pairs<'a> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        let mut out = Out {
+            separator: self.separator,
+            path: String::from(key.as_str()),
+            visitor: &mut *self.visitor,
+            error: None,
+        };
+
+        let result = value.serialize(Field {
+            out: &mut out,
+            value: &value,
+            nested: false,
+        });
+
+        // Errors are passed back through `serde`, which may only keep their
+        // message, so the original error from the visitor is kept aside
+        result.map_err(|err| out.error.take().unwrap_or(err.0))
+    }
+}
+
+struct Out<'a> {
+    separator: &'a str,
+    // The key of the field being serialized
+    path: String,
+    visitor: &'a mut dyn for<'v> VisitSource<'v>,
+    error: Option<Error>,
+}
+
+impl<'a> Out<'a> {
+    fn visit(&mut self, value: Value) -> Result<(), FlattenError> {
+        self.visitor
+            .visit_pair(Key::from_str(&self.path), value)
+            .map_err(|err| {
+                self.error = Some(err);
+                ser::Error::custom("failed to visit a flattened key-value")
+            })
+    }
+}
+
+// A serializer for a single field, which visits it as a pair unless it's a
+// map or struct
+//
+// Top-level values that aren't flattened are visited as the `Value` they were
+// captured as, rather than as the primitives they serialize as. That keeps the
+// original representation of values captured using `Display` or as errors.
+// Nested primitives are visited as themselves, since a `Value` captured using
+// `serde` formats strings with quotes
+struct Field<'a, 'b, 'v> {
+    out: &'a mut Out<'b>,
+    value: &'a Value<'v>,
+    nested: bool,
+}
+
+impl<'a, 'b, 'v> Field<'a, 'b, 'v> {
+    fn visit(self) -> Result<(), FlattenError> {
+        self.out.visit(self.value.to_value())
+    }
+
+    fn visit_primitive(self, value: Value) -> Result<(), FlattenError> {
+        if self.nested {
+            self.out.visit(value)
+        } else {
+            self.visit()
+        }
+    }
+
+    fn flatten(self) -> Fields<'a, 'b> {
+        Fields {
+            len: self.out.path.len(),
+            out: self.out,
+        }
+    }
+}
+
+// The fields of a map or struct
+struct Fields<'a, 'b> {
+    out: &'a mut Out<'b>,
+    // The length of the key of the map or struct itself
+    len: usize,
+}
+
+impl<'a, 'b> Fields<'a, 'b> {
+    fn key(&mut self, name: &str) {
+        self.out.path.truncate(self.len);
+        self.out.path.push_str(self.out.separator);
+        self.out.path.push_str(name);
+    }
+
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FlattenError> {
+        let value = Ref(value);
+        let value = Value::from_serde(&value);
+        let result = value.serialize(Field {
+            out: &mut *self.out,
+            value: &value,
+            nested: true,
+        });
+
+        self.out.path.truncate(self.len);
+        result
+    }
+}
+
+impl<'a, 'b> ser::SerializeMap for Fields<'a, 'b> {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), FlattenError> {
+        let key = Ref(key);
+        let key = Value::from_serde(&key);
+
+        match key.to_cow_str() {
+            Some(key) => self.key(&key),
+            None => self.key(&key.to_string()),
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b> ser::SerializeStruct for Fields<'a, 'b> {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.key(key);
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl<'a, 'b> ser::SerializeStructVariant for Fields<'a, 'b> {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.key(key);
+        self.value(value)
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+macro_rules! visit_primitive {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<(), FlattenError> {
+                self.visit_primitive(Value::from(value))
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, 'v> Serializer for Field<'a, 'b, 'v> {
+    type Ok = ();
+    type Error = FlattenError;
+
+    type SerializeSeq = Skip;
+    type SerializeTuple = Skip;
+    type SerializeTupleStruct = Skip;
+    type SerializeTupleVariant = Skip;
+    type SerializeMap = Fields<'a, 'b>;
+    type SerializeStruct = Fields<'a, 'b>;
+    type SerializeStructVariant = Fields<'a, 'b>;
+
+    visit_primitive! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), FlattenError> {
+        self.visit()
+    }
+
+    fn serialize_none(self) -> Result<(), FlattenError> {
+        self.visit_primitive(Value::null())
+    }
+
+    fn serialize_unit(self) -> Result<(), FlattenError> {
+        self.visit_primitive(Value::null())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), FlattenError> {
+        self.visit_primitive(Value::null())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), FlattenError> {
+        self.visit_primitive(Value::from(variant))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), FlattenError> {
+        self.visit()
+    }
+
+    // Sequences aren't flattened, so they're visited as a whole and their
+    // elements are skipped
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Skip, FlattenError> {
+        self.visit().map(|()| Skip)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Skip, FlattenError> {
+        self.visit().map(|()| Skip)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Skip, FlattenError> {
+        self.visit().map(|()| Skip)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Skip, FlattenError> {
+        self.visit().map(|()| Skip)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Fields<'a, 'b>, FlattenError> {
+        Ok(self.flatten())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Fields<'a, 'b>, FlattenError> {
+        Ok(self.flatten())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Fields<'a, 'b>, FlattenError> {
+        Ok(self.flatten())
+    }
+}
+
+// The elements of a sequence, which are skipped
+struct Skip;
+
+impl ser::SerializeSeq for Skip {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, _: &T) -> Result<(), FlattenError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Skip {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, _: &T) -> Result<(), FlattenError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Skip {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &T) -> Result<(), FlattenError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Skip {
+    type Ok = ();
+    type Error = FlattenError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &T) -> Result<(), FlattenError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+// Lets unsized values be captured as a `Value`
+struct Ref<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: Serialize + ?Sized> Serialize for Ref<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+struct FlattenError(Error);
+
+impl fmt::Debug for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for FlattenError {}
+
+impl ser::Error for FlattenError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FlattenError(Error::boxed(msg.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Collect {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    fn collect(source: &dyn Source, separator: &str) -> Vec<(String, String)> {
+        let mut collect = Collect(Vec::new());
+        flatten(source, separator).visit(&mut collect).unwrap();
+
+        collect.0
+    }
+
+    #[test]
+    fn nested() {
+        #[derive(Serialize)]
+        struct User {
+            name: &'static str,
+            address: Address,
+            roles: Vec<&'static str>,
+            manager: Option<Box<User>>,
+        }
+
+        #[derive(Serialize)]
+        struct Address {
+            city: &'static str,
+        }
+
+        let user = User {
+            name: "ferris",
+            address: Address { city: "Berlin" },
+            roles: vec!["admin"],
+            manager: None,
+        };
+
+        let mut map = BTreeMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+
+        let pairs = collect(
+            &[
+                ("user", Value::from_serde(&user)),
+                ("map", Value::from_serde(&map)),
+                ("id", Value::from(7)),
+                ("display", Value::from_display(&"shown")),
+            ],
+            "_",
+        );
+
+        let expected = [
+            ("user_name", "ferris"),
+            ("user_address_city", "Berlin"),
+            ("user_roles", "[\"admin\"]"),
+            ("user_manager", "None"),
+            ("map_a", "1"),
+            ("map_b", "2"),
+            ("id", "7"),
+            ("display", "shown"),
+        ];
+
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>(),
+            pairs
+        );
+    }
+
+    #[test]
+    fn error() {
+        struct Fail;
+
+        impl<'kvs> VisitSource<'kvs> for Fail {
+            fn visit_pair(&mut self, _: Key<'kvs>, _: Value<'kvs>) -> Result<(), Error> {
+                Err(Error::msg("failed"))
+            }
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert("a", 1);
+
+        let source = [("map", Value::from_serde(&map))];
+        let err = flatten(&source, ".").visit(&mut Fail).unwrap_err();
+
+        assert_eq!("failed", err.to_string());
+    }
+}
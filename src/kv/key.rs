@@ -1,7 +1,9 @@
 //! Structured keys.
 
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 /// A type that can be converted into a [`Key`](struct.Key.html).
 pub trait ToKey {
@@ -20,7 +22,7 @@ where
 
 impl<'k> ToKey for Key<'k> {
     fn to_key(&self) -> Key {
-        Key { key: self.key }
+        self.clone()
     }
 }
 
@@ -31,18 +33,63 @@ impl ToKey for str {
 }
 
 /// A key in a key-value.
+///
+/// Keys are compared, ordered, and hashed by their string value only, in the same
+/// way as `str`. A key created with [`Key::from_static`] is equal to a key with
+/// the same string value created with [`Key::from_str`].
 // These impls must only be based on the as_str() representation of the key
 // If a new field (such as an optional index) is added to the key they must not affect comparison
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone)]
 pub struct Key<'k> {
     // NOTE: This may become `Cow<'k, str>`
-    key: &'k str,
+    key: KeyStr<'k>,
+}
+
+#[derive(Clone)]
+enum KeyStr<'k> {
+    Static(&'static str),
+    Borrowed(&'k str),
+    #[cfg(feature = "kv_std")]
+    Buffered(Box<str>),
 }
 
 impl<'k> Key<'k> {
     /// Get a key from a borrowed string.
     pub fn from_str(key: &'k str) -> Self {
-        Key { key }
+        Key {
+            key: KeyStr::Borrowed(key),
+        }
+    }
+
+    /// Get a key from a `'static` string.
+    ///
+    /// The key remembers that its string is `'static`, so it can be retrieved
+    /// later using [`Key::to_static_str`]. Sinks can use this to cache state
+    /// for a key, using the address of the string as its identity, instead of
+    /// hashing the string on every record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Key;
+    ///
+    /// const KEY: Key<'static> = Key::from_static("request_id");
+    ///
+    /// assert_eq!(Some("request_id"), KEY.to_static_str());
+    /// assert_eq!(KEY, Key::from_str("request_id"));
+    /// ```
+    pub const fn from_static(key: &'static str) -> Self {
+        Key {
+            key: KeyStr::Static(key),
+        }
+    }
+
+    /// Get a key that owns its string.
+    #[cfg(feature = "kv_std")]
+    pub(in crate::kv) fn from_buffered(key: String) -> Self {
+        Key {
+            key: KeyStr::Buffered(key.into()),
+        }
     }
 
     /// Get a borrowed string from this key.
@@ -50,7 +97,24 @@ impl<'k> Key<'k> {
     /// The lifetime of the returned string is bound to the borrow of `self` rather
     /// than to `'k`.
     pub fn as_str(&self) -> &str {
-        self.key
+        match self.key {
+            KeyStr::Static(key) => key,
+            KeyStr::Borrowed(key) => key,
+            #[cfg(feature = "kv_std")]
+            KeyStr::Buffered(ref key) => key,
+        }
+    }
+
+    /// Get the length of this key in bytes.
+    ///
+    /// This is a shortcut for `key.as_str().len()`.
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Whether this key is an empty string.
+    pub fn is_empty(&self) -> bool {
+        self.as_str().is_empty()
     }
 
     /// Try get a borrowed string for the lifetime `'k` from this key.
@@ -58,16 +122,62 @@ impl<'k> Key<'k> {
     /// If the key is a borrow of a longer lived string, this method will return `Some`.
     /// If the key is internally buffered, this method will return `None`.
     pub fn to_borrowed_str(&self) -> Option<&'k str> {
-        // NOTE: If the internals of `Key` support buffering this
-        // won't be unconditionally `Some` anymore. We want to keep
-        // this option open
-        Some(self.key)
+        match self.key {
+            KeyStr::Static(key) => Some(key),
+            KeyStr::Borrowed(key) => Some(key),
+            #[cfg(feature = "kv_std")]
+            KeyStr::Buffered(_) => None,
+        }
+    }
+
+    /// Try get a `'static` string from this key.
+    ///
+    /// If the key was created using [`Key::from_static`], this method will return `Some`.
+    /// Otherwise, this method will return `None`, even if the key happens to borrow
+    /// from a `'static` string.
+    pub fn to_static_str(&self) -> Option<&'static str> {
+        match self.key {
+            KeyStr::Static(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+impl<'k> fmt::Debug for Key<'k> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Key").field("key", &self.as_str()).finish()
     }
 }
 
 impl<'k> fmt::Display for Key<'k> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.key.fmt(f)
+        self.as_str().fmt(f)
+    }
+}
+
+impl<'a, 'b> PartialEq<Key<'b>> for Key<'a> {
+    fn eq(&self, other: &Key<'b>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<'k> Eq for Key<'k> {}
+
+impl<'a, 'b> PartialOrd<Key<'b>> for Key<'a> {
+    fn partial_cmp(&self, other: &Key<'b>) -> Option<Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl<'k> Ord for Key<'k> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<'k> Hash for Key<'k> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
     }
 }
 
@@ -106,8 +216,120 @@ mod std_support {
             Key::from_str(self)
         }
     }
+
+    /// An owned copy of a [`Key`].
+    ///
+    /// Owned keys can be stored in context built at runtime, like a map of
+    /// request headers, and later visited as borrowed keys without allocating
+    /// again. A key created with [`Key::from_static`] stays `'static` when it's
+    /// copied, so its string isn't copied either.
+    ///
+    /// Collections of owned keys and values, like `Vec<(OwnedKey, OwnedValue)>`
+    /// and `HashMap<OwnedKey, OwnedValue>`, implement [`Source`].
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use log::kv::{Key, OwnedKey, OwnedValue, Source, Value};
+    ///
+    /// let mut context = HashMap::new();
+    ///
+    /// let header = String::from("x-request-id");
+    /// context.insert(
+    ///     Key::from_str(&header).to_owned_key(),
+    ///     OwnedValue::from(Value::from("abc")),
+    /// );
+    /// drop(header);
+    ///
+    /// let value = Source::get(&context, Key::from_str("x-request-id")).unwrap();
+    /// assert_eq!(Some("abc"), value.to_borrowed_str());
+    /// ```
+    ///
+    /// [`Key`]: struct.Key.html
+    /// [`Key::from_static`]: struct.Key.html#method.from_static
+    /// [`Source`]: trait.Source.html
+    #[cfg(feature = "kv_std")]
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct OwnedKey(Key<'static>);
+
+    #[cfg(feature = "kv_std")]
+    impl OwnedKey {
+        /// Get an owned key from a `'static` string, without copying it.
+        pub const fn from_static(key: &'static str) -> Self {
+            OwnedKey(Key::from_static(key))
+        }
+
+        /// Get a borrowed string from this key.
+        pub fn as_str(&self) -> &str {
+            self.0.as_str()
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl<'k> Key<'k> {
+        /// Get an owned copy of this key.
+        ///
+        /// Requires the `kv_std` feature.
+        pub fn to_owned_key(&self) -> OwnedKey {
+            OwnedKey::from(self.clone())
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl ToKey for OwnedKey {
+        fn to_key(&self) -> Key {
+            match self.0.to_static_str() {
+                Some(key) => Key::from_static(key),
+                None => Key::from_str(self.0.as_str()),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl<'k> From<Key<'k>> for OwnedKey {
+        fn from(key: Key<'k>) -> Self {
+            match key.to_static_str() {
+                Some(key) => OwnedKey::from_static(key),
+                None => OwnedKey::from(String::from(key.as_str())),
+            }
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl From<String> for OwnedKey {
+        fn from(key: String) -> Self {
+            OwnedKey(Key::from_buffered(key))
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl fmt::Display for OwnedKey {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl AsRef<str> for OwnedKey {
+        fn as_ref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    #[cfg(feature = "kv_std")]
+    impl Borrow<str> for OwnedKey {
+        fn borrow(&self) -> &str {
+            self.as_str()
+        }
+    }
 }
 
+#[cfg(feature = "kv_std")]
+pub use self::std_support::OwnedKey;
+
 #[cfg(feature = "kv_sval")]
 mod sval_support {
     use super::*;
@@ -120,13 +342,18 @@ mod sval_support {
             &'sval self,
             stream: &mut S,
         ) -> sval::Result {
-            self.key.stream(stream)
+            self.as_str().stream(stream)
         }
     }
 
     impl<'a> ValueRef<'a> for Key<'a> {
         fn stream_ref<S: sval::Stream<'a> + ?Sized>(&self, stream: &mut S) -> sval::Result {
-            self.key.stream(stream)
+            match self.key {
+                KeyStr::Static(key) => key.stream(stream),
+                KeyStr::Borrowed(key) => key.stream(stream),
+                #[cfg(feature = "kv_std")]
+                KeyStr::Buffered(ref key) => stream.value_computed(&**key),
+            }
         }
     }
 }
@@ -142,7 +369,7 @@ mod serde_support {
         where
             S: Serializer,
         {
-            self.key.serialize(serializer)
+            self.as_str().serialize(serializer)
         }
     }
 }
@@ -159,5 +386,68 @@ mod tests {
     #[test]
     fn key_to_borrowed() {
         assert_eq!("a key", Key::from_str("a key").to_borrowed_str().unwrap());
+        assert_eq!(
+            "a key",
+            Key::from_static("a key").to_borrowed_str().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_std")]
+    fn owned_key() {
+        let borrowed = String::from("a key");
+        let owned = Key::from_str(&borrowed).to_owned_key();
+        drop(borrowed);
+
+        assert_eq!("a key", owned.as_str());
+        assert_eq!(Key::from_str("a key"), owned.to_key());
+        assert!(owned.to_key().to_borrowed_str().is_some());
+
+        let s = "a key";
+        let owned = Key::from_static(s).to_owned_key();
+
+        assert_eq!(s.as_ptr(), owned.to_key().to_static_str().unwrap().as_ptr());
+        assert_eq!(OwnedKey::from(String::from("a key")), owned);
+    }
+
+    #[test]
+    fn key_to_static() {
+        let s = "a key";
+
+        assert_eq!(
+            s.as_ptr(),
+            Key::from_static(s).to_static_str().unwrap().as_ptr()
+        );
+        assert!(Key::from_str(s).to_static_str().is_none());
+    }
+
+    #[test]
+    fn key_cmp_ignores_static() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(key: &Key) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        fn hash_str(key: &str) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let owned = String::from("b");
+
+        let a = Key::from_static("a");
+        let b_static = Key::from_static("b");
+        let b = Key::from_str(&owned);
+
+        assert_eq!(b_static, b);
+        assert!(a < b);
+        assert!(a < b_static);
+        assert_eq!(Ordering::Equal, b.cmp(&b_static));
+        assert_eq!(hash(&b), hash(&b_static));
+        assert_eq!(hash_str("b"), hash(&b));
     }
 }
@@ -0,0 +1,108 @@
+//! Well-known keys.
+//!
+//! These constants are the canonical names for key-values that many producers
+//! and consumers care about. Using them means a logger that, say, correlates
+//! records by request can find the request id without knowing whether each
+//! crate calls it `request_id`, `req_id`, or `requestId`.
+//!
+//! Each constant documents what its value is expected to be. Loggers should
+//! still handle values of other types gracefully, since nothing enforces this.
+//!
+//! # Examples
+//!
+//! Looking up a well-known key in a logger:
+//!
+//! ```
+//! use log::kv::{keys, Source};
+//! use log::Record;
+//!
+//! fn request_id(record: &Record) -> Option<u64> {
+//!     record.key_values().get(keys::REQUEST_ID)?.to_u64()
+//! }
+//!
+//! let kvs = [("request_id", 42)];
+//! let record = Record::builder().key_values(&kvs).build();
+//!
+//! assert_eq!(Some(42), request_id(&record));
+//! ```
+//!
+//! The logging macros take keys as identifiers or string literals rather than
+//! constants, so producers use the constant's name directly:
+//!
+//! ```
+//! # use log::info;
+//! # let elapsed = std::time::Duration::from_millis(5);
+//! info!(request_id = 42, duration_ms = elapsed.as_millis() as u64; "handled request");
+//! ```
+
+use crate::kv::Key;
+
+/// An error that caused the record, captured so its source chain can be
+/// inspected.
+///
+/// The value should be captured using `Value::from_dyn_error`, or the `:err`
+/// capture in the logging macros. The [`log_err!`] macro uses this key.
+///
+/// [`log_err!`]: ../../macro.log_err.html
+pub const ERR: Key<'static> = Key::from_static("error");
+
+/// A backtrace of the callsite that logged the record, formatted using
+/// `Display`.
+///
+/// With the `backtrace` feature, the logging macros attach this to error
+/// records when `std::backtrace::Backtrace::capture` would capture one, which
+/// depends on the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment
+/// variables. The backtrace's symbols are only resolved if the value is
+/// formatted, so loggers that ignore it don't pay for that.
+pub const BACKTRACE: Key<'static> = Key::from_static("backtrace");
+
+/// An identifier for the request being handled, as a string or an integer.
+pub const REQUEST_ID: Key<'static> = Key::from_static("request_id");
+
+/// The id of the distributed trace the record belongs to, as a hex string.
+///
+/// This follows the W3C Trace Context format used by OpenTelemetry: 32
+/// lowercase hex digits.
+pub const TRACE_ID: Key<'static> = Key::from_static("trace_id");
+
+/// The id of the span within a distributed trace the record belongs to, as a
+/// hex string.
+///
+/// This follows the W3C Trace Context format used by OpenTelemetry: 16
+/// lowercase hex digits.
+pub const SPAN_ID: Key<'static> = Key::from_static("span_id");
+
+/// How long the operation the record describes took, in milliseconds, as an
+/// integer or a float.
+pub const DURATION_MS: Key<'static> = Key::from_static("duration_ms");
+
+/// The target a record should be attributed to, as a string, when it differs
+/// from the record's own target.
+///
+/// Bridges that forward records from another logging system through a single
+/// callsite can use this to preserve the original target. Loggers that see it
+/// should prefer it over [`Metadata::target`] when displaying or filtering the
+/// record.
+///
+/// [`Metadata::target`]: ../../struct.Metadata.html#method.target
+pub const TARGET_OVERRIDE: Key<'static> = Key::from_static("log.target");
+
+/// Set to `true` on records that were cut to fit in the global [`Limits`].
+///
+/// [`Limits`]: ../../struct.Limits.html
+pub const TRUNCATED: Key<'static> = Key::from_static("log.truncated");
+
+/// The version of the program, from the global [`BuildInfo`].
+///
+/// [`BuildInfo`]: ../../struct.BuildInfo.html
+pub const BUILD_VERSION: Key<'static> = Key::from_static("build.version");
+
+/// The commit the program was built from, from the global [`BuildInfo`].
+///
+/// [`BuildInfo`]: ../../struct.BuildInfo.html
+pub const BUILD_GIT_SHA: Key<'static> = Key::from_static("build.git_sha");
+
+/// The profile the program was built with, from the global [`BuildInfo`].
+///
+/// [`BuildInfo`]: ../../struct.BuildInfo.html
+pub const BUILD_PROFILE: Key<'static> = Key::from_static("build.profile");
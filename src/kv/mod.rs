@@ -56,6 +56,8 @@
 //! - `:err` will capture the value using `std::error::Error` (requires the `kv_std` feature).
 //! - `:sval` will capture the value using `sval::Value` (requires the `kv_sval` feature).
 //! - `:serde` will capture the value using `serde::Serialize` (requires the `kv_serde` feature).
+//! - `:x`, `:X`, `:o`, and `:b` will capture the value using `ToValue`, with a
+//!   [`FormatHint`] to render it as hexadecimal, octal, or binary text.
 //!
 //! ## Working with key-values on log records
 //!
@@ -244,7 +246,10 @@
 //! ```
 
 mod error;
+#[cfg(feature = "kv_serde")]
+mod flatten;
 mod key;
+pub mod keys;
 
 #[cfg(not(feature = "kv_unstable"))]
 mod source;
@@ -252,9 +257,18 @@ mod source;
 mod value;
 
 pub use self::error::Error;
+#[cfg(feature = "kv_serde")]
+pub use self::flatten::{flatten, Flatten};
 pub use self::key::{Key, ToKey};
 pub use self::source::{Source, VisitSource};
-pub use self::value::{ToValue, Value, VisitValue};
+pub use self::value::{Fill, FormatHint, Slot, ToValue, Value, VisitValue};
+
+#[cfg(feature = "kv_std")]
+pub use self::key::OwnedKey;
+#[cfg(feature = "kv_std")]
+pub use self::source::{Namespaced, Sorted};
+#[cfg(feature = "kv_std")]
+pub use self::value::OwnedValue;
 
 #[cfg(feature = "kv_unstable")]
 pub mod source;
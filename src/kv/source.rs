@@ -85,6 +85,271 @@ pub trait Source {
     fn count(&self) -> usize {
         count_default(self)
     }
+
+    /// Estimate the length in bytes of the key-values when they're rendered.
+    ///
+    /// This is the sum of the lengths of each key and the estimated length of
+    /// each value's `Display` output, as estimated by
+    /// [`Value::estimate_display_len`]. It doesn't include separators or
+    /// quoting, which depend on the format, so formatters should add their own
+    /// overhead per key-value, using [`Source::count`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Source;
+    ///
+    /// let source = [("user", "alice"), ("id", "42")];
+    ///
+    /// // `user=alice id=42`
+    /// let overhead = 2 * source.count() - 1;
+    /// let capacity = source.estimate_rendered_len() + overhead;
+    ///
+    /// assert_eq!(16, capacity);
+    /// ```
+    ///
+    /// [`Value::estimate_display_len`]: struct.Value.html#method.estimate_display_len
+    /// [`Source::count`]: trait.Source.html#method.count
+    fn estimate_rendered_len(&self) -> usize {
+        estimate_rendered_len_default(self)
+    }
+
+    /// Whether [`visit`](#tymethod.visit) always yields key-values in order of
+    /// their keys.
+    ///
+    /// Keys are ordered by comparing their strings. This is used by
+    /// [`Source::sorted`] to avoid buffering key-values that are already in
+    /// order.
+    ///
+    /// # Implementation notes
+    ///
+    /// The default implementation returns `false`. Sources that are always
+    /// sorted, like a `BTreeMap` or a single pair, should override it.
+    ///
+    /// [`Source::sorted`]: #method.sorted
+    fn is_sorted(&self) -> bool {
+        false
+    }
+
+    /// Copy the key-values into a list of owned keys and values.
+    ///
+    /// Key-values are kept in the order they're visited, including any
+    /// duplicate keys. See [`OwnedValue`] for how values are copied.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// [`OwnedValue`]: struct.OwnedValue.html
+    #[cfg(feature = "kv_std")]
+    fn to_owned_vec(&self) -> Vec<(String, crate::kv::OwnedValue)> {
+        std_support::to_owned_vec(self)
+    }
+
+    /// Copy the key-values into a map of owned keys and values.
+    ///
+    /// If a key appears multiple times then the last value visited for it is
+    /// kept. See [`OwnedValue`] for how values are copied.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// [`OwnedValue`]: struct.OwnedValue.html
+    #[cfg(feature = "kv_std")]
+    fn to_owned_map(&self) -> std::collections::BTreeMap<String, crate::kv::OwnedValue> {
+        self.to_owned_vec().into_iter().collect()
+    }
+
+    /// Put the key-values under a namespace.
+    ///
+    /// Each key is prefixed with the namespace and a `.`, so a library can
+    /// emit its key-values as `db.query` and `db.rows` without colliding
+    /// with the application's own keys when both are logged in one record.
+    /// Namespaces can be nested.
+    ///
+    /// The prefixed keys are built while the source is visited, so
+    /// [`Key::to_borrowed_str`] returns `None` for them.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{Source, Value};
+    ///
+    /// let db = [("query", Value::from("select 1")), ("rows", Value::from(1))];
+    /// let app = [("query", Value::from("/users?id=1"))];
+    ///
+    /// let source: [&dyn Source; 2] = [&db.namespaced("db"), &app];
+    ///
+    /// assert_eq!(3, source.count());
+    /// assert_eq!(Some(1), source.get("db.rows".into()).and_then(|v| v.to_u64()));
+    /// assert_eq!("/users?id=1", source.get("query".into()).unwrap().to_string());
+    /// ```
+    ///
+    /// [`Key::to_borrowed_str`]: struct.Key.html#method.to_borrowed_str
+    #[cfg(feature = "kv_std")]
+    fn namespaced(self, namespace: &str) -> Namespaced<'_, Self>
+    where
+        Self: Sized,
+    {
+        Namespaced {
+            source: self,
+            namespace,
+        }
+    }
+
+    /// Visit the key-values in order of their keys.
+    ///
+    /// Keys are ordered by comparing their strings. Key-values with the same
+    /// key are kept in the order they're visited in. This is useful for
+    /// formatters that need stable output, like diffable logs and snapshot
+    /// tests.
+    ///
+    /// The key-values are buffered and sorted each time the source is visited,
+    /// unless [`Source::is_sorted`] says they're already in order.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Source;
+    ///
+    /// let source = [("b", 1), ("c", 2), ("a", 3)];
+    ///
+    /// let keys = source
+    ///     .sorted()
+    ///     .to_owned_vec()
+    ///     .into_iter()
+    ///     .map(|(key, _)| key)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec!["a", "b", "c"], keys);
+    /// ```
+    ///
+    /// [`Source::is_sorted`]: #method.is_sorted
+    #[cfg(feature = "kv_std")]
+    fn sorted(self) -> Sorted<Self>
+    where
+        Self: Sized,
+    {
+        Sorted { source: self }
+    }
+}
+
+/// The key-values in a source, in order of their keys.
+///
+/// This type is returned by [`Source::sorted`]. See its documentation for
+/// details.
+///
+/// [`Source::sorted`]: trait.Source.html#method.sorted
+#[cfg(feature = "kv_std")]
+#[derive(Debug, Clone)]
+pub struct Sorted<S> {
+    source: S,
+}
+
+#[cfg(feature = "kv_std")]
+impl<S> Source for Sorted<S>
+where
+    S: Source,
+{
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        if self.source.is_sorted() {
+            return self.source.visit(visitor);
+        }
+
+        struct Collect<'kvs>(Vec<(Key<'kvs>, Value<'kvs>)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect<'kvs> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key, value));
+
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::with_capacity(self.source.count()));
+        self.source.visit(&mut collect)?;
+
+        // A stable sort keeps duplicate keys in the order they were visited
+        collect
+            .0
+            .sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+        for (key, value) in collect.0 {
+            visitor.visit_pair(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        self.source.get(key)
+    }
+
+    fn count(&self) -> usize {
+        self.source.count()
+    }
+
+    fn is_sorted(&self) -> bool {
+        true
+    }
+}
+
+/// The key-values in a source, under a namespace.
+///
+/// This type is returned by [`Source::namespaced`]. See its documentation for
+/// details.
+///
+/// [`Source::namespaced`]: trait.Source.html#method.namespaced
+#[cfg(feature = "kv_std")]
+#[derive(Debug, Clone)]
+pub struct Namespaced<'n, S> {
+    source: S,
+    namespace: &'n str,
+}
+
+#[cfg(feature = "kv_std")]
+impl<'n, S> Source for Namespaced<'n, S>
+where
+    S: Source,
+{
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+        struct Prefix<'a, 'kvs> {
+            namespace: &'a str,
+            visitor: &'a mut dyn VisitSource<'kvs>,
+        }
+
+        impl<'a, 'kvs> VisitSource<'kvs> for Prefix<'a, 'kvs> {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                let key = Key::from_buffered(format!("{}.{}", self.namespace, key));
+
+                self.visitor.visit_pair(key, value)
+            }
+        }
+
+        self.source.visit(&mut Prefix {
+            namespace: self.namespace,
+            visitor,
+        })
+    }
+
+    fn get(&self, key: Key) -> Option<Value<'_>> {
+        let key = key
+            .as_str()
+            .strip_prefix(self.namespace)?
+            .strip_prefix('.')?;
+
+        self.source.get(Key::from_str(key))
+    }
+
+    fn count(&self) -> usize {
+        self.source.count()
+    }
+
+    // Every key gets the same prefix, so their order doesn't change
+    fn is_sorted(&self) -> bool {
+        self.source.is_sorted()
+    }
 }
 
 /// The default implementation of `Source::get`
@@ -127,6 +392,23 @@ fn count_default(source: impl Source) -> usize {
     count.0
 }
 
+/// The default implementation of `Source::estimate_rendered_len`.
+fn estimate_rendered_len_default(source: impl Source) -> usize {
+    struct Estimate(usize);
+
+    impl<'kvs> VisitSource<'kvs> for Estimate {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            self.0 += key.len() + value.estimate_display_len();
+
+            Ok(())
+        }
+    }
+
+    let mut estimate = Estimate(0);
+    let _ = source.visit(&mut estimate);
+    estimate.0
+}
+
 impl<'a, T> Source for &'a T
 where
     T: Source + ?Sized,
@@ -142,6 +424,14 @@ where
     fn count(&self) -> usize {
         Source::count(&**self)
     }
+
+    fn estimate_rendered_len(&self) -> usize {
+        Source::estimate_rendered_len(&**self)
+    }
+
+    fn is_sorted(&self) -> bool {
+        Source::is_sorted(&**self)
+    }
 }
 
 impl<K, V> Source for (K, V)
@@ -164,6 +454,10 @@ where
     fn count(&self) -> usize {
         1
     }
+
+    fn is_sorted(&self) -> bool {
+        true
+    }
 }
 
 impl<S> Source for [S]
@@ -229,6 +523,10 @@ where
     fn count(&self) -> usize {
         self.as_ref().map_or(0, Source::count)
     }
+
+    fn is_sorted(&self) -> bool {
+        self.as_ref().map_or(true, Source::is_sorted)
+    }
 }
 
 /// A visitor for the key-value pairs in a [`Source`](trait.Source.html).
@@ -284,6 +582,27 @@ mod std_support {
     use std::rc::Rc;
     use std::sync::Arc;
 
+    #[cfg(feature = "kv_std")]
+    use crate::kv::OwnedValue;
+
+    /// The implementation of `Source::to_owned_vec`.
+    #[cfg(feature = "kv_std")]
+    pub(super) fn to_owned_vec(source: &(impl Source + ?Sized)) -> Vec<(String, OwnedValue)> {
+        struct Collect(Vec<(String, OwnedValue)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+                self.0.push((key.as_str().into(), value.into()));
+
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        let _ = source.visit(&mut collect);
+        collect.0
+    }
+
     impl<S> Source for Box<S>
     where
         S: Source + ?Sized,
@@ -299,6 +618,10 @@ mod std_support {
         fn count(&self) -> usize {
             Source::count(&**self)
         }
+
+        fn is_sorted(&self) -> bool {
+            Source::is_sorted(&**self)
+        }
     }
 
     impl<S> Source for Arc<S>
@@ -316,6 +639,10 @@ mod std_support {
         fn count(&self) -> usize {
             Source::count(&**self)
         }
+
+        fn is_sorted(&self) -> bool {
+            Source::is_sorted(&**self)
+        }
     }
 
     impl<S> Source for Rc<S>
@@ -333,6 +660,10 @@ mod std_support {
         fn count(&self) -> usize {
             Source::count(&**self)
         }
+
+        fn is_sorted(&self) -> bool {
+            Source::is_sorted(&**self)
+        }
     }
 
     impl<S> Source for Vec<S>
@@ -350,6 +681,10 @@ mod std_support {
         fn count(&self) -> usize {
             Source::count(&**self)
         }
+
+        fn is_sorted(&self) -> bool {
+            Source::is_sorted(&**self)
+        }
     }
 
     impl<'kvs, V> VisitSource<'kvs> for Box<V>
@@ -402,6 +737,11 @@ mod std_support {
         fn count(&self) -> usize {
             self.len()
         }
+
+        // `Borrow` requires keys to be ordered the same way as their strings
+        fn is_sorted(&self) -> bool {
+            true
+        }
     }
 
     #[cfg(test)]
@@ -428,6 +768,76 @@ mod std_support {
             assert!(Source::get(&source, Key::from_str("a")).is_none());
         }
 
+        #[test]
+        #[cfg(feature = "kv_std")]
+        fn to_owned() {
+            let source = vec![("b", 1), ("a", 2), ("b", 3)];
+
+            let vec = Source::to_owned_vec(&source);
+            assert_eq!(
+                vec![
+                    ("b".to_owned(), OwnedValue::from(Value::from(1))),
+                    ("a".to_owned(), OwnedValue::from(Value::from(2))),
+                    ("b".to_owned(), OwnedValue::from(Value::from(3))),
+                ],
+                vec
+            );
+
+            let map = Source::to_owned_map(&source);
+            assert_eq!(2, map.len());
+            assert_eq!(Some(3), map["b"].to_value().to_i64());
+            assert_eq!(Some(2), map["a"].to_value().to_i64());
+        }
+
+        #[test]
+        #[cfg(feature = "kv_std")]
+        fn namespaced() {
+            let source = vec![("query", 1), ("rows", 2)];
+            let namespaced = (&source).namespaced("db").namespaced("app");
+
+            let keys = namespaced
+                .to_owned_vec()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+            assert_eq!(vec!["app.db.query", "app.db.rows"], keys);
+
+            assert_eq!(2, namespaced.count());
+            assert_eq!(
+                value::inner::Token::I64(2),
+                Source::get(&namespaced, Key::from_str("app.db.rows"))
+                    .unwrap()
+                    .to_token()
+            );
+            assert!(Source::get(&namespaced, Key::from_str("rows")).is_none());
+            assert!(Source::get(&namespaced, Key::from_str("app.dbrows")).is_none());
+        }
+
+        #[test]
+        #[cfg(feature = "kv_std")]
+        fn sorted() {
+            fn keys(source: impl Source) -> Vec<String> {
+                source
+                    .to_owned_vec()
+                    .into_iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect()
+            }
+
+            let source = vec![("b", 1), ("c", 2), ("a", 3), ("b", 4)];
+            assert!(!source.is_sorted());
+            assert_eq!(vec!["a=3", "b=1", "b=4", "c=2"], keys((&source).sorted()));
+            assert_eq!(4, (&source).sorted().count());
+            assert!((&source).sorted().is_sorted());
+
+            let mut map = BTreeMap::new();
+            map.insert("b", 1);
+            map.insert("a", 2);
+            assert!(map.is_sorted());
+            assert!((&map).namespaced("ns").is_sorted());
+            assert_eq!(vec!["a=2", "b=1"], keys((&map).sorted()));
+        }
+
         #[test]
         fn hash_map() {
             let mut map = HashMap::new();
@@ -456,6 +866,56 @@ mod std_support {
     }
 }
 
+#[cfg(feature = "kv_serde_json")]
+mod serde_json_support {
+    use super::*;
+
+    impl Source for serde_json::Map<String, serde_json::Value> {
+        fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), Error> {
+            for (key, value) in self {
+                visitor.visit_pair(key.to_key(), value.to_value())?;
+            }
+            Ok(())
+        }
+
+        fn get(&self, key: Key) -> Option<Value<'_>> {
+            serde_json::Map::get(self, key.as_str()).map(|v| v.to_value())
+        }
+
+        fn count(&self) -> usize {
+            self.len()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn json_map() {
+            let json = serde_json::json!({
+                "a": 1,
+                "b": "a string",
+                "c": { "nested": true },
+            });
+            let map = json.as_object().unwrap();
+
+            assert_eq!(3, Source::count(map));
+            assert_eq!(
+                Some(1),
+                Source::get(map, Key::from_str("a")).unwrap().to_u64()
+            );
+            assert_eq!(
+                Some("a string"),
+                Source::get(map, Key::from_str("b"))
+                    .unwrap()
+                    .to_borrowed_str()
+            );
+            assert!(Source::get(map, Key::from_str("d")).is_none());
+        }
+    }
+}
+
 // NOTE: Deprecated; but aliases can't carry this attribute
 #[cfg(feature = "kv_unstable")]
 pub use VisitSource as Visitor;
@@ -476,6 +936,33 @@ mod tests {
         fn _check(_: &dyn VisitSource) {}
     }
 
+    #[test]
+    fn prebuilt_values() {
+        let values: &[(&str, Value)] = &[("a", Value::from(1)), ("b", Value::from("two"))];
+        assert_eq!(2, Source::count(&values));
+        assert_eq!(
+            Some(1),
+            Source::get(&values, Key::from_str("a")).and_then(|v| v.to_i64())
+        );
+
+        let values = [("a", Value::from(1)), ("b", Value::from("two"))];
+        assert_eq!(2, Source::count(&values));
+        assert_eq!(
+            Some("two"),
+            Source::get(&values, Key::from_str("b")).and_then(|v| v.to_borrowed_str())
+        );
+    }
+
+    #[test]
+    fn estimate_rendered_len() {
+        assert_eq!(0, Source::estimate_rendered_len(&None::<(&str, i32)>));
+        assert_eq!(3, Source::estimate_rendered_len(&("a", 10)));
+        assert_eq!(
+            8,
+            Source::estimate_rendered_len(&[("a", "one"), ("bb", "ab")] as &[_])
+        );
+    }
+
     #[test]
     fn count() {
         struct OnePair {
@@ -3,11 +3,39 @@
 //! This module defines the [`Value`] type and supporting APIs for
 //! capturing and serializing them.
 
+use std::cmp::Ordering;
 use std::fmt;
 
 pub use crate::kv::Error;
 
 /// A type that can be converted into a [`Value`](struct.Value.html).
+///
+/// # Well-known types
+///
+/// Durations, timestamps, and some common types from other crates can be
+/// captured when their feature is enabled. They're captured using `Display`,
+/// so they're formatted and serialized as strings, with these canonical
+/// representations:
+///
+/// | Feature | Types | Representation |
+/// | ------- | ----- | -------------- |
+/// | `kv` | `std::time::Duration` | Seconds, like `1.250s` |
+/// | `kv_std` | `std::time::SystemTime` | RFC 3339 in UTC, like `2024-05-01T12:30:00.250Z` |
+/// | `kv_uuid` | `uuid::Uuid` | Lowercase and hyphenated, like `67e55044-10b1-426f-9247-bb680e5fe0c8` |
+/// | `kv_time` | `time::OffsetDateTime` | RFC 3339, like `2024-05-01T12:30:00.250Z` |
+/// | `kv_chrono` | `chrono::DateTime<Tz>` | RFC 3339, like `2024-05-01T14:30:00+02:00` |
+///
+/// Timestamps keep their UTC offset, which is written as `Z` when it's zero.
+/// Offsets are written in whole minutes. Fractional seconds are only written
+/// if they're non-zero, with 3, 6, or 9 digits, whichever is the fewest that
+/// represents them exactly. Leap seconds are written as second `60`.
+///
+/// Durations and timestamps can be recovered with [`Value::to_duration`] and
+/// [`Value::to_timestamp`], which also accept strings in these forms, like
+/// values that have been through a serializer.
+///
+/// [`Value::to_duration`]: struct.Value.html#method.to_duration
+/// [`Value::to_timestamp`]: struct.Value.html#method.to_timestamp
 pub trait ToValue {
     /// Perform the conversion.
     fn to_value(&self) -> Value;
@@ -26,6 +54,7 @@ impl<'v> ToValue for Value<'v> {
     fn to_value(&self) -> Value {
         Value {
             inner: self.inner.clone(),
+            format: self.format,
         }
     }
 }
@@ -117,6 +146,52 @@ impl<'v> ToValue for Value<'v> {
 /// through `sval`, or can be formatted using a `Debug`-compatible representation.
 pub struct Value<'v> {
     inner: inner::Inner<'v>,
+    format: Option<FormatHint>,
+}
+
+/// A hint for how to format a numeric value as text.
+///
+/// Values captured with a formatting sigil, like `addr:x = ptr`, carry a hint
+/// so sinks can render them the same way the record's message would. The
+/// hint is used by the value's `Display` implementation, and can be inspected
+/// with [`Value::format_hint`]. It's ignored for values that aren't integers.
+///
+/// Negative integers are formatted as their two's complement, like they are
+/// by the standard library.
+///
+/// | Sigil | Hint | Trait |
+/// | ----- | ---- | ----- |
+/// | `:x` | `LowerHex` | `std::fmt::LowerHex` |
+/// | `:X` | `UpperHex` | `std::fmt::UpperHex` |
+/// | `:o` | `Octal` | `std::fmt::Octal` |
+/// | `:b` | `Binary` | `std::fmt::Binary` |
+///
+/// [`Value::format_hint`]: struct.Value.html#method.format_hint
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FormatHint {
+    /// Format as lowercase hexadecimal, like `2a`.
+    LowerHex,
+    /// Format as uppercase hexadecimal, like `2A`.
+    UpperHex,
+    /// Format as octal, like `52`.
+    Octal,
+    /// Format as binary, like `101010`.
+    Binary,
+}
+
+impl FormatHint {
+    fn fmt_int<T>(self, v: T, f: &mut fmt::Formatter) -> fmt::Result
+    where
+        T: fmt::LowerHex + fmt::UpperHex + fmt::Octal + fmt::Binary,
+    {
+        match self {
+            FormatHint::LowerHex => fmt::LowerHex::fmt(&v, f),
+            FormatHint::UpperHex => fmt::UpperHex::fmt(&v, f),
+            FormatHint::Octal => fmt::Octal::fmt(&v, f),
+            FormatHint::Binary => fmt::Binary::fmt(&v, f),
+        }
+    }
 }
 
 impl<'v> Value<'v> {
@@ -135,6 +210,7 @@ impl<'v> Value<'v> {
     {
         Value {
             inner: inner::Inner::from_debug(value),
+            format: None,
         }
     }
 
@@ -145,6 +221,7 @@ impl<'v> Value<'v> {
     {
         Value {
             inner: inner::Inner::from_display(value),
+            format: None,
         }
     }
 
@@ -156,6 +233,7 @@ impl<'v> Value<'v> {
     {
         Value {
             inner: inner::Inner::from_serde1(value),
+            format: None,
         }
     }
 
@@ -167,6 +245,7 @@ impl<'v> Value<'v> {
     {
         Value {
             inner: inner::Inner::from_sval2(value),
+            format: None,
         }
     }
 
@@ -174,6 +253,7 @@ impl<'v> Value<'v> {
     pub fn from_dyn_debug(value: &'v dyn fmt::Debug) -> Self {
         Value {
             inner: inner::Inner::from_dyn_debug(value),
+            format: None,
         }
     }
 
@@ -181,6 +261,20 @@ impl<'v> Value<'v> {
     pub fn from_dyn_display(value: &'v dyn fmt::Display) -> Self {
         Value {
             inner: inner::Inner::from_dyn_display(value),
+            format: None,
+        }
+    }
+
+    /// Get a value that's produced lazily when it's visited.
+    ///
+    /// See the [`Fill`] trait for details.
+    pub fn from_fill<T>(value: &'v T) -> Self
+    where
+        T: Fill,
+    {
+        Value {
+            inner: inner::from_fill(value),
+            format: None,
         }
     }
 
@@ -189,6 +283,7 @@ impl<'v> Value<'v> {
     pub fn from_dyn_error(err: &'v (dyn std::error::Error + 'static)) -> Self {
         Value {
             inner: inner::Inner::from_dyn_error(err),
+            format: None,
         }
     }
 
@@ -196,6 +291,7 @@ impl<'v> Value<'v> {
     pub fn null() -> Self {
         Value {
             inner: inner::Inner::empty(),
+            format: None,
         }
     }
 
@@ -206,16 +302,267 @@ impl<'v> Value<'v> {
     {
         Value {
             inner: value.into(),
+            format: None,
         }
     }
 
+    /// Attach a hint for how to format this value as text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{FormatHint, Value};
+    ///
+    /// let value = Value::from(255u8).with_format_hint(FormatHint::LowerHex);
+    ///
+    /// assert_eq!("ff", value.to_string());
+    /// assert_eq!(Some(255), value.to_u64());
+    /// ```
+    pub fn with_format_hint(self, hint: FormatHint) -> Self {
+        Value {
+            inner: self.inner,
+            format: Some(hint),
+        }
+    }
+
+    /// Get the hint for how to format this value as text, if it has one.
+    ///
+    /// See [`FormatHint`] for details.
+    ///
+    /// [`FormatHint`]: enum.FormatHint.html
+    pub fn format_hint(&self) -> Option<FormatHint> {
+        self.format
+    }
+
     /// Inspect this value using a simple visitor.
     ///
+    /// The visitor can be passed by value, by `&mut` reference, or as a
+    /// `&mut dyn VisitValue`, so sinks that only know about their visitor at
+    /// runtime can still dispatch on the kind of primitive in the value.
+    ///
     /// When the `kv_serde` or `kv_sval` features are enabled, you can also
     /// serialize a value using its `Serialize` or `Value` implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{self, Value, VisitValue};
+    ///
+    /// struct IsNumber(bool);
+    ///
+    /// impl<'v> VisitValue<'v> for IsNumber {
+    ///     fn visit_any(&mut self, _: Value) -> Result<(), kv::Error> {
+    ///         self.0 = false;
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn visit_u64(&mut self, _: u64) -> Result<(), kv::Error> {
+    ///         self.0 = true;
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn visit_i64(&mut self, _: i64) -> Result<(), kv::Error> {
+    ///         self.0 = true;
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn visit_f64(&mut self, _: f64) -> Result<(), kv::Error> {
+    ///         self.0 = true;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut is_number = IsNumber(false);
+    /// let visitor: &mut dyn VisitValue = &mut is_number;
+    ///
+    /// Value::from(42).visit(visitor)?;
+    /// assert!(is_number.0);
+    /// # Ok::<(), kv::Error>(())
+    /// ```
     pub fn visit(&self, visitor: impl VisitValue<'v>) -> Result<(), Error> {
         inner::visit(&self.inner, visitor)
     }
+
+    /// Check whether this value is `null`.
+    ///
+    /// Values captured from `()` or an `Option` that was `None` are `null`.
+    /// A key-value with a `null` value was still logged, so sinks should
+    /// write it out as a null in their format rather than skip it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{ToValue, Value};
+    ///
+    /// assert!(Value::null().is_null());
+    /// assert!(None::<i32>.to_value().is_null());
+    ///
+    /// assert!(!Some(42).to_value().is_null());
+    /// ```
+    pub fn is_null(&self) -> bool {
+        struct IsNull(bool);
+
+        impl<'v> VisitValue<'v> for IsNull {
+            fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+                Ok(())
+            }
+
+            fn visit_null(&mut self) -> Result<(), Error> {
+                self.0 = true;
+                Ok(())
+            }
+        }
+
+        let mut is_null = IsNull(false);
+        let _ = self.visit(&mut is_null);
+        is_null.0
+    }
+
+    /// Convert this value into an `Option`, which is `None` if the value is `null`.
+    ///
+    /// This is the inverse of capturing an `Option` as a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::ToValue;
+    ///
+    /// assert!(None::<i32>.to_value().to_option().is_none());
+    ///
+    /// let value = Some(42).to_value();
+    /// assert_eq!(Some(42), value.to_option().and_then(|v| v.to_i64()));
+    /// ```
+    pub fn to_option(&self) -> Option<Value<'_>> {
+        if self.is_null() {
+            None
+        } else {
+            Some(self.to_value())
+        }
+    }
+
+    /// Estimate the length in bytes of this value's `Display` output.
+    ///
+    /// Formatters can use this to pre-size their buffers. The estimate is
+    /// exact for numbers, booleans, characters, and strings. Other values,
+    /// like ones captured using `Debug` or `serde`, can't be measured without
+    /// formatting them, so they're estimated as zero bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Value;
+    ///
+    /// assert_eq!(5, Value::from("hello").estimate_display_len());
+    /// assert_eq!(4, Value::from(-123).estimate_display_len());
+    /// assert_eq!(5, Value::from(false).estimate_display_len());
+    /// ```
+    pub fn estimate_display_len(&self) -> usize {
+        struct Estimate<'a, 'v> {
+            value: &'a Value<'v>,
+            len: usize,
+        }
+
+        impl<'a, 'v> Estimate<'a, 'v> {
+            fn measure(&mut self) {
+                struct Count(usize);
+
+                impl fmt::Write for Count {
+                    fn write_str(&mut self, s: &str) -> fmt::Result {
+                        self.0 += s.len();
+                        Ok(())
+                    }
+                }
+
+                // Formatting primitives doesn't allocate, so they're measured
+                // exactly, honoring any format hint
+                let mut count = Count(0);
+                let _ = fmt::Write::write_fmt(&mut count, format_args!("{}", self.value));
+                self.len = count.0;
+            }
+        }
+
+        impl<'a, 'v, 'b> VisitValue<'b> for Estimate<'a, 'v> {
+            fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+                Ok(())
+            }
+
+            fn visit_null(&mut self) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_u64(&mut self, _: u64) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_i64(&mut self, _: i64) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_u128(&mut self, _: u128) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_i128(&mut self, _: i128) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_f64(&mut self, _: f64) -> Result<(), Error> {
+                self.measure();
+                Ok(())
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                self.len = if value { 4 } else { 5 };
+                Ok(())
+            }
+
+            fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                self.len = value.len();
+                Ok(())
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                self.len = value.len_utf8();
+                Ok(())
+            }
+        }
+
+        let mut estimate = Estimate {
+            value: self,
+            len: 0,
+        };
+        let _ = self.visit(&mut estimate);
+        estimate.len
+    }
+
+    /// Get a `serde::Serialize` proxy for this value.
+    ///
+    /// Values implement `serde::Serialize` directly, but this can be handy
+    /// when passing a value to code that's generic over its serializable type.
+    /// However the value was captured, it serializes in `serde`'s data model
+    /// without being formatted to a string first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Value;
+    ///
+    /// let data = vec![1, 2, 3];
+    /// let value = Value::from_serde(&data);
+    ///
+    /// let json = serde_json::to_string(&value.to_serializable())?;
+    /// assert_eq!("[1,2,3]", json);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    #[cfg(feature = "kv_serde")]
+    pub fn to_serializable(&self) -> impl serde::Serialize + '_ {
+        self
+    }
 }
 
 impl<'v> fmt::Debug for Value<'v> {
@@ -226,6 +573,21 @@ impl<'v> fmt::Debug for Value<'v> {
 
 impl<'v> fmt::Display for Value<'v> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(hint) = self.format {
+            if let Some(v) = self.to_u64() {
+                return hint.fmt_int(v, f);
+            }
+            if let Some(v) = self.to_i64() {
+                return hint.fmt_int(v, f);
+            }
+            if let Some(v) = self.to_u128() {
+                return hint.fmt_int(v, f);
+            }
+            if let Some(v) = self.to_i128() {
+                return hint.fmt_int(v, f);
+            }
+        }
+
         fmt::Display::fmt(&self.inner, f)
     }
 }
@@ -372,6 +734,40 @@ impl_value_to_primitive![
     to_bool -> bool,
 ];
 
+macro_rules! impl_value_to_narrow_primitive {
+    ($(#[doc = $doc:tt] $into_name:ident -> $into_ty:ty = $from_name:ident,)*) => {
+        impl<'v> Value<'v> {
+            $(
+                #[doc = $doc]
+                ///
+                /// This returns `None` if the value doesn't fit, instead of truncating it.
+                pub fn $into_name(&self) -> Option<$into_ty> {
+                    self.$from_name()?.try_into().ok()
+                }
+            )*
+        }
+    }
+}
+
+impl_value_to_narrow_primitive![
+    #[doc = "Try convert this value into a `u8`."]
+    to_u8 -> u8 = to_u64,
+    #[doc = "Try convert this value into a `u16`."]
+    to_u16 -> u16 = to_u64,
+    #[doc = "Try convert this value into a `u32`."]
+    to_u32 -> u32 = to_u64,
+    #[doc = "Try convert this value into a `usize`."]
+    to_usize -> usize = to_u64,
+    #[doc = "Try convert this value into a `i8`."]
+    to_i8 -> i8 = to_i64,
+    #[doc = "Try convert this value into a `i16`."]
+    to_i16 -> i16 = to_i64,
+    #[doc = "Try convert this value into a `i32`."]
+    to_i32 -> i32 = to_i64,
+    #[doc = "Try convert this value into a `isize`."]
+    to_isize -> isize = to_i64,
+];
+
 impl<'v> Value<'v> {
     /// Try to convert this value into an error.
     #[cfg(feature = "kv_std")]
@@ -380,14 +776,201 @@ impl<'v> Value<'v> {
     }
 
     /// Try to convert this value into a borrowed string.
+    ///
+    /// This only returns a string if the value was captured as one, so it
+    /// doesn't need to allocate. Use [`Value::to_str_in`] to also get other
+    /// values as strings without allocating.
+    ///
+    /// [`Value::to_str_in`]: #method.to_str_in
     pub fn to_borrowed_str(&self) -> Option<&'v str> {
         self.inner.to_borrowed_str()
     }
+
+    /// Write this value's `Display` output to `w`.
+    ///
+    /// This doesn't allocate, so sinks without the `std` feature can use it
+    /// to stringify values into a fixed-size buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Value;
+    ///
+    /// let mut rendered = String::new();
+    /// Value::from(42).render_into(&mut rendered)?;
+    ///
+    /// assert_eq!("42", rendered);
+    /// # Ok::<(), std::fmt::Error>(())
+    /// ```
+    pub fn render_into(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        w.write_fmt(format_args!("{}", self))
+    }
+
+    /// Get this value as a string, formatting it into `buf` if it isn't one.
+    ///
+    /// A value captured as a string is returned as it is. Any other value is
+    /// formatted into `buf` with [`Value::render_into`]. This returns `None`
+    /// if its `Display` output doesn't fit in `buf`, or if formatting it fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::Value;
+    ///
+    /// let mut buf = [0; 8];
+    ///
+    /// assert_eq!(Some("chashu"), Value::from("chashu").to_str_in(&mut buf));
+    /// assert_eq!(Some("-42"), Value::from(-42).to_str_in(&mut buf));
+    /// assert_eq!(None, Value::from(u64::MAX).to_str_in(&mut buf));
+    /// ```
+    ///
+    /// [`Value::render_into`]: #method.render_into
+    pub fn to_str_in<'a>(&'a self, buf: &'a mut [u8]) -> Option<&'a str> {
+        struct Buf<'a> {
+            buf: &'a mut [u8],
+            len: usize,
+        }
+
+        impl<'a> fmt::Write for Buf<'a> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let end = self.len + s.len();
+                self.buf
+                    .get_mut(self.len..end)
+                    .ok_or(fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.len = end;
+
+                Ok(())
+            }
+        }
+
+        if let Some(s) = self.to_borrowed_str() {
+            return Some(s);
+        }
+
+        let mut w = Buf { buf, len: 0 };
+        self.render_into(&mut w).ok()?;
+
+        // Only whole strings are written, so the buffer is always valid UTF-8
+        let Buf { buf, len } = w;
+        std::str::from_utf8(&buf[..len]).ok()
+    }
+}
+
+impl<'v> Value<'v> {
+    /// Compare this value with another, if they're both primitives of
+    /// comparable kinds.
+    ///
+    /// Numbers are compared by their numeric value, whether they're signed,
+    /// unsigned, or floating point, so `500u16` is equal to `500i64` and less
+    /// than `500.5f64`. Integers are compared exactly; an integer is only
+    /// converted to a float to compare it with a float. Booleans, characters,
+    /// and borrowed strings can be compared with values of the same kind.
+    ///
+    /// This returns `None` if either value isn't a primitive, if they're of
+    /// different kinds, or if a float is `NaN`. The `PartialEq` and
+    /// `PartialOrd` implementations for primitives are based on this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use log::kv::Value;
+    ///
+    /// let status = Value::from(503u16);
+    ///
+    /// assert_eq!(Some(Ordering::Greater), status.partial_cmp_primitive(&Value::from(500i32)));
+    /// assert_eq!(None, status.partial_cmp_primitive(&Value::from("500")));
+    ///
+    /// // The same comparisons with operators
+    /// assert!(status >= 500);
+    /// assert!(status != "500");
+    /// ```
+    pub fn partial_cmp_primitive(&self, other: &Value) -> Option<Ordering> {
+        match (self.to_primitive()?, other.to_primitive()?) {
+            (Primitive::Signed(a), Primitive::Signed(b)) => a.partial_cmp(&b),
+            (Primitive::Unsigned(a), Primitive::Unsigned(b)) => a.partial_cmp(&b),
+            // Unsigned values are only used when they don't fit in an `i128`
+            (Primitive::Signed(_), Primitive::Unsigned(_)) => Some(Ordering::Less),
+            (Primitive::Unsigned(_), Primitive::Signed(_)) => Some(Ordering::Greater),
+            (Primitive::Signed(a), Primitive::Float(b)) => (a as f64).partial_cmp(&b),
+            (Primitive::Unsigned(a), Primitive::Float(b)) => (a as f64).partial_cmp(&b),
+            (Primitive::Float(a), Primitive::Signed(b)) => a.partial_cmp(&(b as f64)),
+            (Primitive::Float(a), Primitive::Unsigned(b)) => a.partial_cmp(&(b as f64)),
+            (Primitive::Float(a), Primitive::Float(b)) => a.partial_cmp(&b),
+            (Primitive::Bool(a), Primitive::Bool(b)) => a.partial_cmp(&b),
+            (Primitive::Char(a), Primitive::Char(b)) => a.partial_cmp(&b),
+            (Primitive::Str(a), Primitive::Str(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+
+    fn to_primitive(&self) -> Option<Primitive<'v>> {
+        if let Some(v) = self.to_i128() {
+            Some(Primitive::Signed(v))
+        } else if let Some(v) = self.to_u128() {
+            Some(Primitive::Unsigned(v))
+        } else if let Some(v) = self.to_f64() {
+            Some(Primitive::Float(v))
+        } else if let Some(v) = self.to_bool() {
+            Some(Primitive::Bool(v))
+        } else if let Some(v) = self.to_char() {
+            Some(Primitive::Char(v))
+        } else {
+            self.to_borrowed_str().map(Primitive::Str)
+        }
+    }
+}
+
+// A value coerced for comparison
+enum Primitive<'v> {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(&'v str),
+}
+
+macro_rules! impl_value_cmp_primitive {
+    ($($ty:ty,)*) => {
+        $(
+            impl<'v> PartialEq<$ty> for Value<'v> {
+                fn eq(&self, other: &$ty) -> bool {
+                    self.partial_cmp_primitive(&Value::from(*other)) == Some(Ordering::Equal)
+                }
+            }
+
+            impl<'v> PartialOrd<$ty> for Value<'v> {
+                fn partial_cmp(&self, other: &$ty) -> Option<Ordering> {
+                    self.partial_cmp_primitive(&Value::from(*other))
+                }
+            }
+        )*
+    };
+}
+
+impl_value_cmp_primitive![
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128, f32, f64, char, bool, &str,
+];
+
+impl<'v> PartialEq<str> for Value<'v> {
+    fn eq(&self, other: &str) -> bool {
+        self.partial_cmp_primitive(&Value::from(other)) == Some(Ordering::Equal)
+    }
+}
+
+impl<'v> PartialOrd<str> for Value<'v> {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        self.partial_cmp_primitive(&Value::from(other))
+    }
 }
 
 #[cfg(feature = "kv_std")]
 mod std_support {
     use std::borrow::Cow;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+    use std::path::{Path, PathBuf};
     use std::rc::Rc;
     use std::sync::Arc;
 
@@ -432,6 +1015,53 @@ mod std_support {
         }
     }
 
+    // Addresses are captured using `Display`, so they're formatted and
+    // serialized as plain strings like `127.0.0.1:8080`
+    macro_rules! impl_to_value_display {
+        ($($ty:ty,)*) => {
+            $(
+                impl ToValue for $ty {
+                    fn to_value(&self) -> Value {
+                        Value::from_display(self)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_to_value_display![
+        IpAddr,
+        Ipv4Addr,
+        Ipv6Addr,
+        SocketAddr,
+        SocketAddrV4,
+        SocketAddrV6,
+    ];
+
+    /// Paths that are valid UTF-8 are captured as strings, so they can be
+    /// retrieved with [`Value::to_borrowed_str`]. Other paths can't be borrowed as
+    /// strings, so they're captured as `null`.
+    impl ToValue for Path {
+        fn to_value(&self) -> Value {
+            Value::from(self)
+        }
+    }
+
+    impl ToValue for PathBuf {
+        fn to_value(&self) -> Value {
+            Value::from(&**self)
+        }
+    }
+
+    impl<'v> From<&'v Path> for Value<'v> {
+        fn from(v: &'v Path) -> Self {
+            match v.to_str() {
+                Some(v) => Value::from(v),
+                None => Value::null(),
+            }
+        }
+    }
+
     impl<'v> Value<'v> {
         /// Try convert this value into a string.
         pub fn to_cow_str(&self) -> Option<Cow<'v, str>> {
@@ -444,6 +1074,906 @@ mod std_support {
             Value::from(&**v)
         }
     }
+
+    /// An owned copy of a [`Value`].
+    ///
+    /// Primitives, strings, and `null` are kept as they are. Any other value,
+    /// such as one captured using `Debug`, `serde`, or `sval`, is stored as its
+    /// formatted string, so its structure isn't preserved.
+    ///
+    /// Requires the `kv_std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::kv::{OwnedValue, ToValue, Value};
+    ///
+    /// let owned = OwnedValue::from(Value::from(42));
+    ///
+    /// assert_eq!(Some(42), owned.to_value().to_i64());
+    /// ```
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct OwnedValue(Owned);
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Owned {
+        Null,
+        Bool(bool),
+        Char(char),
+        U64(u64),
+        I64(i64),
+        U128(u128),
+        I128(i128),
+        F64(f64),
+        Str(String),
+    }
+
+    impl<'v> From<Value<'v>> for OwnedValue {
+        fn from(value: Value<'v>) -> Self {
+            struct Capture(Option<Owned>);
+
+            impl<'v> VisitValue<'v> for Capture {
+                fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                    self.0 = Some(Owned::Str(value.to_string()));
+                    Ok(())
+                }
+
+                fn visit_null(&mut self) -> Result<(), Error> {
+                    self.0 = Some(Owned::Null);
+                    Ok(())
+                }
+
+                fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                    self.0 = Some(Owned::U64(value));
+                    Ok(())
+                }
+
+                fn visit_i64(&mut self, value: i64) -> Result<(), Error> {
+                    self.0 = Some(Owned::I64(value));
+                    Ok(())
+                }
+
+                fn visit_u128(&mut self, value: u128) -> Result<(), Error> {
+                    self.0 = Some(Owned::U128(value));
+                    Ok(())
+                }
+
+                fn visit_i128(&mut self, value: i128) -> Result<(), Error> {
+                    self.0 = Some(Owned::I128(value));
+                    Ok(())
+                }
+
+                fn visit_f64(&mut self, value: f64) -> Result<(), Error> {
+                    self.0 = Some(Owned::F64(value));
+                    Ok(())
+                }
+
+                fn visit_bool(&mut self, value: bool) -> Result<(), Error> {
+                    self.0 = Some(Owned::Bool(value));
+                    Ok(())
+                }
+
+                fn visit_str(&mut self, value: &str) -> Result<(), Error> {
+                    self.0 = Some(Owned::Str(value.into()));
+                    Ok(())
+                }
+
+                fn visit_char(&mut self, value: char) -> Result<(), Error> {
+                    self.0 = Some(Owned::Char(value));
+                    Ok(())
+                }
+
+                fn visit_error(
+                    &mut self,
+                    err: &(dyn std::error::Error + 'static),
+                ) -> Result<(), Error> {
+                    self.0 = Some(Owned::Str(err.to_string()));
+                    Ok(())
+                }
+            }
+
+            let mut capture = Capture(None);
+            let _ = value.visit(&mut capture);
+
+            OwnedValue(capture.0.unwrap_or_else(|| Owned::Str(value.to_string())))
+        }
+    }
+
+    impl ToValue for OwnedValue {
+        fn to_value(&self) -> Value {
+            match self.0 {
+                Owned::Null => Value::null(),
+                Owned::Bool(v) => Value::from(v),
+                Owned::Char(v) => Value::from(v),
+                Owned::U64(v) => Value::from(v),
+                Owned::I64(v) => Value::from(v),
+                Owned::U128(v) => Value::from(v),
+                Owned::I128(v) => Value::from(v),
+                Owned::F64(v) => Value::from(v),
+                Owned::Str(ref v) => Value::from(&**v),
+            }
+        }
+    }
+
+    impl fmt::Display for OwnedValue {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&self.to_value(), f)
+        }
+    }
+}
+
+#[cfg(feature = "kv_std")]
+pub use self::std_support::OwnedValue;
+
+#[cfg(feature = "kv_serde_json")]
+mod serde_json_support {
+    use super::*;
+
+    impl ToValue for serde_json::Value {
+        fn to_value(&self) -> Value {
+            match self {
+                serde_json::Value::Null => Value::null(),
+                serde_json::Value::Bool(v) => Value::from(*v),
+                serde_json::Value::Number(v) => {
+                    if let Some(v) = v.as_u64() {
+                        Value::from(v)
+                    } else if let Some(v) = v.as_i64() {
+                        Value::from(v)
+                    } else if let Some(v) = v.as_f64() {
+                        Value::from(v)
+                    } else {
+                        Value::from_serde(v)
+                    }
+                }
+                serde_json::Value::String(v) => Value::from(&**v),
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                    Value::from_serde(self)
+                }
+            }
+        }
+    }
+
+    impl<'v> From<&'v serde_json::Value> for Value<'v> {
+        fn from(v: &'v serde_json::Value) -> Self {
+            v.to_value()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn json_to_value() {
+            let json = serde_json::json!({
+                "null": null,
+                "bool": true,
+                "uint": 1,
+                "int": -1,
+                "float": 1.5,
+                "str": "a string",
+                "array": [1, 2],
+                "object": { "a": 1 },
+            });
+
+            let value = |key: &str| json[key].to_value();
+
+            assert_eq!(inner::Token::None, value("null").to_token());
+            assert_eq!(Some(true), value("bool").to_bool());
+            assert_eq!(Some(1), value("uint").to_u64());
+            assert_eq!(Some(-1), value("int").to_i64());
+            assert_eq!(Some(1.5), value("float").to_f64());
+            assert_eq!(Some("a string"), value("str").to_borrowed_str());
+            assert_eq!("[1,2]", serde_json::to_string(&value("array")).unwrap());
+            assert_eq!(
+                "{\"a\":1}",
+                serde_json::to_string(&value("object")).unwrap()
+            );
+        }
+    }
+}
+
+#[cfg(feature = "kv_uuid")]
+mod uuid_support {
+    use super::*;
+
+    impl ToValue for uuid::Uuid {
+        fn to_value(&self) -> Value {
+            Value::from_display(self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn uuid_to_value() {
+            let uuid = uuid::Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+
+            assert_eq!(
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                uuid.to_value().to_string()
+            );
+        }
+    }
+}
+
+// Write fractional seconds with 3, 6, or 9 digits, or nothing if they're zero
+fn write_nanos(f: &mut fmt::Formatter, nanos: u32) -> fmt::Result {
+    if nanos == 0 {
+        Ok(())
+    } else if nanos % 1_000_000 == 0 {
+        write!(f, ".{:03}", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        write!(f, ".{:06}", nanos / 1_000)
+    } else {
+        write!(f, ".{:09}", nanos)
+    }
+}
+
+// Parse fractional seconds written by `write_nanos`, without the `.`
+fn parse_nanos(s: &str) -> Option<u32> {
+    if s.is_empty() || s.len() > 9 {
+        return None;
+    }
+
+    let nanos = parse_digits(s)? as u32;
+    Some(nanos * 10u32.pow(9 - s.len() as u32))
+}
+
+// Parse a non-empty string of ASCII digits
+fn parse_digits(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+mod duration_support {
+    use super::*;
+
+    use std::time::Duration;
+
+    // `Duration`'s own `Debug` picks a unit based on its size, so durations
+    // are cast to this wrapper instead
+    #[repr(transparent)]
+    struct DisplaySeconds(Duration);
+
+    impl fmt::Display for DisplaySeconds {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0.as_secs())?;
+            write_nanos(f, self.0.subsec_nanos())?;
+            f.write_str("s")
+        }
+    }
+
+    impl ToValue for Duration {
+        fn to_value(&self) -> Value {
+            // SAFETY: `DisplaySeconds` is `repr(transparent)` over `Duration`
+            let v = unsafe { &*(self as *const Duration as *const DisplaySeconds) };
+
+            Value::from_display(v)
+        }
+    }
+
+    impl<'v> Value<'v> {
+        /// Try convert this value into a `Duration`.
+        ///
+        /// This reverses the way a `Duration` is captured, so it works for
+        /// durations captured with [`ToValue`], and for strings in the same
+        /// form, like `1.250s`.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::time::Duration;
+        /// use log::kv::{ToValue, Value};
+        ///
+        /// let elapsed = Duration::from_millis(1250);
+        ///
+        /// assert_eq!(Some(elapsed), elapsed.to_value().to_duration());
+        /// assert_eq!(Some(elapsed), Value::from("1.250s").to_duration());
+        /// ```
+        ///
+        /// [`ToValue`]: trait.ToValue.html
+        pub fn to_duration(&self) -> Option<Duration> {
+            let mut buf = [0; 32];
+            parse_seconds(self.to_str_in(&mut buf)?)
+        }
+    }
+
+    fn parse_seconds(s: &str) -> Option<Duration> {
+        let s = s.strip_suffix('s')?;
+
+        let (secs, nanos) = match s.split_once('.') {
+            Some((secs, nanos)) => (secs, parse_nanos(nanos)?),
+            None => (s, 0),
+        };
+
+        Some(Duration::new(parse_digits(secs)?, nanos))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn duration_roundtrip() {
+            for (duration, expected) in [
+                (Duration::ZERO, "0s"),
+                (Duration::from_nanos(1), "0.000000001s"),
+                (Duration::from_micros(1500), "0.001500s"),
+                (Duration::from_millis(1250), "1.250s"),
+                (Duration::from_secs(86_400), "86400s"),
+                (Duration::MAX, "18446744073709551615.999999999s"),
+            ] {
+                let value = duration.to_value();
+
+                assert_eq!(expected, value.to_string());
+                assert_eq!(Some(duration), value.to_duration());
+                assert_eq!(Some(duration), Value::from(expected).to_duration());
+            }
+        }
+
+        #[test]
+        fn to_duration_invalid() {
+            for invalid in ["", "s", "1", "1.s", "-1s", "+1s", "1.0000000001s", "1.5ms"] {
+                assert_eq!(None, Value::from(invalid).to_duration(), "{}", invalid);
+            }
+
+            assert_eq!(None, Value::from(1.5).to_duration());
+        }
+    }
+}
+
+// The fields of a timestamp, formatted as RFC 3339
+#[cfg(any(feature = "kv_std", feature = "kv_time", feature = "kv_chrono"))]
+struct Rfc3339 {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    offset_seconds: i32,
+}
+
+#[cfg(any(feature = "kv_std", feature = "kv_time", feature = "kv_chrono"))]
+impl fmt::Display for Rfc3339 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Leap seconds are represented by a nanosecond past one second
+        let (second, nanosecond) = if self.nanosecond >= 1_000_000_000 {
+            (self.second + 1, self.nanosecond - 1_000_000_000)
+        } else {
+            (self.second, self.nanosecond)
+        };
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, second
+        )?;
+
+        write_nanos(f, nanosecond)?;
+
+        if self.offset_seconds == 0 {
+            f.write_str("Z")
+        } else {
+            let sign = if self.offset_seconds < 0 { '-' } else { '+' };
+            let minutes = self.offset_seconds.unsigned_abs() / 60;
+
+            write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+        }
+    }
+}
+
+#[cfg(feature = "kv_std")]
+impl Rfc3339 {
+    // Parse a timestamp in the form it's written in
+    fn parse(s: &str) -> Option<Rfc3339> {
+        fn two_digits(s: &str) -> Option<u8> {
+            if s.len() != 2 {
+                return None;
+            }
+
+            parse_digits(s).map(|v| v as u8)
+        }
+
+        let (date, time) = s.split_once('T')?;
+
+        // The year has at least 4 digits, and may be negative
+        let (year, month_day) = date.split_at(date.len().checked_sub(6)?);
+        let (sign, digits) = match year.strip_prefix('-') {
+            Some(digits) => (-1, digits),
+            None => (1, year),
+        };
+        if digits.len() < 4 {
+            return None;
+        }
+        let year = sign * i32::try_from(parse_digits(digits)?).ok()?;
+        let month = two_digits(month_day.strip_prefix('-')?.get(..2)?)?;
+        let day = two_digits(month_day.get(4..)?)?;
+        if month_day.get(3..4)? != "-" || !(1..=12).contains(&month) || day == 0 {
+            return None;
+        }
+
+        let (time, offset_seconds) = if let Some(time) = time.strip_suffix('Z') {
+            (time, 0)
+        } else {
+            let (time, offset) = time.split_at(time.len().checked_sub(6)?);
+            let sign = match offset.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let hours = two_digits(offset.get(1..3)?)?;
+            let minutes = two_digits(offset.get(4..)?)?;
+            if offset.get(3..4)? != ":" || hours > 23 || minutes > 59 {
+                return None;
+            }
+
+            (
+                time,
+                sign * (i32::from(hours) * 3600 + i32::from(minutes) * 60),
+            )
+        };
+
+        let (time, nanosecond) = match time.split_once('.') {
+            Some((time, nanos)) => (time, parse_nanos(nanos)?),
+            None => (time, 0),
+        };
+        let hour = two_digits(time.get(..2)?)?;
+        let minute = two_digits(time.get(3..5)?)?;
+        let second = two_digits(time.get(6..)?)?;
+        if time.get(2..3)? != ":" || time.get(5..6)? != ":" {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        Some(Rfc3339 {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            offset_seconds,
+        })
+    }
+}
+
+// Convert between days since the Unix epoch and dates in the proleptic
+// Gregorian calendar
+//
+// These are Howard Hinnant's `days_from_civil` and `civil_from_days`.
+#[cfg(feature = "kv_std")]
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let (month, day) = (i64::from(month), i64::from(day));
+    let year = if month <= 2 { year - 1 } else { year };
+
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(feature = "kv_std")]
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+
+    (
+        if month <= 2 { year + 1 } else { year },
+        month as u8,
+        day as u8,
+    )
+}
+
+#[cfg(feature = "kv_std")]
+mod system_time_support {
+    use super::*;
+
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    // `SystemTime` doesn't implement `Display`, so timestamps are cast to
+    // this wrapper instead
+    #[repr(transparent)]
+    struct DisplayRfc3339(SystemTime);
+
+    impl fmt::Display for DisplayRfc3339 {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            // Whole seconds are rounded down, so times before the epoch
+            // still have positive nanoseconds
+            let (secs, nanosecond) = match self.0.duration_since(UNIX_EPOCH) {
+                Ok(since) => (i64::try_from(since.as_secs()).ok(), since.subsec_nanos()),
+                Err(err) => {
+                    let before = err.duration();
+                    let secs = i64::try_from(before.as_secs()).ok().map(|secs| -secs);
+
+                    match before.subsec_nanos() {
+                        0 => (secs, 0),
+                        nanos => (
+                            secs.and_then(|secs| secs.checked_sub(1)),
+                            1_000_000_000 - nanos,
+                        ),
+                    }
+                }
+            };
+
+            let date = secs.and_then(|secs| {
+                let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+                Some((
+                    i32::try_from(year).ok()?,
+                    month,
+                    day,
+                    secs.rem_euclid(86_400),
+                ))
+            });
+
+            // Times too far from the epoch for a year to represent fall back
+            // to their `Debug` output
+            let (year, month, day, secs) = match date {
+                Some(date) => date,
+                None => return fmt::Debug::fmt(&self.0, f),
+            };
+
+            fmt::Display::fmt(
+                &Rfc3339 {
+                    year,
+                    month,
+                    day,
+                    hour: (secs / 3600) as u8,
+                    minute: (secs / 60 % 60) as u8,
+                    second: (secs % 60) as u8,
+                    nanosecond,
+                    offset_seconds: 0,
+                },
+                f,
+            )
+        }
+    }
+
+    impl ToValue for SystemTime {
+        fn to_value(&self) -> Value {
+            // SAFETY: `DisplayRfc3339` is `repr(transparent)` over `SystemTime`
+            let v = unsafe { &*(self as *const SystemTime as *const DisplayRfc3339) };
+
+            Value::from_display(v)
+        }
+    }
+
+    impl<'v> Value<'v> {
+        /// Try convert this value into a `SystemTime`.
+        ///
+        /// This reverses the way timestamps are captured, so it works for
+        /// `SystemTime`s captured with [`ToValue`], timestamps from other
+        /// crates like `time` and `chrono`, and strings in the same RFC 3339
+        /// form, like `2024-05-01T14:30:00.250+02:00`. The UTC offset is
+        /// applied, so it's lost. A leap second is treated as the first
+        /// second of the next minute.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+        /// use log::kv::{ToValue, Value};
+        ///
+        /// let now = SystemTime::now();
+        /// assert_eq!(Some(now), now.to_value().to_timestamp());
+        ///
+        /// let timestamp = Value::from("2024-05-01T14:30:00.250+02:00").to_timestamp();
+        /// assert_eq!(
+        ///     Some(UNIX_EPOCH + Duration::from_millis(1_714_566_600_250)),
+        ///     timestamp,
+        /// );
+        /// ```
+        ///
+        /// [`ToValue`]: trait.ToValue.html
+        pub fn to_timestamp(&self) -> Option<SystemTime> {
+            let mut buf = [0; 64];
+            let v = Rfc3339::parse(self.to_str_in(&mut buf)?)?;
+
+            let days = days_from_civil(i64::from(v.year), v.month, v.day);
+            if civil_from_days(days) != (i64::from(v.year), v.month, v.day) {
+                // The day doesn't exist in the month
+                return None;
+            }
+
+            let secs = days * 86_400
+                + i64::from(v.hour) * 3600
+                + i64::from(v.minute) * 60
+                + i64::from(v.second)
+                - i64::from(v.offset_seconds);
+
+            if secs >= 0 {
+                UNIX_EPOCH.checked_add(Duration::new(secs as u64, v.nanosecond))
+            } else {
+                UNIX_EPOCH
+                    .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+                    .checked_add(Duration::from_nanos(u64::from(v.nanosecond)))
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn system_time_roundtrip() {
+            for (secs, nanos, expected) in [
+                (0i64, 0, "1970-01-01T00:00:00Z"),
+                (1_714_566_600, 250_000_000, "2024-05-01T12:30:00.250Z"),
+                (951_782_400, 1, "2000-02-29T00:00:00.000000001Z"),
+                (-1, 500_000, "1969-12-31T23:59:59.000500Z"),
+                (-62_135_596_800, 0, "0001-01-01T00:00:00Z"),
+                (253_402_300_799, 0, "9999-12-31T23:59:59Z"),
+            ] {
+                let time = if secs >= 0 {
+                    UNIX_EPOCH + Duration::new(secs as u64, nanos)
+                } else {
+                    UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+                        + Duration::from_nanos(u64::from(nanos))
+                };
+                let value = time.to_value();
+
+                assert_eq!(expected, value.to_string());
+                assert_eq!(Some(time), value.to_timestamp());
+                assert_eq!(Some(time), Value::from(expected).to_timestamp());
+            }
+        }
+
+        #[test]
+        fn to_timestamp_offsets() {
+            let expected = UNIX_EPOCH + Duration::from_secs(1_714_566_600);
+
+            for timestamp in [
+                "2024-05-01T12:30:00Z",
+                "2024-05-01T14:30:00+02:00",
+                "2024-05-01T03:00:00-09:30",
+            ] {
+                assert_eq!(
+                    Some(expected),
+                    Value::from(timestamp).to_timestamp(),
+                    "{}",
+                    timestamp
+                );
+            }
+
+            // Leap seconds are the start of the next minute
+            assert_eq!(
+                Value::from("2017-01-01T00:00:00Z").to_timestamp(),
+                Value::from("2016-12-31T23:59:60Z").to_timestamp(),
+            );
+        }
+
+        #[test]
+        fn to_timestamp_invalid() {
+            for invalid in [
+                "",
+                "2024-05-01",
+                "2024-05-01T12:30:00",
+                "2024-05-01 12:30:00Z",
+                "2024-13-01T12:30:00Z",
+                "2023-02-29T12:30:00Z",
+                "2024-05-01T24:00:00Z",
+                "2024-05-01T12:30:00.Z",
+                "2024-05-01T12:30:00+2:00",
+                "24-05-01T12:30:00Z",
+                "2024-5-01T12:30:00Z",
+            ] {
+                assert_eq!(None, Value::from(invalid).to_timestamp(), "{}", invalid);
+            }
+
+            assert_eq!(None, Value::from(1_714_566_600).to_timestamp());
+        }
+    }
+}
+
+#[cfg(feature = "kv_time")]
+mod time_support {
+    use super::*;
+
+    // `OffsetDateTime`'s own `Display` isn't RFC 3339, so timestamps are cast
+    // to this wrapper instead
+    #[repr(transparent)]
+    struct DisplayRfc3339(time::OffsetDateTime);
+
+    impl fmt::Display for DisplayRfc3339 {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let v = &self.0;
+
+            fmt::Display::fmt(
+                &Rfc3339 {
+                    year: v.year(),
+                    month: v.month() as u8,
+                    day: v.day(),
+                    hour: v.hour(),
+                    minute: v.minute(),
+                    second: v.second(),
+                    nanosecond: v.nanosecond(),
+                    offset_seconds: v.offset().whole_seconds(),
+                },
+                f,
+            )
+        }
+    }
+
+    impl ToValue for time::OffsetDateTime {
+        fn to_value(&self) -> Value {
+            // SAFETY: `DisplayRfc3339` is `repr(transparent)` over `OffsetDateTime`
+            let v = unsafe { &*(self as *const time::OffsetDateTime as *const DisplayRfc3339) };
+
+            Value::from_display(v)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use time::{Duration, OffsetDateTime, UtcOffset};
+
+        #[test]
+        fn offset_date_time_to_value() {
+            let v = OffsetDateTime::from_unix_timestamp(1_714_566_600).unwrap();
+
+            assert_eq!("2024-05-01T12:30:00Z", v.to_value().to_string());
+
+            let v =
+                (v + Duration::milliseconds(250)).to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+            assert_eq!("2024-05-01T14:30:00.250+02:00", v.to_value().to_string());
+
+            let v =
+                (v + Duration::nanoseconds(1)).to_offset(UtcOffset::from_hms(-9, -30, 0).unwrap());
+            assert_eq!(
+                "2024-05-01T03:00:00.250000001-09:30",
+                v.to_value().to_string()
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "kv_std")]
+        fn offset_date_time_to_timestamp() {
+            use std::time::{Duration, UNIX_EPOCH};
+
+            let utc = OffsetDateTime::from_unix_timestamp_nanos(1_714_566_600_250_000_001).unwrap();
+            let expected = UNIX_EPOCH + Duration::new(1_714_566_600, 250_000_001);
+
+            for v in [
+                utc,
+                utc.to_offset(UtcOffset::from_hms(2, 0, 0).unwrap()),
+                utc.to_offset(UtcOffset::from_hms(-9, -30, 0).unwrap()),
+            ] {
+                assert_eq!(Some(expected), v.to_value().to_timestamp());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "kv_chrono")]
+mod chrono_support {
+    use super::*;
+
+    use chrono::{DateTime, Datelike, Offset, TimeZone, Timelike};
+
+    // `DateTime`'s own `Display` isn't RFC 3339, so timestamps are cast to
+    // this wrapper instead
+    #[repr(transparent)]
+    struct DisplayRfc3339<Tz: TimeZone>(DateTime<Tz>);
+
+    impl<Tz: TimeZone> fmt::Display for DisplayRfc3339<Tz> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let v = self.0.naive_local();
+
+            fmt::Display::fmt(
+                &Rfc3339 {
+                    year: v.year(),
+                    month: v.month() as u8,
+                    day: v.day() as u8,
+                    hour: v.hour() as u8,
+                    minute: v.minute() as u8,
+                    second: v.second() as u8,
+                    nanosecond: v.nanosecond(),
+                    offset_seconds: self.0.offset().fix().local_minus_utc(),
+                },
+                f,
+            )
+        }
+    }
+
+    impl<Tz: TimeZone> ToValue for DateTime<Tz> {
+        fn to_value(&self) -> Value {
+            // SAFETY: `DisplayRfc3339<Tz>` is `repr(transparent)` over `DateTime<Tz>`
+            let v = unsafe { &*(self as *const DateTime<Tz> as *const DisplayRfc3339<Tz>) };
+
+            Value::from_display(v)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use chrono::{FixedOffset, NaiveDate, Utc};
+
+        #[test]
+        fn date_time_to_value() {
+            let naive = NaiveDate::from_ymd_opt(2024, 5, 1)
+                .unwrap()
+                .and_hms_milli_opt(12, 30, 0, 250)
+                .unwrap();
+
+            let utc = Utc.from_utc_datetime(&naive);
+            assert_eq!("2024-05-01T12:30:00.250Z", utc.to_value().to_string());
+
+            let offset = utc.with_timezone(&FixedOffset::east_opt(2 * 3600).unwrap());
+            assert_eq!(
+                "2024-05-01T14:30:00.250+02:00",
+                offset.to_value().to_string()
+            );
+
+            let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+                .unwrap()
+                .and_hms_nano_opt(23, 59, 59, 1_000_000_000)
+                .unwrap();
+            assert_eq!(
+                "2016-12-31T23:59:60Z",
+                Utc.from_utc_datetime(&leap).to_value().to_string()
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "kv_std")]
+        fn date_time_to_timestamp() {
+            use std::time::{Duration, UNIX_EPOCH};
+
+            let naive = NaiveDate::from_ymd_opt(2024, 5, 1)
+                .unwrap()
+                .and_hms_nano_opt(12, 30, 0, 250_000_001)
+                .unwrap();
+            let utc = Utc.from_utc_datetime(&naive);
+            let expected = UNIX_EPOCH + Duration::new(1_714_566_600, 250_000_001);
+
+            assert_eq!(Some(expected), utc.to_value().to_timestamp());
+            assert_eq!(
+                Some(expected),
+                utc.with_timezone(&FixedOffset::west_opt(9 * 3600 + 1800).unwrap())
+                    .to_value()
+                    .to_timestamp()
+            );
+
+            let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+                .unwrap()
+                .and_hms_nano_opt(23, 59, 59, 1_000_000_000)
+                .unwrap();
+            assert_eq!(
+                Some(UNIX_EPOCH + Duration::from_secs(1_483_228_800)),
+                Utc.from_utc_datetime(&leap).to_value().to_timestamp()
+            );
+        }
+    }
 }
 
 /// A visitor for a [`Value`].
@@ -596,6 +2126,70 @@ where
     }
 }
 
+/// A value that's produced lazily, when it's visited.
+///
+/// Values captured with [`Value::from_fill`] don't do any work until a logger
+/// inspects them, whether through [`Value::visit`], formatting, or one of the
+/// `to_*` conversions. This is useful for values that are only cheap to produce
+/// at that point, like a snapshot of some shared state.
+///
+/// `Fill` is implemented for closures that accept a [`Slot`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// use log::kv::{Slot, Value};
+///
+/// let connections = AtomicU64::new(3);
+///
+/// // `connections` is only read when the value is inspected
+/// let fill = |slot: Slot| slot.fill_any(connections.load(Ordering::Relaxed));
+/// let value = Value::from_fill(&fill);
+///
+/// connections.store(4, Ordering::Relaxed);
+///
+/// assert_eq!(Some(4), value.to_u64());
+/// ```
+pub trait Fill {
+    /// Fill the slot with a value.
+    ///
+    /// This method may be called any number of times, or not at all, so it
+    /// shouldn't have side-effects.
+    fn fill(&self, slot: Slot) -> Result<(), Error>;
+}
+
+impl<F> Fill for F
+where
+    F: Fn(Slot) -> Result<(), Error>,
+{
+    fn fill(&self, slot: Slot) -> Result<(), Error> {
+        (self)(slot)
+    }
+}
+
+/// A slot to put the value produced by a [`Fill`] into.
+pub struct Slot<'s, 'f> {
+    inner: inner::Slot<'s, 'f>,
+}
+
+impl<'s, 'f> fmt::Debug for Slot<'s, 'f> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Slot").finish()
+    }
+}
+
+impl<'s, 'f> Slot<'s, 'f> {
+    /// Fill the slot with a value.
+    pub fn fill_any<T>(self, value: T) -> Result<(), Error>
+    where
+        T: Into<Value<'f>>,
+    {
+        inner::fill_slot(self.inner, value.into())
+    }
+}
+
 #[cfg(feature = "value-bag")]
 pub(in crate::kv) mod inner {
     /**
@@ -611,9 +2205,42 @@ pub(in crate::kv) mod inner {
 
     pub use value_bag::Error;
 
+    pub use value_bag::fill::Slot;
+
     #[cfg(test)]
     pub use value_bag::test::TestToken as Token;
 
+    // Our `Fill` trait can't be implemented for `value_bag`'s directly,
+    // so values are cast to this wrapper instead
+    #[repr(transparent)]
+    struct FillAdapter<T>(T);
+
+    impl<T> value_bag::fill::Fill for FillAdapter<T>
+    where
+        T: Fill,
+    {
+        fn fill(&self, slot: Slot) -> Result<(), Error> {
+            self.0
+                .fill(super::Slot { inner: slot })
+                .map_err(crate::kv::Error::into_value)
+        }
+    }
+
+    pub fn from_fill<'v, T>(value: &'v T) -> Inner<'v>
+    where
+        T: Fill,
+    {
+        // SAFETY: `FillAdapter<T>` is `repr(transparent)` over `T`
+        let adapter = unsafe { &*(value as *const T as *const FillAdapter<T>) };
+
+        Inner::from_fill(adapter)
+    }
+
+    pub fn fill_slot<'f>(slot: Slot<'_, 'f>, value: Value<'f>) -> Result<(), crate::kv::Error> {
+        slot.fill_any(value.inner)
+            .map_err(crate::kv::Error::from_value)
+    }
+
     pub fn visit<'v>(
         inner: &Inner<'v>,
         visitor: impl VisitValue<'v>,
@@ -626,7 +2253,10 @@ pub(in crate::kv) mod inner {
         {
             fn visit_any(&mut self, value: value_bag::ValueBag) -> Result<(), Error> {
                 self.0
-                    .visit_any(Value { inner: value })
+                    .visit_any(Value {
+                        inner: value,
+                        format: None,
+                    })
                     .map_err(crate::kv::Error::into_value)
             }
 
@@ -745,6 +2375,114 @@ pub(in crate::kv) mod inner {
         U128(u128),
         Debug(&'v dyn fmt::Debug),
         Display(&'v dyn fmt::Display),
+        Fill(&'v dyn Fill),
+    }
+
+    pub struct Slot<'s, 'f> {
+        visitor: &'s mut dyn VisitValue<'f>,
+    }
+
+    pub fn from_fill<'v, T>(value: &'v T) -> Inner<'v>
+    where
+        T: Fill,
+    {
+        Inner::Fill(value)
+    }
+
+    pub fn fill_slot<'f>(slot: Slot<'_, 'f>, value: Value<'f>) -> Result<(), crate::kv::Error> {
+        value.visit(slot.visitor)
+    }
+
+    fn fill<'v>(
+        value: &'v dyn Fill,
+        visitor: &mut dyn VisitValue<'v>,
+    ) -> Result<(), crate::kv::Error> {
+        value.fill(super::Slot {
+            inner: Slot { visitor },
+        })
+    }
+
+    // Fill a value and then convert the primitive it was filled with
+    fn fill_to<'v, R>(value: &'v dyn Fill, to: impl FnOnce(&Inner<'v>) -> Option<R>) -> Option<R> {
+        struct Capture<'v>(Option<Inner<'v>>);
+
+        impl<'v> VisitValue<'v> for Capture<'v> {
+            fn visit_any(&mut self, _: Value) -> Result<(), crate::kv::Error> {
+                Ok(())
+            }
+
+            fn visit_null(&mut self) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::None);
+                Ok(())
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::U64(value));
+                Ok(())
+            }
+
+            fn visit_i64(&mut self, value: i64) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::I64(value));
+                Ok(())
+            }
+
+            fn visit_u128(&mut self, value: u128) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::U128(value));
+                Ok(())
+            }
+
+            fn visit_i128(&mut self, value: i128) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::I128(value));
+                Ok(())
+            }
+
+            fn visit_f64(&mut self, value: f64) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::F64(value));
+                Ok(())
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::Bool(value));
+                Ok(())
+            }
+
+            fn visit_borrowed_str(&mut self, value: &'v str) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::Str(value));
+                Ok(())
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), crate::kv::Error> {
+                self.0 = Some(Inner::Char(value));
+                Ok(())
+            }
+        }
+
+        let mut capture = Capture(None);
+        fill(value, &mut capture).ok()?;
+
+        capture.0.as_ref().and_then(to)
+    }
+
+    // Fill a value and then format it
+    fn fill_fmt(value: &dyn Fill, f: &mut fmt::Formatter, debug: bool) -> fmt::Result {
+        struct Format<'a, 'b> {
+            f: &'a mut fmt::Formatter<'b>,
+            debug: bool,
+        }
+
+        impl<'a, 'b, 'v> VisitValue<'v> for Format<'a, 'b> {
+            fn visit_any(&mut self, value: Value) -> Result<(), crate::kv::Error> {
+                if self.debug {
+                    fmt::Debug::fmt(&value, self.f)?;
+                } else {
+                    fmt::Display::fmt(&value, self.f)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        fill(value, &mut Format { f, debug }).map_err(|_| fmt::Error)
     }
 
     impl<'v> From<()> for Inner<'v> {
@@ -869,6 +2607,7 @@ pub(in crate::kv) mod inner {
                 Inner::U128(v) => fmt::Debug::fmt(v, f),
                 Inner::Debug(v) => fmt::Debug::fmt(v, f),
                 Inner::Display(v) => fmt::Display::fmt(v, f),
+                Inner::Fill(v) => fill_fmt(*v, f, true),
             }
         }
     }
@@ -887,6 +2626,7 @@ pub(in crate::kv) mod inner {
                 Inner::U128(v) => fmt::Display::fmt(v, f),
                 Inner::Debug(v) => fmt::Debug::fmt(v, f),
                 Inner::Display(v) => fmt::Display::fmt(v, f),
+                Inner::Fill(v) => fill_fmt(*v, f, false),
             }
         }
     }
@@ -915,6 +2655,7 @@ pub(in crate::kv) mod inner {
         pub fn to_bool(&self) -> Option<bool> {
             match self {
                 Inner::Bool(v) => Some(*v),
+                Inner::Fill(v) => fill_to(*v, Inner::to_bool),
                 _ => None,
             }
         }
@@ -922,6 +2663,7 @@ pub(in crate::kv) mod inner {
         pub fn to_char(&self) -> Option<char> {
             match self {
                 Inner::Char(v) => Some(*v),
+                Inner::Fill(v) => fill_to(*v, Inner::to_char),
                 _ => None,
             }
         }
@@ -945,6 +2687,7 @@ pub(in crate::kv) mod inner {
                     let v: u32 = (*v).try_into().ok()?;
                     v.try_into().ok()
                 }
+                Inner::Fill(v) => fill_to(*v, Inner::to_f64),
                 _ => None,
             }
         }
@@ -955,6 +2698,7 @@ pub(in crate::kv) mod inner {
                 Inner::U64(v) => (*v).try_into().ok(),
                 Inner::I128(v) => (*v).try_into().ok(),
                 Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Fill(v) => fill_to(*v, Inner::to_i64),
                 _ => None,
             }
         }
@@ -965,6 +2709,7 @@ pub(in crate::kv) mod inner {
                 Inner::I64(v) => (*v).try_into().ok(),
                 Inner::I128(v) => (*v).try_into().ok(),
                 Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Fill(v) => fill_to(*v, Inner::to_u64),
                 _ => None,
             }
         }
@@ -975,6 +2720,7 @@ pub(in crate::kv) mod inner {
                 Inner::I64(v) => (*v).try_into().ok(),
                 Inner::U64(v) => (*v).try_into().ok(),
                 Inner::I128(v) => (*v).try_into().ok(),
+                Inner::Fill(v) => fill_to(*v, Inner::to_u128),
                 _ => None,
             }
         }
@@ -985,6 +2731,7 @@ pub(in crate::kv) mod inner {
                 Inner::I64(v) => (*v).try_into().ok(),
                 Inner::U64(v) => (*v).try_into().ok(),
                 Inner::U128(v) => (*v).try_into().ok(),
+                Inner::Fill(v) => fill_to(*v, Inner::to_i128),
                 _ => None,
             }
         }
@@ -992,6 +2739,7 @@ pub(in crate::kv) mod inner {
         pub fn to_borrowed_str(&self) -> Option<&'v str> {
             match self {
                 Inner::Str(v) => Some(v),
+                Inner::Fill(v) => fill_to(*v, Inner::to_borrowed_str),
                 _ => None,
             }
         }
@@ -1010,6 +2758,7 @@ pub(in crate::kv) mod inner {
                 Inner::U128(_) => unimplemented!(),
                 Inner::Debug(_) => unimplemented!(),
                 Inner::Display(_) => unimplemented!(),
+                Inner::Fill(_) => unimplemented!(),
             }
         }
     }
@@ -1042,6 +2791,7 @@ pub(in crate::kv) mod inner {
             Inner::U128(v) => visitor.visit_u128(*v),
             Inner::Debug(v) => visitor.visit_any(Value::from_dyn_debug(*v)),
             Inner::Display(v) => visitor.visit_any(Value::from_dyn_display(*v)),
+            Inner::Fill(v) => fill(*v, &mut visitor),
         }
     }
 }
@@ -1257,6 +3007,77 @@ pub(crate) mod tests {
         assert_eq!(None::<bool>.to_value().to_token(), inner::Token::None);
     }
 
+    #[test]
+    fn test_is_null() {
+        assert!(Value::null().is_null());
+        assert!(().to_value().is_null());
+        assert!(None::<bool>.to_value().is_null());
+        assert!(Value::from_fill(&|slot: Slot| slot.fill_any(Value::null())).is_null());
+
+        for v in unsigned().chain(signed()).chain(str()).chain(bool()) {
+            assert!(!v.is_null());
+            assert!(v.to_option().is_some());
+        }
+
+        assert!(Value::from_debug(&None::<bool>).to_option().is_some());
+        assert!(None::<bool>.to_value().to_option().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_to_serializable() {
+        let data = vec![Some(1), None];
+
+        assert_eq!(
+            "[1,null]",
+            serde_json::to_string(&Value::from_serde(&data).to_serializable()).unwrap()
+        );
+        assert_eq!(
+            "42",
+            serde_json::to_string(&Value::from(42).to_serializable()).unwrap()
+        );
+        assert_eq!(
+            "\"text\"",
+            serde_json::to_string(&Value::from_display(&"text").to_serializable()).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_serde")]
+    fn test_null_serde() {
+        assert_eq!("null", serde_json::to_string(&Value::null()).unwrap());
+        assert_eq!(
+            "null",
+            serde_json::to_string(&None::<bool>.to_value()).unwrap()
+        );
+        assert_eq!(
+            "true",
+            serde_json::to_string(&Some(true).to_value()).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_std")]
+    fn test_owned_value() {
+        for v in unsigned()
+            .chain(signed())
+            .chain(float())
+            .chain(bool())
+            .chain(char())
+            .chain(str())
+        {
+            let owned = OwnedValue::from(v.to_value());
+
+            assert_eq!(v.to_token(), owned.to_value().to_token());
+            assert_eq!(v.to_string(), owned.to_string());
+        }
+
+        assert!(OwnedValue::from(Value::null()).to_value().is_null());
+
+        let debug = OwnedValue::from(Value::from_debug(&Some(1)));
+        assert_eq!(Some("Some(1)"), debug.to_value().to_borrowed_str());
+    }
+
     #[test]
     fn test_to_number() {
         for v in unsigned() {
@@ -1289,6 +3110,86 @@ pub(crate) mod tests {
         assert!(Value::from((u32::MAX as u64) + 1).to_f64().is_none());
     }
 
+    #[test]
+    fn test_estimate_display_len() {
+        let values = [
+            Value::from(0u8),
+            Value::from(u64::MAX),
+            Value::from(i64::MIN),
+            Value::from(u128::MAX),
+            Value::from(1.5f64),
+            Value::from(true),
+            Value::from('\u{1F600}'),
+            Value::from("a string"),
+            Value::null(),
+            Value::from(255u8).with_format_hint(FormatHint::Binary),
+        ];
+
+        for value in values {
+            assert_eq!(value.to_string().len(), value.estimate_display_len());
+        }
+
+        assert_eq!(0, Value::from_debug(&[1, 2, 3]).estimate_display_len());
+    }
+
+    #[test]
+    fn test_format_hint() {
+        let cases = [
+            (FormatHint::LowerHex, "2a"),
+            (FormatHint::UpperHex, "2A"),
+            (FormatHint::Octal, "52"),
+            (FormatHint::Binary, "101010"),
+        ];
+
+        for (hint, expected) in cases {
+            let value = Value::from(42u32).with_format_hint(hint);
+
+            assert_eq!(Some(hint), value.format_hint());
+            assert_eq!(expected, value.to_string());
+            assert_eq!(Some(hint), value.to_value().format_hint());
+        }
+
+        assert_eq!(
+            "0x000000ff",
+            format!(
+                "{:#010}",
+                Value::from(255).with_format_hint(FormatHint::LowerHex)
+            )
+        );
+        assert_eq!(
+            "ffffffffffffffff",
+            Value::from(-1i64)
+                .with_format_hint(FormatHint::LowerHex)
+                .to_string()
+        );
+
+        // Hints are ignored for values that aren't integers
+        assert_eq!(
+            "a",
+            Value::from("a")
+                .with_format_hint(FormatHint::LowerHex)
+                .to_string()
+        );
+        assert_eq!(None, Value::from(42).format_hint());
+    }
+
+    #[test]
+    fn test_to_narrow_number() {
+        assert_eq!(Some(u32::MAX), Value::from(u32::MAX as u64).to_u32());
+        assert_eq!(Some(i8::MIN), Value::from(i8::MIN as i64).to_i8());
+        assert_eq!(
+            Some(7),
+            Value::from(std::num::NonZeroU64::new(7).unwrap()).to_u16()
+        );
+
+        // Values that don't fit aren't truncated
+        assert!(Value::from((u32::MAX as u64) + 1).to_u32().is_none());
+        assert!(Value::from(-1i64).to_u8().is_none());
+        assert!(Value::from((i32::MAX as i64) + 1).to_i32().is_none());
+        assert!(Value::from(u128::MAX).to_usize().is_none());
+        assert!(Value::from("1").to_u32().is_none());
+    }
+
     #[test]
     fn test_to_cow_str() {
         for v in str() {
@@ -1314,6 +3215,68 @@ pub(crate) mod tests {
         }
     }
 
+    #[test]
+    fn test_to_str_in() {
+        let mut buf = [0; 4];
+
+        let short_lived = String::from("short lived");
+        assert_eq!(
+            Some("short lived"),
+            Value::from(&*short_lived).to_str_in(&mut buf)
+        );
+
+        assert_eq!(Some("true"), Value::from(true).to_str_in(&mut buf));
+        assert_eq!(
+            Some("2a"),
+            Value::from(42)
+                .with_format_hint(FormatHint::LowerHex)
+                .to_str_in(&mut buf)
+        );
+        assert_eq!(Some("é"), Value::from('é').to_str_in(&mut buf));
+        assert_eq!(None, Value::from(12345).to_str_in(&mut buf));
+        assert_eq!(None, Value::from(false).to_str_in(&mut buf));
+
+        let display = "display";
+        assert_eq!(None, Value::from_display(&display).to_str_in(&mut buf));
+        assert_eq!(
+            Some("display"),
+            Value::from_display(&display).to_str_in(&mut [0; 16])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "kv_std")]
+    fn test_std_net_and_path() {
+        use std::net::{IpAddr, SocketAddr};
+        use std::path::{Path, PathBuf};
+
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let addr: SocketAddr = "[::1]:8080".parse().unwrap();
+
+        assert_eq!("127.0.0.1", ip.to_value().to_string());
+        assert_eq!("[::1]:8080", addr.to_value().to_string());
+
+        let path = PathBuf::from("/var/log/app.log");
+
+        assert_eq!(Some("/var/log/app.log"), path.to_value().to_borrowed_str());
+        assert_eq!(
+            Some("/var/log/app.log"),
+            Value::from(Path::new("/var/log/app.log")).to_borrowed_str()
+        );
+
+        #[cfg(feature = "kv_serde")]
+        {
+            assert_eq!(
+                "\"[::1]:8080\"",
+                serde_json::to_string(&addr.to_value()).unwrap()
+            );
+            assert_eq!(
+                "\"/var/log/app.log\"",
+                serde_json::to_string(&path.to_value()).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_to_bool() {
         for v in bool() {
@@ -1368,6 +3331,108 @@ pub(crate) mod tests {
         assert_eq!(Some(42), extract.0);
     }
 
+    #[test]
+    fn test_visit_dyn() {
+        #[derive(Debug, PartialEq)]
+        enum Kind {
+            Null,
+            F64,
+            Bool,
+            Char,
+            Str,
+            Any,
+        }
+
+        struct Classify(Option<Kind>);
+
+        impl<'v> VisitValue<'v> for Classify {
+            fn visit_any(&mut self, _: Value) -> Result<(), Error> {
+                self.0 = Some(Kind::Any);
+                Ok(())
+            }
+
+            fn visit_null(&mut self) -> Result<(), Error> {
+                self.0 = Some(Kind::Null);
+                Ok(())
+            }
+
+            fn visit_f64(&mut self, _: f64) -> Result<(), Error> {
+                self.0 = Some(Kind::F64);
+                Ok(())
+            }
+
+            fn visit_bool(&mut self, _: bool) -> Result<(), Error> {
+                self.0 = Some(Kind::Bool);
+                Ok(())
+            }
+
+            fn visit_char(&mut self, _: char) -> Result<(), Error> {
+                self.0 = Some(Kind::Char);
+                Ok(())
+            }
+
+            fn visit_str(&mut self, _: &str) -> Result<(), Error> {
+                self.0 = Some(Kind::Str);
+                Ok(())
+            }
+        }
+
+        for (value, expected) in [
+            (Value::null(), Kind::Null),
+            (Value::from(1.5f64), Kind::F64),
+            (Value::from(true), Kind::Bool),
+            (Value::from('a'), Kind::Char),
+            (Value::from("a"), Kind::Str),
+            (Value::from_debug(&[1, 2]), Kind::Any),
+        ] {
+            let mut classify = Classify(None);
+            let visitor: &mut dyn VisitValue = &mut classify;
+
+            value.visit(visitor).unwrap();
+
+            assert_eq!(Some(expected), classify.0);
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        let fill_u64 = |slot: Slot| slot.fill_any(42u64);
+        let fill_str = |slot: Slot| slot.fill_any("a string");
+        let fill_debug = |slot: Slot| slot.fill_any(Value::from_debug(&Some(1)));
+
+        assert_eq!(Some(42), Value::from_fill(&fill_u64).to_u64());
+        assert_eq!(Some(42), Value::from_fill(&fill_u64).to_i64());
+        assert_eq!("42", Value::from_fill(&fill_u64).to_string());
+
+        assert_eq!(
+            Some("a string"),
+            Value::from_fill(&fill_str).to_borrowed_str()
+        );
+        assert_eq!("a string", Value::from_fill(&fill_str).to_string());
+
+        assert_eq!("Some(1)", format!("{:?}", Value::from_fill(&fill_debug)));
+        assert!(Value::from_fill(&fill_debug).to_u64().is_none());
+
+        struct Extract(Option<u64>);
+
+        impl<'v> VisitValue<'v> for Extract {
+            fn visit_any(&mut self, value: Value) -> Result<(), Error> {
+                unimplemented!("unexpected value: {value:?}")
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), Error> {
+                self.0 = Some(value);
+
+                Ok(())
+            }
+        }
+
+        let mut extract = Extract(None);
+        Value::from_fill(&fill_u64).visit(&mut extract).unwrap();
+
+        assert_eq!(Some(42), extract.0);
+    }
+
     #[test]
     fn test_visit_borrowed_str() {
         struct Extract<'v>(Option<&'v str>);
@@ -1391,4 +3456,33 @@ pub(crate) mod tests {
 
         assert_eq!(Some("A short-lived string"), extract.0);
     }
+
+    #[test]
+    fn test_cmp_primitive() {
+        assert!(Value::from(500u16) == 500i64);
+        assert!(Value::from(500u16) < 500.5f64);
+        assert!(Value::from(-1i8) < 0u8);
+        assert!(Value::from(u128::MAX) > i128::MAX);
+        assert!(Value::from(i128::MIN) < u128::MAX);
+        assert!(Value::from(2.5f64) > 2i32);
+        assert!(Value::from(2.0f32) == 2u64);
+        assert!(Value::from(true) == true);
+        assert!(Value::from('a') < 'b');
+        assert!(Value::from("apple") < "banana");
+        assert!(Value::from("apple") == *"apple");
+
+        assert!(Value::from(500) != "500");
+        assert!(Value::from(1) != true);
+        assert!(Value::from(f64::NAN) != f64::NAN);
+        assert!(Value::from_debug(&500) != 500);
+
+        assert_eq!(
+            None,
+            Value::from(1).partial_cmp_primitive(&Value::from("1"))
+        );
+        assert_eq!(
+            None,
+            Value::from_display(&"a").partial_cmp_primitive(&Value::from("a"))
+        );
+    }
 }
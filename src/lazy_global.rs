@@ -0,0 +1,41 @@
+//! Statics that are allocated the first time they're used.
+//!
+//! `Mutex::new` isn't `const` at the crate's MSRV, so statics that hold one
+//! are boxed and leaked on first use instead.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+pub(crate) struct LazyGlobal<T>(AtomicPtr<T>);
+
+impl<T> LazyGlobal<T> {
+    pub(crate) const fn new() -> Self {
+        LazyGlobal(AtomicPtr::new(ptr::null_mut()))
+    }
+}
+
+impl<T: Send + Sync> LazyGlobal<T> {
+    /// Get the value, creating it with `init` if it hasn't been yet.
+    ///
+    /// If threads race to create it, the first value stored is kept and the
+    /// others are dropped. The value is never freed.
+    pub(crate) fn get_or_init(&'static self, init: impl FnOnce() -> T) -> &'static T {
+        let current = self.0.load(Ordering::Acquire);
+        if !current.is_null() {
+            return unsafe { &*current };
+        }
+
+        let new = Box::into_raw(Box::new(init()));
+        match self
+            .0
+            .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => unsafe { &*new },
+            Err(current) => {
+                // Another thread won the race; discard our value in favor of theirs
+                drop(unsafe { Box::from_raw(new) });
+                unsafe { &*current }
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Sets of levels that don't have to be contiguous.
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Not};
+
+use crate::{Level, LevelFilter, Metadata};
+
+/// A set of levels.
+///
+/// Unlike a [`LevelFilter`], which enables every level up to a maximum, a mask
+/// can enable any combination of levels, such as `Error` and `Trace` but not
+/// `Info`. This is useful for sinks that only want some kinds of records, like
+/// an alerting sink that only wants errors alongside a ring buffer that only
+/// keeps trace records for debugging.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, LevelFilter, LevelMask};
+///
+/// let mask = LevelMask::from(Level::Error) | Level::Trace;
+///
+/// assert!(mask.contains(Level::Error));
+/// assert!(!mask.contains(Level::Info));
+/// assert!(mask.contains(Level::Trace));
+///
+/// // A filter enables every level up to its maximum
+/// let mask = LevelMask::from(LevelFilter::Warn);
+/// assert_eq!(vec![Level::Error, Level::Warn], mask.iter().collect::<Vec<_>>());
+/// ```
+///
+/// [`LevelFilter`]: enum.LevelFilter.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct LevelMask(u8);
+
+impl LevelMask {
+    /// A mask that contains no levels.
+    #[inline]
+    pub const fn empty() -> Self {
+        LevelMask(0)
+    }
+
+    /// A mask that contains every level.
+    #[inline]
+    pub const fn all() -> Self {
+        LevelMask(0b11111)
+    }
+
+    /// Add a level to the mask.
+    #[inline]
+    pub const fn with(self, level: Level) -> Self {
+        LevelMask(self.0 | bit(level))
+    }
+
+    /// Remove a level from the mask.
+    #[inline]
+    pub const fn without(self, level: Level) -> Self {
+        LevelMask(self.0 & !bit(level))
+    }
+
+    /// Whether the mask contains a level.
+    #[inline]
+    pub const fn contains(self, level: Level) -> bool {
+        self.0 & bit(level) != 0
+    }
+
+    /// Whether the mask contains the level of some metadata.
+    ///
+    /// Loggers can use this in their [`Log::enabled`] implementation.
+    ///
+    /// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+    #[inline]
+    pub fn enabled(self, metadata: &Metadata) -> bool {
+        self.contains(metadata.level())
+    }
+
+    /// Whether the mask contains no levels.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The most verbose level in the mask.
+    ///
+    /// This is the [`LevelFilter`] a logger using the mask should pass to
+    /// [`set_max_level`], so records at levels the mask contains aren't
+    /// filtered out before they reach the logger.
+    ///
+    /// [`LevelFilter`]: enum.LevelFilter.html
+    /// [`set_max_level`]: fn.set_max_level.html
+    pub fn max_level(self) -> LevelFilter {
+        self.iter()
+            .last()
+            .map_or(LevelFilter::Off, |level| level.to_level_filter())
+    }
+
+    /// Iterate through the levels in the mask, from `Error` to `Trace`.
+    pub fn iter(self) -> impl Iterator<Item = Level> {
+        Level::iter().filter(move |level| self.contains(*level))
+    }
+}
+
+#[inline]
+const fn bit(level: Level) -> u8 {
+    1 << (level as usize - 1)
+}
+
+impl From<Level> for LevelMask {
+    fn from(level: Level) -> Self {
+        LevelMask::empty().with(level)
+    }
+}
+
+/// Converts a filter into the mask of every level it enables.
+impl From<LevelFilter> for LevelMask {
+    fn from(filter: LevelFilter) -> Self {
+        LevelMask((1 << filter as usize) - 1)
+    }
+}
+
+impl FromIterator<Level> for LevelMask {
+    fn from_iter<I: IntoIterator<Item = Level>>(iter: I) -> Self {
+        iter.into_iter().fold(LevelMask::empty(), LevelMask::with)
+    }
+}
+
+impl<T: Into<LevelMask>> BitOr<T> for LevelMask {
+    type Output = LevelMask;
+
+    fn bitor(self, other: T) -> LevelMask {
+        LevelMask(self.0 | other.into().0)
+    }
+}
+
+impl<T: Into<LevelMask>> BitAnd<T> for LevelMask {
+    type Output = LevelMask;
+
+    fn bitand(self, other: T) -> LevelMask {
+        LevelMask(self.0 & other.into().0)
+    }
+}
+
+impl Not for LevelMask {
+    type Output = LevelMask;
+
+    fn not(self) -> LevelMask {
+        LevelMask(!self.0 & LevelMask::all().0)
+    }
+}
+
+impl fmt::Debug for LevelMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_filter() {
+        assert_eq!(LevelMask::empty(), LevelMask::from(LevelFilter::Off));
+        assert_eq!(LevelMask::all(), LevelMask::from(LevelFilter::Trace));
+
+        for filter in LevelFilter::iter() {
+            let mask = LevelMask::from(filter);
+
+            for level in Level::iter() {
+                assert_eq!(
+                    level <= filter,
+                    mask.contains(level),
+                    "{} {}",
+                    filter,
+                    level
+                );
+            }
+
+            assert_eq!(filter, mask.max_level());
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let mask = LevelMask::from(Level::Error) | Level::Trace;
+
+        assert_eq!(
+            vec![Level::Error, Level::Trace],
+            mask.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(LevelFilter::Trace, mask.max_level());
+        assert_eq!(mask, [Level::Trace, Level::Error].iter().copied().collect());
+
+        assert_eq!(LevelMask::from(Level::Error), mask & LevelFilter::Info);
+        assert_eq!(
+            LevelMask::from(LevelFilter::Debug).without(Level::Error),
+            !mask
+        );
+        assert!((mask & Level::Info).is_empty());
+
+        assert!(mask.enabled(&Metadata::builder().level(Level::Trace).build()));
+        assert!(!mask.enabled(&Metadata::builder().level(Level::Warn).build()));
+        assert_eq!("{Error, Trace}", format!("{:?}", mask));
+    }
+}
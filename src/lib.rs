@@ -126,6 +126,18 @@
 //!
 //! See the [`kv`] module documentation for more details.
 //!
+//! Keys in the logging macros must be unique and non-empty. This is checked at compile time:
+//!
+//! ```compile_fail
+//! # use log::info;
+//! info!(id = 1, id = 2; "two ids");
+//! ```
+//!
+//! If the `kv` feature isn't enabled, key-values in the logging macros are still
+//! accepted, but they're stripped at compile time. Their values are never evaluated.
+//! This lets libraries annotate their logs with key-values without requiring their
+//! users to enable the `kv` feature.
+//!
 //! # Available logging implementations
 //!
 //! In order to produce log output executables have to use
@@ -263,7 +275,7 @@
 //! * `max_level_trace`
 //!
 //! Log invocations at disabled levels will be skipped and will not even be present in the
-//! resulting binary. These features control the value of the `STATIC_MAX_LEVEL` constant. The
+//! resulting binary, even without optimizations. These features control the value of the `STATIC_MAX_LEVEL` constant. The
 //! logging macros check this value before logging a message. By default, no levels are disabled.
 //!
 //! It is possible to override this level for release builds only with the following features:
@@ -293,6 +305,52 @@
 //! * `std` allows use of `std` crate instead of the default `core`. Enables using `std::error` and
 //! `set_boxed_logger` functionality.
 //! * `serde` enables support for serialization and deserialization of `Level` and `LevelFilter`.
+//! * `timestamps` adds a timestamp to `Record`s created by the logging macros, using a global
+//!   [`Clock`] installed with [`set_clock`]. Without a clock, records aren't timestamped.
+//! * `seq` numbers `Record`s created by the logging macros in the order they're created, across
+//!   all threads. See [`Record::seq`] for details.
+//! * `early_buffer` buffers records created by the logging macros before a logger is installed,
+//!   and replays them to the logger once it is. See the [`early_buffer`] module for details.
+//! * `stderr_fallback` writes error records created by the logging macros to stderr when no
+//!   logger is installed, instead of discarding them. The maximum log level still defaults to
+//!   `Off`, so nothing is written until it's raised with [`set_max_level`]. See
+//!   [`PreInitPolicy::Stderr`] for details.
+//! * `correlation` adds the current thread's correlation id, set with [`with_correlation_id`], to
+//!   `Record`s created by the logging macros. See [`Record::correlation_id`] for details.
+//! * `nesting` adds the current thread's nesting depth, increased with [`indent_scope`], to
+//!   `Record`s created by the logging macros. See [`Record::nesting`] for details.
+//! * `extensions` lets loggers attach typed data to `Record`s. See [`Extensions`] for details.
+//! * `render_cache` caches the message formatted by [`Record::render`] in the record, so loggers
+//!   that pass it to several sinks only format it once.
+//! * `backtrace` attaches a backtrace of the callsite to error records created by the logging
+//!   macros, as the [`kv::keys::BACKTRACE`] key-value, when `RUST_BACKTRACE` or
+//!   `RUST_LIB_BACKTRACE` enables them. Requires Rust 1.65, which is newer than the minimum
+//!   supported Rust version of the rest of the crate.
+//! * `flight_recorder` adds a logger that keeps the most recent records in a ring buffer, to dump
+//!   them when the program panics. See the [`flight_recorder`] module for details.
+//! * `wire` adds a compact binary encoding of `Record`s, including their key-values, so they can
+//!   be forwarded to other processes. See the [`wire`] module for details.
+//! * `async_log` adds the [`AsyncLog`] trait for loggers written for an async runtime, and
+//!   [`bridge_async`] to install one as the global logger. Requires Rust 1.75.
+//! * `no_location` stops the logging macros from capturing the module path, file, and line of
+//!   their callsite, so those strings don't end up in the binary. This is useful on embedded
+//!   targets where file paths take up a lot of flash. `Record`s created by the macros then return
+//!   `None` from [`Record::source_location`] and related methods. The module path is still used
+//!   as the default target.
+//! * `const_noop` lets the logging macros be used in a `const fn` by starting them with
+//!   `const fn;`. They're type checked but never log anything. See [`log!`] for details.
+//! * `outline_callsites` keeps the code that builds a record out of line, so it's shared by
+//!   every logging macro instead of being inlined into each one. This trades a function call
+//!   per enabled record for smaller binaries, mostly when building with LTO, which can
+//!   otherwise inline it into each of tens of thousands of callsites.
+//! * `global-fields` attaches the global [`BuildInfo`] to every `Record` created by the logging
+//!   macros, under the [`kv::keys::BUILD_VERSION`] and related keys. Records aren't changed
+//!   until [`set_build_info`] is called.
+//! * `kv_uuid`, `kv_time`, and `kv_chrono` let `Uuid`s from `uuid`, `OffsetDateTime`s from
+//!   `time`, and `DateTime`s from `chrono` be captured as key-values. See [`kv::ToValue`] for
+//!   how they're represented.
+//! * `tracing_interop` adds conversions between `Level` and `LevelFilter` and their counterparts
+//!   in `tracing`.
 //!
 //! ```toml
 //! [dependencies]
@@ -311,6 +369,20 @@
 //! [filter_link]: enum.LevelFilter.html
 //! [`set_logger`]: fn.set_logger.html
 //! [`set_max_level`]: fn.set_max_level.html
+//! [`Clock`]: trait.Clock.html
+//! [`set_clock`]: fn.set_clock.html
+//! [`Record::source_location`]: struct.Record.html#method.source_location
+//! [`log!`]: macro.log.html
+//! [`AsyncLog`]: trait.AsyncLog.html
+//! [`bridge_async`]: fn.bridge_async.html
+//! [`BuildInfo`]: struct.BuildInfo.html
+//! [`with_correlation_id`]: fn.with_correlation_id.html
+//! [`Record::correlation_id`]: struct.Record.html#method.correlation_id
+//! [`indent_scope`]: fn.indent_scope.html
+//! [`Record::nesting`]: struct.Record.html#method.nesting
+//! [`Extensions`]: struct.Extensions.html
+//! [`Record::render`]: struct.Record.html#method.render
+//! [`set_build_info`]: fn.set_build_info.html
 //! [`try_set_logger_raw`]: fn.try_set_logger_raw.html
 //! [`shutdown_logger_raw`]: fn.shutdown_logger_raw.html
 //! [env_logger]: https://docs.rs/env_logger/*/env_logger/
@@ -393,16 +465,142 @@ extern crate core as std;
 use std::cfg;
 #[cfg(feature = "std")]
 use std::error;
+use std::panic::Location;
 use std::str::FromStr;
 use std::{cmp, fmt, mem};
 
 #[macro_use]
 mod macros;
 mod serde;
+mod tracing_interop;
 
 #[cfg(feature = "kv")]
 pub mod kv;
 
+mod diagnostics;
+
+#[cfg(feature = "std")]
+mod lazy_global;
+
+pub mod macro_support;
+
+mod target;
+
+mod source_location;
+
+mod dedup_key;
+
+mod level_mask;
+
+mod pre_init;
+
+mod limits;
+
+mod build_info;
+
+#[cfg(feature = "timestamps")]
+mod clock;
+
+#[cfg(feature = "std")]
+pub mod dedup;
+
+#[cfg(feature = "early_buffer")]
+pub mod early_buffer;
+
+#[cfg(feature = "flight_recorder")]
+pub mod flight_recorder;
+
+#[cfg(feature = "wire")]
+pub mod wire;
+
+#[cfg(feature = "async_log")]
+mod async_log;
+
+#[cfg(feature = "correlation")]
+mod correlation;
+
+#[cfg(feature = "std")]
+mod thread_level;
+
+#[cfg(feature = "nesting")]
+mod nesting;
+
+#[cfg(feature = "extensions")]
+mod extensions;
+
+#[cfg(feature = "std")]
+mod capture;
+
+#[cfg(feature = "std")]
+mod memoized_filter;
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod suggested_level;
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod failure;
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+mod shutdown;
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub use self::shutdown::{try_flush_and_shutdown, ShutdownError, ShutdownReport};
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr", any(unix, windows)))]
+pub use self::shutdown::flush_on_exit;
+
+pub use self::diagnostics::{diagnostics, enable_diagnostics, Diagnostics};
+
+pub use self::pre_init::{pre_init_policy, set_pre_init_policy, PreInitPolicy};
+
+pub use self::limits::{limits, set_limits, Limits};
+
+#[cfg(target_has_atomic = "ptr")]
+pub use self::build_info::set_build_info;
+pub use self::build_info::{build_info, set_build_info_racy, BuildInfo, SetBuildInfoError};
+#[cfg(feature = "std")]
+pub use self::target::register_target;
+
+pub use self::target::TargetHandle;
+
+pub use self::source_location::SourceLocation;
+
+pub use self::dedup_key::DedupKey;
+
+pub use self::level_mask::LevelMask;
+
+#[cfg(feature = "correlation")]
+pub use self::correlation::{correlation_id, with_correlation_id};
+
+#[cfg(feature = "extensions")]
+pub use self::extensions::Extensions;
+
+#[cfg(feature = "std")]
+pub use self::capture::{capture, CapturedRecord};
+
+#[cfg(feature = "nesting")]
+pub use self::nesting::{indent_scope, nesting, IndentScope};
+
+#[cfg(feature = "std")]
+pub use self::thread_level::{clear_thread_max_level, set_thread_max_level, thread_max_level};
+
+#[cfg(feature = "std")]
+pub use self::memoized_filter::MemoizedFilter;
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub use self::failure::{clear_failure_handler, set_failure_handler, LoggerFailure};
+
+#[cfg(feature = "async_log")]
+pub use self::async_log::{bridge_async, AsyncLog};
+
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub use self::suggested_level::{suggest_level, suggested_filter, SuggestedFilter};
+
+#[cfg(all(feature = "timestamps", target_has_atomic = "ptr"))]
+pub use self::clock::set_clock;
+#[cfg(feature = "timestamps")]
+pub use self::clock::{clock, set_clock_racy, Clock, SetClockError};
+
 #[cfg(target_has_atomic = "ptr")]
 use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -430,6 +628,12 @@ impl AtomicUsize {
         self.v.set(val)
     }
 
+    fn fetch_add(&self, val: usize, _order: Ordering) -> usize {
+        let prev = self.v.get();
+        self.v.set(prev.wrapping_add(val));
+        prev
+    }
+
     #[cfg(target_has_atomic = "ptr")]
     fn compare_exchange(
         &self,
@@ -453,6 +657,27 @@ unsafe impl Sync for AtomicUsize {}
 
 // The LOGGER static holds a pointer to the global logger. It is protected by
 // the STATE static which determines whether LOGGER has been initialized yet.
+//
+// Memory orderings:
+//
+// - `STATE` moves from `UNINITIALIZED` to `INITIALIZING` with an `Acquire`
+//   compare-exchange, so only one thread writes `LOGGER`. That thread stores
+//   `INITIALIZED` with `Release` after writing it, and `logger` loads `STATE`
+//   with `Acquire`, so a thread that sees `INITIALIZED` also sees `LOGGER` and
+//   everything the logger did to initialize itself, including `on_install`.
+// - `MAX_LOG_LEVEL_FILTER` is stored with `Release` and loaded with `Acquire`
+//   by `max_level`, so a thread that sees a new level also sees whatever the
+//   setter wrote before changing it, like a logger's reconfigured filters.
+//   The logging macros use `max_level_relaxed` instead, since they only need
+//   the level itself and re-check anything else through `logger`.
+// - `FILTER_EPOCH` is incremented with `Release` after the level or logger it
+//   covers is stored, and loaded with `Acquire` before reading them, so an
+//   epoch is never newer than the values read alongside it.
+//
+// These orderings aren't model checked. loom would need its own atomics in
+// place of these statics under `cfg(loom)`, and a dependency the crate
+// doesn't have. `test_max_level_ordering` in the integration tests only
+// exercises the level's `Release`/`Acquire` pairing on real threads.
 static mut LOGGER: &dyn Log = &NopLogger;
 
 static STATE: AtomicUsize = AtomicUsize::new(0);
@@ -466,12 +691,29 @@ const INITIALIZED: usize = 2;
 
 static MAX_LOG_LEVEL_FILTER: AtomicUsize = AtomicUsize::new(0);
 
+// The FILTER_EPOCH static is incremented whenever the global logger or maximum
+// log level is set. It lets `TargetLogger` and users of `max_level_epoch` know
+// when their cached filtering results are stale.
+static FILTER_EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+// The SEQ static holds the sequence number of the next record created by
+// the logging macros.
+#[cfg(feature = "seq")]
+static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "seq")]
+fn next_seq() -> u64 {
+    SEQ.fetch_add(1, Ordering::Relaxed) as u64
+}
+
 static LOG_LEVEL_NAMES: [&str; 6] = ["OFF", "ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
 
 static SET_LOGGER_ERROR: &str = "attempted to set a logger after the logging system \
                                  was already initialized";
 static LEVEL_PARSE_ERROR: &str =
     "attempted to convert a string that doesn't match an existing log level";
+static LEVEL_TRY_FROM_ERROR: &str =
+    "attempted to convert an integer that doesn't match an existing log level";
 
 /// An enum representing the available verbosity levels of the logger.
 ///
@@ -479,6 +721,33 @@ static LEVEL_PARSE_ERROR: &str =
 /// [`log_enabled!`](macro.log_enabled.html), specifying the `Level` of
 /// [`log!`](macro.log.html), and comparing a `Level` directly to a
 /// [`LevelFilter`](enum.LevelFilter.html).
+///
+/// # Numeric values
+///
+/// Levels can be converted to and from integers, to store them compactly in
+/// binary formats or pass them across an FFI boundary. Their values are
+/// stable, and match the values of the [`LevelFilter`] with the same name:
+///
+/// | Level   | Value |
+/// | ------- | ----- |
+/// | `Error` | 1     |
+/// | `Warn`  | 2     |
+/// | `Info`  | 3     |
+/// | `Debug` | 4     |
+/// | `Trace` | 5     |
+///
+/// Prefer these conversions to `as` casts, which rely on the enum's
+/// representation.
+///
+/// ```
+/// use log::Level;
+///
+/// assert_eq!(2u8, u8::from(Level::Warn));
+/// assert_eq!(Ok(Level::Warn), Level::try_from(2u8));
+/// assert!(Level::try_from(0u8).is_err());
+/// ```
+///
+/// [`LevelFilter`]: enum.LevelFilter.html
 #[repr(usize)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub enum Level {
@@ -541,8 +810,62 @@ impl fmt::Display for Level {
     }
 }
 
+impl From<Level> for u8 {
+    #[inline]
+    fn from(level: Level) -> u8 {
+        level as u8
+    }
+}
+
+impl From<Level> for usize {
+    #[inline]
+    fn from(level: Level) -> usize {
+        level as usize
+    }
+}
+
+impl TryFrom<u8> for Level {
+    type Error = TryFromLevelError;
+    #[inline]
+    fn try_from(value: u8) -> Result<Level, TryFromLevelError> {
+        Level::try_from(usize::from(value))
+    }
+}
+
+impl TryFrom<usize> for Level {
+    type Error = TryFromLevelError;
+    #[inline]
+    fn try_from(value: usize) -> Result<Level, TryFromLevelError> {
+        Level::from_usize(value).ok_or(TryFromLevelError(()))
+    }
+}
+
 impl Level {
-    fn from_usize(u: usize) -> Option<Level> {
+    /// All logging levels, from more severe to less severe.
+    ///
+    /// This is handy for listing the accepted values of a setting, together
+    /// with [`Level::as_str`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::Level;
+    ///
+    /// let names = Level::ALL.map(|level| level.as_str());
+    ///
+    /// assert_eq!(["ERROR", "WARN", "INFO", "DEBUG", "TRACE"], names);
+    /// ```
+    ///
+    /// [`Level::as_str`]: #method.as_str
+    pub const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    const fn from_usize(u: usize) -> Option<Level> {
         match u {
             1 => Some(Level::Error),
             2 => Some(Level::Warn),
@@ -555,7 +878,7 @@ impl Level {
 
     /// Returns the most verbose logging level.
     #[inline]
-    pub fn max() -> Level {
+    pub const fn max() -> Level {
         Level::Trace
     }
 
@@ -596,6 +919,21 @@ impl Level {
 /// A `LevelFilter` may be compared directly to a [`Level`]. Use this type
 /// to get and set the maximum log level with [`max_level()`] and [`set_max_level`].
 ///
+///
+/// # Numeric values
+///
+/// Level filters can be converted to and from integers like [`Level`]s can.
+/// `Off` is `0`, and the rest have the same value as the `Level` with the
+/// same name, from `Error` as `1` to `Trace` as `5`.
+///
+/// ```
+/// use log::LevelFilter;
+///
+/// assert_eq!(0u8, u8::from(LevelFilter::Off));
+/// assert_eq!(Ok(LevelFilter::Trace), LevelFilter::try_from(5u8));
+/// assert!(LevelFilter::try_from(6u8).is_err());
+/// ```
+///
 /// [`Level`]: enum.Level.html
 /// [`max_level()`]: fn.max_level.html
 /// [`set_max_level`]: fn.set_max_level.html
@@ -647,8 +985,58 @@ impl fmt::Display for LevelFilter {
     }
 }
 
+impl From<LevelFilter> for u8 {
+    #[inline]
+    fn from(filter: LevelFilter) -> u8 {
+        filter as u8
+    }
+}
+
+impl From<LevelFilter> for usize {
+    #[inline]
+    fn from(filter: LevelFilter) -> usize {
+        filter as usize
+    }
+}
+
+impl TryFrom<u8> for LevelFilter {
+    type Error = TryFromLevelError;
+    #[inline]
+    fn try_from(value: u8) -> Result<LevelFilter, TryFromLevelError> {
+        LevelFilter::try_from(usize::from(value))
+    }
+}
+
+impl TryFrom<usize> for LevelFilter {
+    type Error = TryFromLevelError;
+    #[inline]
+    fn try_from(value: usize) -> Result<LevelFilter, TryFromLevelError> {
+        LevelFilter::from_usize(value).ok_or(TryFromLevelError(()))
+    }
+}
+
 impl LevelFilter {
-    fn from_usize(u: usize) -> Option<LevelFilter> {
+    /// All filtering levels, from less to more verbose.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    ///
+    /// let names = LevelFilter::ALL.map(|level| level.as_str().to_ascii_lowercase());
+    ///
+    /// assert_eq!(["off", "error", "warn", "info", "debug", "trace"], names);
+    /// ```
+    pub const ALL: [LevelFilter; 6] = [
+        LevelFilter::Off,
+        LevelFilter::Error,
+        LevelFilter::Warn,
+        LevelFilter::Info,
+        LevelFilter::Debug,
+        LevelFilter::Trace,
+    ];
+
+    const fn from_usize(u: usize) -> Option<LevelFilter> {
         match u {
             0 => Some(LevelFilter::Off),
             1 => Some(LevelFilter::Error),
@@ -662,7 +1050,7 @@ impl LevelFilter {
 
     /// Returns the most verbose logging level filter.
     #[inline]
-    pub fn max() -> LevelFilter {
+    pub const fn max() -> LevelFilter {
         LevelFilter::Trace
     }
 
@@ -670,7 +1058,7 @@ impl LevelFilter {
     ///
     /// Returns `None` if `self` is `LevelFilter::Off`.
     #[inline]
-    pub fn to_level(&self) -> Option<Level> {
+    pub const fn to_level(&self) -> Option<Level> {
         Level::from_usize(*self as usize)
     }
 
@@ -700,6 +1088,149 @@ impl LevelFilter {
     }
 }
 
+/// A verbosity setting, as given on a command line.
+///
+/// `Verbosity` parses any of the common ways command-line programs let users
+/// choose how much to log, and converts them into a [`LevelFilter`]:
+///
+/// - A level filter name, like `"off"` or `"debug"`, in any case.
+/// - A number from `"0"` (`Off`) to `"5"` (`Trace`).
+/// - A count of repeated `v` flags, like `"-vvv"`, which is treated the same
+///   as the number of `v`s. Counts above 5 are clamped to `Trace`.
+///
+/// Its `Display` implementation writes the name of the level filter, so a
+/// formatted `Verbosity` parses back to the same value.
+///
+/// # Examples
+///
+/// ```
+/// use log::{LevelFilter, Verbosity};
+///
+/// let verbosity: Verbosity = "-vvv".parse().unwrap();
+/// assert_eq!(LevelFilter::Info, verbosity.level_filter());
+///
+/// let verbosity: Verbosity = "4".parse().unwrap();
+/// assert_eq!(LevelFilter::Debug, verbosity.level_filter());
+///
+/// assert_eq!("DEBUG", verbosity.to_string());
+/// ```
+///
+/// [`LevelFilter`]: enum.LevelFilter.html
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Verbosity(LevelFilter);
+
+impl Verbosity {
+    /// Create a verbosity from a level filter.
+    #[inline]
+    pub const fn new(filter: LevelFilter) -> Verbosity {
+        Verbosity(filter)
+    }
+
+    /// Create a verbosity from a count of verbosity flags, like the number of
+    /// times `-v` was given.
+    ///
+    /// A count of `0` is `Off`, and counts above `5` are clamped to `Trace`.
+    #[inline]
+    pub fn from_count(count: usize) -> Verbosity {
+        Verbosity(LevelFilter::from_usize(cmp::min(count, LevelFilter::max() as usize)).unwrap())
+    }
+
+    /// Get the level filter for this verbosity.
+    #[inline]
+    pub fn level_filter(&self) -> LevelFilter {
+        self.0
+    }
+}
+
+impl From<LevelFilter> for Verbosity {
+    #[inline]
+    fn from(filter: LevelFilter) -> Verbosity {
+        Verbosity(filter)
+    }
+}
+
+impl From<Verbosity> for LevelFilter {
+    #[inline]
+    fn from(verbosity: Verbosity) -> LevelFilter {
+        verbosity.0
+    }
+}
+
+impl FromStr for Verbosity {
+    type Err = ParseLevelError;
+    fn from_str(verbosity: &str) -> Result<Verbosity, Self::Err> {
+        if let Ok(filter) = verbosity.parse() {
+            return Ok(Verbosity(filter));
+        }
+
+        if let Ok(number) = verbosity.parse::<u8>() {
+            return LevelFilter::from_usize(number as usize)
+                .map(Verbosity)
+                .ok_or(ParseLevelError(()));
+        }
+
+        match verbosity.strip_prefix('-') {
+            Some(flags) if !flags.is_empty() && flags.bytes().all(|b| b == b'v') => {
+                Ok(Verbosity::from_count(flags.len()))
+            }
+            _ => Err(ParseLevelError(())),
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+// The message of a record, formatted the first time it's rendered.
+//
+// `Record` isn't `Sync`, since `fmt::Arguments` isn't, so the cache is never
+// accessed from multiple threads. It's only written to once, so references to
+// the cached message stay valid for as long as the record is borrowed.
+//
+// Formatting the message can render the same record again, so the cache may
+// already be set once formatting returns. The message that was cached first
+// is kept, since references to it may still be in use.
+#[cfg(feature = "render_cache")]
+#[derive(Default)]
+struct RenderCache(std::cell::UnsafeCell<Option<String>>);
+
+#[cfg(feature = "render_cache")]
+impl RenderCache {
+    fn get(&self) -> Option<&str> {
+        unsafe { (*self.0.get()).as_deref() }
+    }
+
+    fn get_or_init(&self, f: impl FnOnce() -> String) -> &str {
+        if self.get().is_none() {
+            let message = f();
+
+            // A re-entrant call may have filled the cache while formatting
+            if self.get().is_none() {
+                unsafe { *self.0.get() = Some(message) };
+            }
+        }
+
+        self.get().expect("the message was just rendered")
+    }
+}
+
+#[cfg(feature = "render_cache")]
+impl Clone for RenderCache {
+    fn clone(&self) -> Self {
+        RenderCache(std::cell::UnsafeCell::new(self.get().map(Into::into)))
+    }
+}
+
+#[cfg(feature = "render_cache")]
+impl fmt::Debug for RenderCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 enum MaybeStaticStr<'a> {
     Static(&'static str),
@@ -768,8 +1299,21 @@ pub struct Record<'a> {
     module_path: Option<MaybeStaticStr<'a>>,
     file: Option<MaybeStaticStr<'a>>,
     line: Option<u32>,
+    column: Option<u32>,
+    #[cfg(feature = "correlation")]
+    correlation_id: Option<u64>,
+    #[cfg(feature = "nesting")]
+    nesting: usize,
+    #[cfg(feature = "timestamps")]
+    timestamp: Option<u64>,
+    #[cfg(feature = "seq")]
+    seq: Option<u64>,
     #[cfg(feature = "kv")]
     key_values: KeyValues<'a>,
+    #[cfg(feature = "extensions")]
+    extensions: &'a Extensions,
+    #[cfg(feature = "render_cache")]
+    rendered: RenderCache,
 }
 
 // This wrapper type is only needed so we can
@@ -778,13 +1322,51 @@ pub struct Record<'a> {
 // the underlying `Source`.
 #[cfg(feature = "kv")]
 #[derive(Clone)]
-struct KeyValues<'a>(&'a dyn kv::Source);
+struct KeyValues<'a> {
+    source: &'a dyn kv::Source,
+    // Additional key-values chained onto `source` by `Record::with_extra_kvs`
+    extra: Option<&'a dyn kv::Source>,
+}
+
+#[cfg(feature = "kv")]
+impl<'a> KeyValues<'a> {
+    fn new(source: &'a dyn kv::Source) -> Self {
+        KeyValues {
+            source,
+            extra: None,
+        }
+    }
+}
+
+#[cfg(feature = "kv")]
+impl<'a> kv::Source for KeyValues<'a> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn kv::VisitSource<'kvs>) -> Result<(), kv::Error> {
+        self.source.visit(visitor)?;
+
+        if let Some(extra) = self.extra {
+            extra.visit(visitor)?;
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: kv::Key) -> Option<kv::Value<'_>> {
+        match self.extra {
+            Some(extra) => self.source.get(key.clone()).or_else(|| extra.get(key)),
+            None => self.source.get(key),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.source.count() + self.extra.map_or(0, |extra| extra.count())
+    }
+}
 
 #[cfg(feature = "kv")]
 impl<'a> fmt::Debug for KeyValues<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut visitor = f.debug_map();
-        self.0.visit(&mut visitor).map_err(|_| fmt::Error)?;
+        kv::Source::visit(self, &mut visitor).map_err(|_| fmt::Error)?;
         visitor.finish()
     }
 }
@@ -802,6 +1384,53 @@ impl<'a> Record<'a> {
         &self.args
     }
 
+    /// The message body as a string.
+    ///
+    /// If the message is a plain string literal, it's returned without any
+    /// formatting. Otherwise, with the `render_cache` feature, it's formatted
+    /// the first time this method is called, and the formatted message is
+    /// cached in the record. Loggers that pass the same record to several sinks
+    /// can call this method in each of them without formatting the message more
+    /// than once. Without the feature, the message is formatted on every call.
+    ///
+    /// Requires the `std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::Record;
+    ///
+    /// fn write(record: &Record, sinks: &mut [Vec<String>]) {
+    ///     // With `render_cache`, the message is only formatted once, however
+    ///     // many sinks there are
+    ///     for sink in sinks {
+    ///         sink.push(record.render().into_owned());
+    ///     }
+    /// }
+    ///
+    /// let port = 8080;
+    /// let mut sinks = [Vec::new(), Vec::new()];
+    ///
+    /// write(
+    ///     &Record::builder()
+    ///         .args(format_args!("listening on port {}", port))
+    ///         .build(),
+    ///     &mut sinks,
+    /// );
+    ///
+    /// assert_eq!(["listening on port 8080"], &*sinks[1]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn render(&self) -> std::borrow::Cow<'_, str> {
+        match self.args.as_str() {
+            Some(message) => std::borrow::Cow::Borrowed(message),
+            #[cfg(feature = "render_cache")]
+            None => std::borrow::Cow::Borrowed(self.rendered.get_or_init(|| self.args.to_string())),
+            #[cfg(not(feature = "render_cache"))]
+            None => std::borrow::Cow::Owned(self.args.to_string()),
+        }
+    }
+
     /// Metadata about the log directive.
     #[inline]
     pub fn metadata(&self) -> &Metadata<'a> {
@@ -856,29 +1485,262 @@ impl<'a> Record<'a> {
         self.line
     }
 
-    /// The structured key-value pairs associated with the message.
-    #[cfg(feature = "kv")]
+    /// The column containing the message.
     #[inline]
-    pub fn key_values(&self) -> &dyn kv::Source {
-        self.key_values.0
+    pub fn column(&self) -> Option<u32> {
+        self.column
     }
 
-    /// Create a new [`RecordBuilder`](struct.RecordBuilder.html) based on this record.
-    #[cfg(feature = "kv")]
+    /// The module path, file, line, and column of the message together.
+    ///
+    /// Returns `None` unless the module path, file, and line are all known,
+    /// which they always are for records created by the logging macros.
     #[inline]
-    pub fn to_builder(&self) -> RecordBuilder {
-        RecordBuilder {
-            record: Record {
-                metadata: Metadata {
-                    level: self.metadata.level,
-                    target: self.metadata.target,
-                },
-                args: self.args,
-                module_path: self.module_path,
-                file: self.file,
-                line: self.line,
-                key_values: self.key_values.clone(),
-            },
+    pub fn source_location(&self) -> Option<SourceLocation<'a>> {
+        match (self.module_path, self.file, self.line) {
+            (Some(module_path), Some(file), Some(line)) => {
+                let location = SourceLocation::new(module_path.get(), file.get(), line);
+
+                Some(match self.column {
+                    Some(column) => location.with_column(column),
+                    None => location,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The correlation id of the message, if any.
+    ///
+    /// Records created by the logging macros inside a call to
+    /// [`with_correlation_id`] carry its id, so loggers can tie together the
+    /// records produced while handling a single request or task.
+    ///
+    /// Requires the `correlation` feature.
+    ///
+    /// [`with_correlation_id`]: fn.with_correlation_id.html
+    #[cfg(feature = "correlation")]
+    #[inline]
+    pub fn correlation_id(&self) -> Option<u64> {
+        self.correlation_id
+    }
+
+    /// The nesting depth of the message.
+    ///
+    /// Records created by the logging macros while an [`indent_scope`] is
+    /// alive carry the number of scopes on their thread, so loggers can indent
+    /// records logged by nested operations. Records created outside of any
+    /// scope have a depth of `0`.
+    ///
+    /// Requires the `nesting` feature.
+    ///
+    /// [`indent_scope`]: fn.indent_scope.html
+    #[cfg(feature = "nesting")]
+    #[inline]
+    pub fn nesting(&self) -> usize {
+        self.nesting
+    }
+
+    /// The time the message was logged at, in ticks of the global [`Clock`].
+    ///
+    /// Records created by the logging macros are timestamped using the clock
+    /// set by [`set_clock`]. If no clock has been set this returns `None`.
+    ///
+    /// Requires the `timestamps` feature.
+    ///
+    /// [`Clock`]: trait.Clock.html
+    /// [`set_clock`]: fn.set_clock.html
+    #[cfg(feature = "timestamps")]
+    #[inline]
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// The sequence number of the message.
+    ///
+    /// Records created by the logging macros are numbered in the order they're
+    /// created, across all threads. Sinks can use this to reconstruct the order
+    /// records were logged in, even if their timestamps are equal or come from
+    /// skewed clocks. Sequence numbers are unique and increasing, but not
+    /// necessarily contiguous, since records that aren't logged still use one.
+    ///
+    /// On targets where `usize` is 32 bits, sequence numbers wrap after
+    /// `u32::MAX`.
+    ///
+    /// Requires the `seq` feature.
+    #[cfg(feature = "seq")]
+    #[inline]
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The structured key-value pairs associated with the message.
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn key_values(&self) -> &dyn kv::Source {
+        &self.key_values
+    }
+
+    /// Typed data attached to the record.
+    ///
+    /// Records created by the logging macros don't have any extensions. Loggers
+    /// that wrap other loggers can attach them using [`RecordBuilder::extensions`].
+    ///
+    /// Requires the `extensions` feature.
+    ///
+    /// [`RecordBuilder::extensions`]: struct.RecordBuilder.html#method.extensions
+    #[cfg(feature = "extensions")]
+    #[inline]
+    pub fn extensions(&self) -> &'a Extensions {
+        self.extensions
+    }
+
+    /// Write this record in the binary format described in the [`wire`] module.
+    ///
+    /// The message is formatted while it's written. The record can be decoded
+    /// with [`OwnedRecord::decode`].
+    ///
+    /// Requires the `wire` feature.
+    ///
+    /// [`wire`]: wire/index.html
+    /// [`OwnedRecord::decode`]: wire/struct.OwnedRecord.html#method.decode
+    #[cfg(feature = "wire")]
+    pub fn encode(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        wire::encode(self, w)
+    }
+
+    /// Get a record whose key-values are this record's key-values followed by `kvs`.
+    ///
+    /// This is useful for loggers that wrap another logger and enrich the records
+    /// passed to it with additional key-values, like a span or request id. The
+    /// returned record borrows from this one, so nothing needs to be reallocated.
+    ///
+    /// If a key appears in both this record and `kvs` then the value from this
+    /// record is returned by [`Source::get`](kv/trait.Source.html#method.get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Log, Metadata, Record};
+    ///
+    /// struct WithRequestId<L>(L);
+    ///
+    /// impl<L: Log> Log for WithRequestId<L> {
+    ///     fn enabled(&self, metadata: &Metadata) -> bool {
+    ///         self.0.enabled(metadata)
+    ///     }
+    ///
+    ///     fn log(&self, record: &Record) {
+    ///         let extra = [("request_id", 42)];
+    ///
+    ///         self.0.log(&record.with_extra_kvs(&extra));
+    ///     }
+    ///
+    ///     fn flush(&self) {
+    ///         self.0.flush()
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn with_extra_kvs(&'a self, kvs: &'a dyn kv::Source) -> Record<'a> {
+        Record {
+            metadata: self.metadata.clone(),
+            args: self.args,
+            module_path: self.module_path,
+            file: self.file,
+            line: self.line,
+            column: self.column,
+            #[cfg(feature = "correlation")]
+            correlation_id: self.correlation_id,
+            #[cfg(feature = "nesting")]
+            nesting: self.nesting,
+            #[cfg(feature = "timestamps")]
+            timestamp: self.timestamp,
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+            key_values: KeyValues {
+                source: &self.key_values,
+                extra: Some(kvs),
+            },
+            #[cfg(feature = "extensions")]
+            extensions: self.extensions,
+            // Copying the rendered message would allocate for every wrapped record
+            #[cfg(feature = "render_cache")]
+            rendered: RenderCache::default(),
+        }
+    }
+
+    /// Get a copy of this record with a different target.
+    ///
+    /// All other fields, including the key-values, are kept. This is cheaper
+    /// than rebuilding the record with [`to_builder`], and works without the
+    /// `kv` feature. See [`Metadata::clone_with_target`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Log, Metadata, Record};
+    ///
+    /// // Prefixes the target of every record with a tenant's name
+    /// struct Tenant<L> {
+    ///     name: &'static str,
+    ///     inner: L,
+    /// }
+    ///
+    /// impl<L: Log> Log for Tenant<L> {
+    ///     fn enabled(&self, metadata: &Metadata) -> bool {
+    ///         self.inner.enabled(metadata)
+    ///     }
+    ///
+    ///     fn log(&self, record: &Record) {
+    ///         let target = format!("{}::{}", self.name, record.target());
+    ///
+    ///         self.inner.log(&record.clone_with_target(&target));
+    ///     }
+    ///
+    ///     fn flush(&self) {
+    ///         self.inner.flush()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`to_builder`]: #method.to_builder
+    /// [`Metadata::clone_with_target`]: struct.Metadata.html#method.clone_with_target
+    #[inline]
+    pub fn clone_with_target(&self, target: &'a str) -> Record<'a> {
+        let mut record = self.clone();
+        record.metadata = record.metadata.clone_with_target(target);
+        record
+    }
+
+    /// Create a new [`RecordBuilder`](struct.RecordBuilder.html) based on this record.
+    #[cfg(feature = "kv")]
+    #[inline]
+    pub fn to_builder(&self) -> RecordBuilder {
+        RecordBuilder {
+            record: Record {
+                metadata: self.metadata.clone(),
+                args: self.args,
+                module_path: self.module_path,
+                file: self.file,
+                line: self.line,
+                column: self.column,
+                #[cfg(feature = "correlation")]
+                correlation_id: self.correlation_id,
+                #[cfg(feature = "nesting")]
+                nesting: self.nesting,
+                #[cfg(feature = "timestamps")]
+                timestamp: self.timestamp,
+                #[cfg(feature = "seq")]
+                seq: self.seq,
+                key_values: self.key_values.clone(),
+                #[cfg(feature = "extensions")]
+                extensions: self.extensions,
+                // The builder may change the message
+                #[cfg(feature = "render_cache")]
+                rendered: RenderCache::default(),
+            },
         }
     }
 }
@@ -937,6 +1799,12 @@ impl<'a> RecordBuilder<'a> {
     /// - `module_path`: `None`
     /// - `file`: `None`
     /// - `line`: `None`
+    /// - `column`: `None`
+    /// - `correlation_id`: `None`
+    /// - `nesting`: `0`
+    /// - `timestamp`: `None`
+    /// - `seq`: `None`
+    /// - `extensions`: empty
     ///
     /// [`format_args!("")`]: https://doc.rust-lang.org/std/macro.format_args.html
     /// [`Metadata::builder().build()`]: struct.MetadataBuilder.html#method.build
@@ -949,8 +1817,21 @@ impl<'a> RecordBuilder<'a> {
                 module_path: None,
                 file: None,
                 line: None,
+                column: None,
+                #[cfg(feature = "correlation")]
+                correlation_id: None,
+                #[cfg(feature = "nesting")]
+                nesting: 0,
+                #[cfg(feature = "timestamps")]
+                timestamp: None,
+                #[cfg(feature = "seq")]
+                seq: None,
                 #[cfg(feature = "kv")]
-                key_values: KeyValues(&None::<(kv::Key, kv::Value)>),
+                key_values: KeyValues::new(&None::<(kv::Key, kv::Value)>),
+                #[cfg(feature = "extensions")]
+                extensions: &extensions::EMPTY,
+                #[cfg(feature = "render_cache")]
+                rendered: RenderCache::default(),
             },
         }
     }
@@ -983,6 +1864,13 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Set [`Metadata::extras`](struct.Metadata.html#method.extras)
+    #[inline]
+    pub fn extras(&mut self, extras: Option<&'a MetadataExtras<'a>>) -> &mut RecordBuilder<'a> {
+        self.record.metadata.extras = extras;
+        self
+    }
+
     /// Set [`module_path`](struct.Record.html#method.module_path)
     #[inline]
     pub fn module_path(&mut self, path: Option<&'a str>) -> &mut RecordBuilder<'a> {
@@ -1018,19 +1906,148 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Set [`column`](struct.Record.html#method.column)
+    #[inline]
+    pub fn column(&mut self, column: Option<u32>) -> &mut RecordBuilder<'a> {
+        self.record.column = column;
+        self
+    }
+
+    /// Set [`file`](struct.Record.html#method.file), [`line`](struct.Record.html#method.line),
+    /// and [`column`](struct.Record.html#method.column) from a source location.
+    ///
+    /// The location of a macro's caller can be captured with [`location!`](macro.location.html).
+    #[inline]
+    pub fn location(&mut self, location: &'static Location<'static>) -> &mut RecordBuilder<'a> {
+        self.file_static(Some(location.file()))
+            .line(Some(location.line()))
+            .column(Some(location.column()))
+    }
+
+    /// Set [`module_path`](struct.Record.html#method.module_path), [`file`](struct.Record.html#method.file),
+    /// [`line`](struct.Record.html#method.line), and [`column`](struct.Record.html#method.column)
+    /// from a [`SourceLocation`](struct.SourceLocation.html).
+    #[inline]
+    pub fn source_location(&mut self, location: &SourceLocation<'a>) -> &mut RecordBuilder<'a> {
+        self.module_path(Some(location.module_path()))
+            .file(Some(location.file()))
+            .line(Some(location.line()))
+            .column(location.column())
+    }
+
+    /// Set [`module_path`](struct.Record.html#method.module_path), [`file`](struct.Record.html#method.file),
+    /// [`line`](struct.Record.html#method.line), and [`column`](struct.Record.html#method.column)
+    /// from a [`SourceLocation`](struct.SourceLocation.html) with `'static` strings.
+    #[inline]
+    pub fn source_location_static(
+        &mut self,
+        location: &SourceLocation<'static>,
+    ) -> &mut RecordBuilder<'a> {
+        self.module_path_static(Some(location.module_path()))
+            .file_static(Some(location.file()))
+            .line(Some(location.line()))
+            .column(location.column())
+    }
+
+    /// Set [`correlation_id`](struct.Record.html#method.correlation_id)
+    #[cfg(feature = "correlation")]
+    #[inline]
+    pub fn correlation_id(&mut self, id: Option<u64>) -> &mut RecordBuilder<'a> {
+        self.record.correlation_id = id;
+        self
+    }
+
+    /// Set [`nesting`](struct.Record.html#method.nesting)
+    #[cfg(feature = "nesting")]
+    #[inline]
+    pub fn nesting(&mut self, depth: usize) -> &mut RecordBuilder<'a> {
+        self.record.nesting = depth;
+        self
+    }
+
+    /// Set [`timestamp`](struct.Record.html#method.timestamp)
+    #[cfg(feature = "timestamps")]
+    #[inline]
+    pub fn timestamp(&mut self, timestamp: Option<u64>) -> &mut RecordBuilder<'a> {
+        self.record.timestamp = timestamp;
+        self
+    }
+
+    /// Set [`seq`](struct.Record.html#method.seq)
+    #[cfg(feature = "seq")]
+    #[inline]
+    pub fn seq(&mut self, seq: Option<u64>) -> &mut RecordBuilder<'a> {
+        self.record.seq = seq;
+        self
+    }
+
     /// Set [`key_values`](struct.Record.html#method.key_values)
     #[cfg(feature = "kv")]
     #[inline]
     pub fn key_values(&mut self, kvs: &'a dyn kv::Source) -> &mut RecordBuilder<'a> {
-        self.record.key_values = KeyValues(kvs);
+        self.record.key_values = KeyValues::new(kvs);
         self
     }
 
+    /// Set [`extensions`](struct.Record.html#method.extensions)
+    #[cfg(feature = "extensions")]
+    #[inline]
+    pub fn extensions(&mut self, extensions: &'a Extensions) -> &mut RecordBuilder<'a> {
+        self.record.extensions = extensions;
+        self
+    }
+
+    /// Move the builder's fields out into a new builder, leaving this one with
+    /// the defaults from [`RecordBuilder::new`].
+    ///
+    /// The setters return `&mut RecordBuilder`, so a builder configured in a
+    /// single chain can't be kept in a variable directly. Ending the chain
+    /// with `take` returns it by value, so it can be configured further.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Level, Record};
+    ///
+    /// # let line = Some(42);
+    /// let mut builder = Record::builder().level(Level::Warn).target("bridge").take();
+    ///
+    /// if let Some(line) = line {
+    ///     builder.line(Some(line));
+    /// }
+    ///
+    /// let record = builder.args(format_args!("converted")).build();
+    /// assert_eq!(Some(42), record.line());
+    /// ```
+    ///
+    /// [`RecordBuilder::new`]: struct.RecordBuilder.html#method.new
+    #[inline]
+    pub fn take(&mut self) -> RecordBuilder<'a> {
+        mem::take(self)
+    }
+
     /// Invoke the builder and return a `Record`
+    ///
+    /// The builder isn't consumed, so `build` can be called any number of
+    /// times, and the builder can be changed between calls. Each call returns
+    /// an independent copy of the record.
     #[inline]
     pub fn build(&self) -> Record<'a> {
         self.record.clone()
     }
+
+    /// Invoke the builder and return a boxed `Record`.
+    ///
+    /// Records are fairly large, so this is useful for storing them in other
+    /// types, like the variants of an enum, without making those types large
+    /// too. Like [`build`], this can be called any number of times.
+    ///
+    /// [`build`]: #method.build
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn build_boxed(&self) -> Box<Record<'a>> {
+        Box::new(self.build())
+    }
 }
 
 impl<'a> Default for RecordBuilder<'a> {
@@ -1039,6 +2056,46 @@ impl<'a> Default for RecordBuilder<'a> {
     }
 }
 
+/// The kind of a log message.
+///
+/// Most messages are plain logs, but applications can mark messages that serve
+/// another purpose so loggers can route them separately. A terminal application
+/// might render progress updates in a progress bar, for example, while writing
+/// other messages to the console.
+///
+/// The logging macros accept a kind before the target:
+///
+/// ```
+/// use log::{info, RecordKind};
+///
+/// # fn main() {
+/// info!(kind: RecordKind::Progress, "downloaded {}%", 42);
+/// info!(kind: RecordKind::Event, target: "auth", "user logged in");
+/// # }
+/// ```
+///
+/// Loggers can check the kind of a message with [`Metadata::kind`].
+///
+/// [`Metadata::kind`]: struct.Metadata.html#method.kind
+#[non_exhaustive]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum RecordKind {
+    /// A diagnostic message. This is the default.
+    Log,
+    /// A notable occurrence in the application, like a request completing.
+    Event,
+    /// An update on the progress of a long-running task.
+    Progress,
+    /// A measurement, usually carried in the message's key-values.
+    Metric,
+}
+
+impl Default for RecordKind {
+    fn default() -> Self {
+        RecordKind::Log
+    }
+}
+
 /// Metadata about a log message.
 ///
 /// # Use
@@ -1081,6 +2138,7 @@ impl<'a> Default for RecordBuilder<'a> {
 pub struct Metadata<'a> {
     level: Level,
     target: &'a str,
+    extras: Option<&'a MetadataExtras<'a>>,
 }
 
 impl<'a> Metadata<'a> {
@@ -1101,6 +2159,246 @@ impl<'a> Metadata<'a> {
     pub fn target(&self) -> &'a str {
         self.target
     }
+
+    /// The handle of the target, if it was logged with a registered target.
+    ///
+    /// The handle is only returned while the [`target`] is its name, so it's
+    /// dropped when the target is changed, such as by [`clone_with_target`].
+    ///
+    /// See [`TargetHandle`](struct.TargetHandle.html) for details.
+    ///
+    /// [`target`]: #method.target
+    /// [`clone_with_target`]: #method.clone_with_target
+    #[inline]
+    pub fn target_handle(&self) -> Option<TargetHandle> {
+        self.extras?
+            .target_handle
+            .filter(|handle| handle.name() == self.target)
+    }
+
+    /// The kind of the message.
+    ///
+    /// It's [`RecordKind::Log`] unless it was set in the [`extras`].
+    ///
+    /// [`RecordKind::Log`]: enum.RecordKind.html#variant.Log
+    /// [`extras`]: #method.extras
+    #[inline]
+    pub fn kind(&self) -> RecordKind {
+        self.extras.map_or(RecordKind::Log, |extras| extras.kind)
+    }
+
+    /// The verbosity of the message within its level.
+    ///
+    /// Messages logged with a `v:` verbosity, such as `trace!(v: 3, ...)`,
+    /// carry it here. It lets libraries that log a lot at the trace level
+    /// split it into finer levels, with higher verbosities for more detailed
+    /// messages, so loggers can filter on it instead of on made-up targets.
+    /// It's `0` for messages logged without one. The facade doesn't filter on
+    /// it; that's up to the logger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Level, Metadata, MetadataExtras};
+    ///
+    /// // Only log trace messages up to verbosity 2
+    /// fn enabled(metadata: &Metadata) -> bool {
+    ///     metadata.level() < Level::Trace || metadata.verbosity() <= 2
+    /// }
+    ///
+    /// let mut extras = MetadataExtras::new();
+    /// extras.verbosity(3);
+    ///
+    /// let metadata = Metadata::builder()
+    ///     .level(Level::Trace)
+    ///     .extras(Some(&extras))
+    ///     .build();
+    /// assert!(!enabled(&metadata));
+    /// ```
+    #[inline]
+    pub fn verbosity(&self) -> u8 {
+        self.extras.map_or(0, |extras| extras.verbosity)
+    }
+
+    /// Whether the message is an audit record.
+    ///
+    /// Audit records are created by the [`audit!`] macro. They must not be
+    /// sampled or dropped, and loggers may want to route them to separate,
+    /// tamper-resistant storage.
+    ///
+    /// [`audit!`]: macro.audit.html
+    #[inline]
+    pub fn is_audit(&self) -> bool {
+        self.audit_name().is_some()
+    }
+
+    /// The event name of an audit record.
+    ///
+    /// This is the `name` given to the [`audit!`] macro, and is `None` if the
+    /// message isn't an audit record.
+    ///
+    /// [`audit!`]: macro.audit.html
+    #[inline]
+    pub fn audit_name(&self) -> Option<&'a str> {
+        self.extras?.audit
+    }
+
+    /// The rate the message was sampled at, if it was sampled.
+    ///
+    /// Messages logged with a `sample:` rate, such as `info!(sample: 0.01, ...)`,
+    /// are only passed to the logger with that probability, so this one stands in
+    /// for roughly `1.0 / rate` messages. Loggers that aggregate messages, such
+    /// as by counting them, can use it to weight their counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Metadata, MetadataExtras};
+    ///
+    /// // Weight a count of messages by their sample rate
+    /// fn weight(metadata: &Metadata) -> f64 {
+    ///     1.0 / metadata.sample_rate().unwrap_or(1.0)
+    /// }
+    ///
+    /// let mut extras = MetadataExtras::new();
+    /// extras.sample_rate(Some(0.25));
+    ///
+    /// let metadata = Metadata::builder().extras(Some(&extras)).build();
+    /// assert_eq!(4.0, weight(&metadata));
+    /// ```
+    #[inline]
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.extras?.sample_rate.map(f64::from_bits)
+    }
+
+    /// The de-duplication key of the message, if it has one.
+    ///
+    /// Messages logged with a `dedup:` key, such as
+    /// `warn!(dedup: "disk_full:/dev/sda1", "disk full")`, carry it here. It
+    /// identifies messages that are repeats of the same underlying event, so
+    /// downstream sinks, like alerting systems, can group them even when they
+    /// come from different processes. The `log` crate only carries the key, it
+    /// doesn't interpret it.
+    ///
+    /// A key can be derived from a set of values with [`DedupKey`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Metadata, MetadataExtras};
+    ///
+    /// let mut extras = MetadataExtras::new();
+    /// extras.dedup_key(Some("disk_full:/dev/sda1"));
+    ///
+    /// let metadata = Metadata::builder().extras(Some(&extras)).build();
+    ///
+    /// assert_eq!(Some("disk_full:/dev/sda1"), metadata.dedup_key());
+    /// ```
+    ///
+    /// [`DedupKey`]: struct.DedupKey.html
+    #[inline]
+    pub fn dedup_key(&self) -> Option<&'a str> {
+        self.extras?.dedup_key
+    }
+
+    /// The stable id of the message, if it has one.
+    ///
+    /// Messages logged with an `id:`, such as
+    /// `info!(id: "user.login.success", "user {} logged in", name)`, carry it
+    /// here. Unlike the message text, which may be reworded between releases,
+    /// the id stays the same, so applications can use it to look up a
+    /// translation of the message, and monitoring dashboards can key on it.
+    /// The `log` crate only carries the id, it doesn't interpret it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Metadata, MetadataExtras};
+    ///
+    /// let mut extras = MetadataExtras::new();
+    /// extras.msg_id(Some("user.login.success"));
+    ///
+    /// let metadata = Metadata::builder().extras(Some(&extras)).build();
+    ///
+    /// assert_eq!(Some("user.login.success"), metadata.msg_id());
+    /// ```
+    #[inline]
+    pub fn msg_id(&self) -> Option<&'static str> {
+        self.extras?.msg_id
+    }
+
+    /// The rarely used metadata of the message, if it has any.
+    ///
+    /// This is where the [`target_handle`], [`kind`], [`verbosity`],
+    /// [`audit_name`], [`sample_rate`], [`dedup_key`], and [`msg_id`] are
+    /// stored. It can be passed to [`MetadataBuilder::extras`] to copy them to
+    /// other metadata.
+    ///
+    /// [`target_handle`]: #method.target_handle
+    /// [`kind`]: #method.kind
+    /// [`verbosity`]: #method.verbosity
+    /// [`audit_name`]: #method.audit_name
+    /// [`sample_rate`]: #method.sample_rate
+    /// [`dedup_key`]: #method.dedup_key
+    /// [`msg_id`]: #method.msg_id
+    /// [`MetadataBuilder::extras`]: struct.MetadataBuilder.html#method.extras
+    #[inline]
+    pub fn extras(&self) -> Option<&'a MetadataExtras<'a>> {
+        self.extras
+    }
+
+    /// Get a copy of this metadata with a different target.
+    ///
+    /// All other fields are kept, but the [`target_handle`] is no longer
+    /// returned, since it belongs to the original target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Level, Metadata};
+    ///
+    /// let metadata = Metadata::builder().level(Level::Warn).target("db").build();
+    /// let renamed = metadata.clone_with_target("tenant_a::db");
+    ///
+    /// assert_eq!("tenant_a::db", renamed.target());
+    /// assert_eq!(Level::Warn, renamed.level());
+    /// ```
+    ///
+    /// [`target_handle`]: #method.target_handle
+    #[inline]
+    pub fn clone_with_target(&self, target: &'a str) -> Metadata<'a> {
+        Metadata {
+            target,
+            ..self.clone()
+        }
+    }
+}
+
+impl Metadata<'static> {
+    /// Construct `Metadata` with a `'static` target.
+    ///
+    /// Unlike [`MetadataBuilder`](struct.MetadataBuilder.html), this function can be
+    /// used in `const` and `static` items, which makes it possible to cache metadata
+    /// per callsite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use log::{Level, Metadata};
+    ///
+    /// static METADATA: Metadata<'static> = Metadata::new_static(Level::Info, "my_app");
+    ///
+    /// assert_eq!(Level::Info, METADATA.level());
+    /// assert_eq!("my_app", METADATA.target());
+    /// ```
+    #[inline]
+    pub const fn new_static(level: Level, target: &'static str) -> Metadata<'static> {
+        Metadata {
+            level,
+            target,
+            extras: None,
+        }
+    }
 }
 
 /// Builder for [`Metadata`](struct.Metadata.html).
@@ -1131,12 +2429,14 @@ impl<'a> MetadataBuilder<'a> {
     ///
     /// - `level`: `Level::Info`
     /// - `target`: `""`
+    /// - `extras`: `None`
     #[inline]
     pub fn new() -> MetadataBuilder<'a> {
         MetadataBuilder {
             metadata: Metadata {
                 level: Level::Info,
                 target: "",
+                extras: None,
             },
         }
     }
@@ -1148,23 +2448,137 @@ impl<'a> MetadataBuilder<'a> {
         self
     }
 
-    /// Setter for [`target`](struct.Metadata.html#method.target).
+    /// Setter for [`target`](struct.Metadata.html#method.target).
+    #[inline]
+    pub fn target(&mut self, target: &'a str) -> &mut MetadataBuilder<'a> {
+        self.metadata.target = target;
+        self
+    }
+
+    /// Setter for [`extras`](struct.Metadata.html#method.extras).
+    #[inline]
+    pub fn extras(&mut self, extras: Option<&'a MetadataExtras<'a>>) -> &mut MetadataBuilder<'a> {
+        self.metadata.extras = extras;
+        self
+    }
+
+    /// Returns a `Metadata` object.
+    #[inline]
+    pub fn build(&self) -> Metadata<'a> {
+        self.metadata.clone()
+    }
+}
+
+impl<'a> Default for MetadataBuilder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rarely used [`Metadata`](struct.Metadata.html) of a log message.
+///
+/// Most messages are plain logs without a registered target, verbosity,
+/// audit name, sample rate, de-duplication key, or id, so these are kept
+/// behind a single optional reference, instead of making every `Metadata`
+/// and [`Record`](struct.Record.html) bigger.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Metadata, MetadataExtras};
+///
+/// let mut extras = MetadataExtras::new();
+/// extras.sample_rate(Some(0.25));
+///
+/// let metadata = Metadata::builder().extras(Some(&extras)).build();
+///
+/// assert_eq!(Some(0.25), metadata.sample_rate());
+/// ```
+#[derive(Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MetadataExtras<'a> {
+    target_handle: Option<TargetHandle>,
+    kind: RecordKind,
+    verbosity: u8,
+    audit: Option<&'a str>,
+    // Stored as bits so `Metadata` can still be `Eq`, `Ord`, and `Hash`
+    sample_rate: Option<u64>,
+    dedup_key: Option<&'a str>,
+    msg_id: Option<&'static str>,
+}
+
+impl<'a> MetadataExtras<'a> {
+    /// Construct new `MetadataExtras`, with all of their fields set to their
+    /// defaults.
+    #[inline]
+    pub const fn new() -> MetadataExtras<'a> {
+        MetadataExtras {
+            target_handle: None,
+            kind: RecordKind::Log,
+            verbosity: 0,
+            audit: None,
+            sample_rate: None,
+            dedup_key: None,
+            msg_id: None,
+        }
+    }
+
+    /// Setter for [`target_handle`](struct.Metadata.html#method.target_handle).
+    ///
+    /// This doesn't change the [`target`](struct.Metadata.html#method.target).
+    #[inline]
+    pub fn target_handle(&mut self, handle: Option<TargetHandle>) -> &mut MetadataExtras<'a> {
+        self.target_handle = handle;
+        self
+    }
+
+    /// Setter for [`kind`](struct.Metadata.html#method.kind).
+    #[inline]
+    pub fn kind(&mut self, kind: RecordKind) -> &mut MetadataExtras<'a> {
+        self.kind = kind;
+        self
+    }
+
+    /// Setter for [`verbosity`](struct.Metadata.html#method.verbosity).
+    #[inline]
+    pub fn verbosity(&mut self, verbosity: u8) -> &mut MetadataExtras<'a> {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Setter for [`audit_name`](struct.Metadata.html#method.audit_name).
+    ///
+    /// Setting a name marks the metadata as belonging to an audit record.
+    #[inline]
+    pub fn audit(&mut self, name: Option<&'a str>) -> &mut MetadataExtras<'a> {
+        self.audit = name;
+        self
+    }
+
+    /// Setter for [`sample_rate`](struct.Metadata.html#method.sample_rate).
+    #[inline]
+    pub fn sample_rate(&mut self, rate: Option<f64>) -> &mut MetadataExtras<'a> {
+        self.sample_rate = rate.map(f64::to_bits);
+        self
+    }
+
+    /// Setter for [`dedup_key`](struct.Metadata.html#method.dedup_key).
     #[inline]
-    pub fn target(&mut self, target: &'a str) -> &mut MetadataBuilder<'a> {
-        self.metadata.target = target;
+    pub fn dedup_key(&mut self, key: Option<&'a str>) -> &mut MetadataExtras<'a> {
+        self.dedup_key = key;
         self
     }
 
-    /// Returns a `Metadata` object.
+    /// Setter for [`msg_id`](struct.Metadata.html#method.msg_id).
     #[inline]
-    pub fn build(&self) -> Metadata<'a> {
-        self.metadata.clone()
+    pub fn msg_id(&mut self, id: Option<&'static str>) -> &mut MetadataExtras<'a> {
+        self.msg_id = id;
+        self
     }
-}
 
-impl<'a> Default for MetadataBuilder<'a> {
-    fn default() -> Self {
-        Self::new()
+    // Whether none of the fields are set, so metadata can leave them out
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == MetadataExtras::new()
     }
 }
 
@@ -1193,6 +2607,22 @@ pub trait Log: Sync + Send {
     /// internally.
     fn log(&self, record: &Record);
 
+    /// Logs a batch of `Record`s.
+    ///
+    /// This is useful for adapters that collect records before passing them to
+    /// a logger, such as from a channel or an FFI boundary.
+    ///
+    /// # For implementors
+    ///
+    /// The default implementation calls [`log`](#tymethod.log) for each record.
+    /// Implementations that can write a batch of records more efficiently, such as
+    /// with a single lock acquisition or syscall, should override it.
+    fn log_batched(&self, records: &[&Record]) {
+        for record in records {
+            self.log(record);
+        }
+    }
+
     /// Flushes any buffered records.
     ///
     /// # For implementors
@@ -1200,10 +2630,64 @@ pub trait Log: Sync + Send {
     /// This method isn't called automatically by the `log!` macros.
     /// It can be called manually on shut-down to ensure any in-flight records are flushed.
     fn flush(&self);
+
+    /// Called once when the logger is installed as the global logger, before
+    /// it's passed any records.
+    ///
+    /// # For implementors
+    ///
+    /// This is a well-defined place to start background threads, open files,
+    /// or register signal handlers, rather than doing it lazily in the first
+    /// call to [`log`](#tymethod.log). The default implementation does nothing.
+    ///
+    /// It's called by [`set_logger`], [`set_boxed_logger`], and
+    /// [`set_logger_racy`] while the global logger is being installed, so it
+    /// must not try to install a logger itself. If it panics, the logger isn't
+    /// installed, and another one can be set instead.
+    /// Records logged from it go wherever records logged before installation
+    /// go; see [`set_pre_init_policy`].
+    ///
+    /// [`set_logger`]: fn.set_logger.html
+    /// [`set_boxed_logger`]: fn.set_boxed_logger.html
+    /// [`set_logger_racy`]: fn.set_logger_racy.html
+    /// [`set_pre_init_policy`]: fn.set_pre_init_policy.html
+    fn on_install(&self) {}
+
+    /// Called once when the logging system is shut down, after the logger's
+    /// final [`flush`](#tymethod.flush).
+    ///
+    /// # For implementors
+    ///
+    /// This is the place to stop background threads and release resources,
+    /// since the global logger is never dropped. The default implementation
+    /// does nothing.
+    ///
+    /// It's called by [`try_flush_and_shutdown`], and counts towards its
+    /// timeout. No more records are passed to the logger afterwards.
+    ///
+    /// [`try_flush_and_shutdown`]: fn.try_flush_and_shutdown.html
+    fn on_shutdown(&self) {}
 }
 
-// Just used as a dummy initial value for LOGGER
-struct NopLogger;
+/// A logger that discards every record.
+///
+/// This is the logger used before one is installed. It can be useful as a
+/// placeholder, such as a fallback in a logger that fans records out to
+/// several others, or to turn logging off in benchmarks.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Log, NopLogger};
+///
+/// fn fallback(logger: Option<&'static dyn Log>) -> &'static dyn Log {
+///     logger.unwrap_or(&NopLogger)
+/// }
+///
+/// log::set_logger(fallback(None)).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NopLogger;
 
 impl Log for NopLogger {
     fn enabled(&self, _: &Metadata) -> bool {
@@ -1214,6 +2698,57 @@ impl Log for NopLogger {
     fn flush(&self) {}
 }
 
+/// A logger that writes every record to standard error.
+///
+/// Each record is written on its own line, as its level, target, and message,
+/// like `WARN my_app::db: connection refused`. Filtering is left to the
+/// maximum log level.
+///
+/// This is meant for tests, examples, and small tools. Applications will
+/// usually want a logger that can be configured.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{LevelFilter, StderrLogger};
+///
+/// log::set_logger(&StderrLogger).unwrap();
+/// log::set_max_level(LevelFilter::Info);
+///
+/// log::info!("starting up");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StderrLogger;
+
+#[cfg(feature = "std")]
+impl Log for StderrLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        use std::io::Write;
+
+        // Errors writing to stderr can't be reported anywhere
+        let _ = writeln!(
+            std::io::stderr().lock(),
+            "{} {}: {}",
+            record.level(),
+            record.target(),
+            record.render()
+        );
+    }
+
+    fn flush(&self) {
+        use std::io::Write;
+
+        let _ = std::io::stderr().flush();
+    }
+}
+
 impl<T> Log for &'_ T
 where
     T: ?Sized + Log,
@@ -1225,9 +2760,18 @@ where
     fn log(&self, record: &Record) {
         (**self).log(record);
     }
+    fn log_batched(&self, records: &[&Record]) {
+        (**self).log_batched(records);
+    }
     fn flush(&self) {
         (**self).flush();
     }
+    fn on_install(&self) {
+        (**self).on_install();
+    }
+    fn on_shutdown(&self) {
+        (**self).on_shutdown();
+    }
 }
 
 #[cfg(feature = "std")]
@@ -1242,9 +2786,18 @@ where
     fn log(&self, record: &Record) {
         self.as_ref().log(record);
     }
+    fn log_batched(&self, records: &[&Record]) {
+        self.as_ref().log_batched(records);
+    }
     fn flush(&self) {
         self.as_ref().flush();
     }
+    fn on_install(&self) {
+        self.as_ref().on_install();
+    }
+    fn on_shutdown(&self) {
+        self.as_ref().on_shutdown();
+    }
 }
 
 #[cfg(feature = "std")]
@@ -1259,9 +2812,18 @@ where
     fn log(&self, record: &Record) {
         self.as_ref().log(record);
     }
+    fn log_batched(&self, records: &[&Record]) {
+        self.as_ref().log_batched(records);
+    }
     fn flush(&self) {
         self.as_ref().flush();
     }
+    fn on_install(&self) {
+        self.as_ref().on_install();
+    }
+    fn on_shutdown(&self) {
+        self.as_ref().on_shutdown();
+    }
 }
 
 /// Sets the global maximum log level.
@@ -1272,7 +2834,8 @@ where
 #[inline]
 #[cfg(target_has_atomic = "ptr")]
 pub fn set_max_level(level: LevelFilter) {
-    MAX_LOG_LEVEL_FILTER.store(level as usize, Ordering::Relaxed);
+    MAX_LOG_LEVEL_FILTER.store(level as usize, Ordering::Release);
+    FILTER_EPOCH.fetch_add(1, Ordering::Release);
 }
 
 /// A thread-unsafe version of [`set_max_level`].
@@ -1299,7 +2862,8 @@ pub unsafe fn set_max_level_racy(level: LevelFilter) {
     // `MAX_LOG_LEVEL_FILTER` uses a `Cell` as the underlying primitive when a
     // platform doesn't support `target_has_atomic = "ptr"`, so even though this looks the same
     // as `set_max_level` it may have different safety properties.
-    MAX_LOG_LEVEL_FILTER.store(level as usize, Ordering::Relaxed);
+    MAX_LOG_LEVEL_FILTER.store(level as usize, Ordering::Release);
+    FILTER_EPOCH.fetch_add(1, Ordering::Release);
 }
 
 /// Returns the current maximum log level.
@@ -1315,15 +2879,93 @@ pub unsafe fn set_max_level_racy(level: LevelFilter) {
 /// [`debug!`]: macro.debug.html
 /// [`trace!`]: macro.trace.html
 /// [`set_max_level`]: fn.set_max_level.html
+///
+/// # Memory ordering
+///
+/// The level is loaded with `Acquire` ordering, and [`set_max_level`] stores it
+/// with `Release` ordering. A thread that sees a level set by another thread
+/// also sees everything that thread did before setting it. Use
+/// [`max_level_relaxed`] where only the level itself matters.
+///
+/// [`max_level_relaxed`]: fn.max_level_relaxed.html
 #[inline(always)]
 pub fn max_level() -> LevelFilter {
-    // Since `LevelFilter` is `repr(usize)`,
-    // this transmute is sound if and only if `MAX_LOG_LEVEL_FILTER`
-    // is set to a usize that is a valid discriminant for `LevelFilter`.
-    // Since `MAX_LOG_LEVEL_FILTER` is private, the only time it's set
-    // is by `set_max_level` above, i.e. by casting a `LevelFilter` to `usize`.
-    // So any usize stored in `MAX_LOG_LEVEL_FILTER` is a valid discriminant.
-    unsafe { mem::transmute(MAX_LOG_LEVEL_FILTER.load(Ordering::Relaxed)) }
+    level_filter_from_usize(MAX_LOG_LEVEL_FILTER.load(Ordering::Acquire))
+}
+
+/// Returns the current maximum log level, without synchronizing with the
+/// thread that set it.
+///
+/// This is the check the logging macros use to discard records cheaply. It
+/// returns the same levels as [`max_level`], but only guarantees that the
+/// level itself is up to date, not any other memory written before it was
+/// set. Loggers that read their own configuration after this check should
+/// synchronize on it themselves.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, LevelFilter};
+///
+/// log::set_max_level(LevelFilter::Info);
+///
+/// if Level::Debug <= log::max_level_relaxed() {
+///     // Only build expensive diagnostics if they could be logged
+/// }
+/// # assert_eq!(LevelFilter::Info, log::max_level_relaxed());
+/// ```
+///
+/// [`max_level`]: fn.max_level.html
+#[inline(always)]
+pub fn max_level_relaxed() -> LevelFilter {
+    level_filter_from_usize(MAX_LOG_LEVEL_FILTER.load(Ordering::Relaxed))
+}
+
+// `MAX_LOG_LEVEL_FILTER` is private and only ever set from a `LevelFilter`,
+// so the fallback is unreachable. Mapping it to `Trace` rather than using
+// `mem::transmute` keeps this safe, and compiles to a `min`.
+#[inline(always)]
+fn level_filter_from_usize(level: usize) -> LevelFilter {
+    match level {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Returns the current maximum log level along with a change epoch.
+///
+/// The epoch is incremented whenever the maximum log level or the global
+/// logger is set. Callers that cache filtering decisions, such as per-callsite
+/// interest caches, can store the epoch alongside their results and only
+/// re-validate them when it changes, instead of consulting the logger for
+/// every record.
+///
+/// The epoch is read before the level, so a returned level is never older
+/// than its epoch. At worst a cache is re-validated one more time than it
+/// needs to be.
+///
+/// # Examples
+///
+/// ```
+/// use log::LevelFilter;
+///
+/// let (_, epoch) = log::max_level_epoch();
+///
+/// log::set_max_level(LevelFilter::Debug);
+///
+/// let (level, new_epoch) = log::max_level_epoch();
+/// assert_eq!(LevelFilter::Debug, level);
+/// assert_ne!(epoch, new_epoch);
+/// ```
+#[inline]
+pub fn max_level_epoch() -> (LevelFilter, u64) {
+    let epoch = FILTER_EPOCH.load(Ordering::Acquire);
+
+    (max_level(), epoch as u64)
 }
 
 /// Sets the global logger to a `Box<Log>`.
@@ -1332,6 +2974,13 @@ pub fn max_level() -> LevelFilter {
 /// `Box<Log>` rather than a `&'static Log`. See the documentation for
 /// [`set_logger`] for more details.
 ///
+/// The logger is leaked, and can't be unset or replaced. Since [`logger`] hands
+/// out `&'static` references to it, which other threads may hold onto for any
+/// amount of time, there's no point at which it would be safe to drop. Loggers
+/// that need to release resources at the end of the program should do so in
+/// their [`Log::flush`] implementation, and be flushed with
+/// [`try_flush_and_shutdown`] or [`flush_on_exit`].
+///
 /// Requires the `std` feature.
 ///
 /// # Errors
@@ -1339,11 +2988,80 @@ pub fn max_level() -> LevelFilter {
 /// An error is returned if a logger has already been set.
 ///
 /// [`set_logger`]: fn.set_logger.html
+/// [`logger`]: fn.logger.html
+/// [`Log::flush`]: trait.Log.html#tymethod.flush
+/// [`try_flush_and_shutdown`]: fn.try_flush_and_shutdown.html
+/// [`flush_on_exit`]: fn.flush_on_exit.html
 #[cfg(all(feature = "std", target_has_atomic = "ptr"))]
 pub fn set_boxed_logger(logger: Box<dyn Log>) -> Result<(), SetLoggerError> {
     set_logger_inner(|| Box::leak(logger))
 }
 
+/// Creates a logger with `make_logger` and sets it as the global logger,
+/// returning a `'static` reference to it.
+///
+/// This is like [`set_boxed_logger`], but keeps the logger's concrete type, so
+/// it can still be configured or inspected after it's installed. `make_logger`
+/// is only called if a logger hasn't already been set. The logger is allocated
+/// once, and never freed.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+///
+/// # Examples
+///
+/// ```
+/// use log::{info, LevelFilter, Log, Metadata, Record};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// #[derive(Default)]
+/// struct CountingLogger {
+///     logged: AtomicUsize,
+/// }
+///
+/// impl Log for CountingLogger {
+///     fn enabled(&self, _: &Metadata) -> bool {
+///         true
+///     }
+///
+///     fn log(&self, _: &Record) {
+///         self.logged.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn flush(&self) {}
+/// }
+///
+/// # fn main() {
+/// let logger = log::init_with(CountingLogger::default).unwrap();
+/// log::set_max_level(LevelFilter::Info);
+///
+/// info!("hello");
+/// assert_eq!(1, logger.logged.load(Ordering::Relaxed));
+/// # }
+/// ```
+///
+/// [`set_boxed_logger`]: fn.set_boxed_logger.html
+#[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+pub fn init_with<T, F>(make_logger: F) -> Result<&'static T, SetLoggerError>
+where
+    T: Log + 'static,
+    F: FnOnce() -> T,
+{
+    let mut installed = None;
+
+    set_logger_inner(|| {
+        let logger: &'static T = Box::leak(Box::new(make_logger()));
+
+        installed = Some(logger);
+        logger
+    })?;
+
+    Ok(installed.expect("the logger was set"))
+}
+
 /// Sets the global logger to a `&'static Log`.
 ///
 /// This function may only be called once in the lifetime of a program. Any log
@@ -1414,10 +3132,30 @@ where
         Ordering::Relaxed,
     ) {
         Ok(UNINITIALIZED) => {
+            // If `make_logger` or `on_install` panics, let a logger be set
+            // again later instead of leaving other callers waiting on it forever
+            struct Reset;
+
+            impl Drop for Reset {
+                fn drop(&mut self) {
+                    STATE.store(UNINITIALIZED, Ordering::Release);
+                }
+            }
+
+            let reset = Reset;
+            let logger = make_logger();
+            logger.on_install();
+            mem::forget(reset);
+
             unsafe {
-                LOGGER = make_logger();
+                LOGGER = logger;
             }
             STATE.store(INITIALIZED, Ordering::Release);
+            FILTER_EPOCH.fetch_add(1, Ordering::Release);
+
+            #[cfg(feature = "early_buffer")]
+            early_buffer::replay(crate::logger());
+
             Ok(())
         }
         Err(INITIALIZING) => {
@@ -1452,8 +3190,14 @@ where
 pub unsafe fn set_logger_racy(logger: &'static dyn Log) -> Result<(), SetLoggerError> {
     match STATE.load(Ordering::Acquire) {
         UNINITIALIZED => {
+            logger.on_install();
             LOGGER = logger;
             STATE.store(INITIALIZED, Ordering::Release);
+            FILTER_EPOCH.fetch_add(1, Ordering::Release);
+
+            #[cfg(feature = "early_buffer")]
+            early_buffer::replay(crate::logger());
+
             Ok(())
         }
         INITIALIZING => {
@@ -1464,57 +3208,290 @@ pub unsafe fn set_logger_racy(logger: &'static dyn Log) -> Result<(), SetLoggerE
     }
 }
 
-/// The type returned by [`set_logger`] if [`set_logger`] has already been called.
+/// The type returned by [`set_logger`] if [`set_logger`] has already been called.
+///
+/// [`set_logger`]: fn.set_logger.html
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub struct SetLoggerError(());
+
+impl fmt::Display for SetLoggerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(SET_LOGGER_ERROR)
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl error::Error for SetLoggerError {}
+
+/// The type returned by [`from_str`] when the string doesn't match any of the log levels.
+///
+/// [`from_str`]: https://doc.rust-lang.org/std/str/trait.FromStr.html#tymethod.from_str
+#[allow(missing_copy_implementations)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseLevelError(());
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(LEVEL_PARSE_ERROR)
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl error::Error for ParseLevelError {}
+
+/// The type returned by `try_from` when the integer doesn't match any of the log levels.
+///
+/// See [`Level`] for the values of each level.
+///
+/// [`Level`]: enum.Level.html
+#[allow(missing_copy_implementations)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct TryFromLevelError(());
+
+impl fmt::Display for TryFromLevelError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(LEVEL_TRY_FROM_ERROR)
+    }
+}
+
+// The Error trait is not available in libcore
+#[cfg(feature = "std")]
+impl error::Error for TryFromLevelError {}
+
+/// Returns a reference to the logger.
+///
+/// If a logger has not been set, a no-op implementation is returned.
+pub fn logger() -> &'static dyn Log {
+    // Acquire memory ordering guarantees that current thread would see any
+    // memory writes that happened before store of the value
+    // into `STATE` with memory ordering `Release` or stronger.
+    //
+    // Since the value `INITIALIZED` is written only after `LOGGER` was
+    // initialized, observing it after `Acquire` load here makes both
+    // write to the `LOGGER` static and initialization of the logger
+    // internal state synchronized with current thread.
+    if STATE.load(Ordering::Acquire) != INITIALIZED {
+        static NOP: NopLogger = NopLogger;
+        &NOP
+    } else {
+        unsafe { LOGGER }
+    }
+}
+
+/// Log a record that wasn't created by the logging macros.
+///
+/// The record is passed to the global logger if its level is enabled by
+/// [`STATIC_MAX_LEVEL`] and the current [`max_level`], and if [`Log::enabled`]
+/// returns `true` for its metadata. Audit records skip the level checks, like
+/// those created by the [`audit!`] macro.
+///
+/// This is useful for bridges that receive records from elsewhere, such as
+/// another logging library, a foreign function interface, or a file being
+/// replayed. If the bridge already called [`Log::enabled`] itself, use
+/// [`log_record_skip_enabled`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, Record};
+///
+/// // A line read from another program's output
+/// fn forward(level: Level, target: &str, message: &str) {
+///     log::log_record(
+///         &Record::builder()
+///             .args(format_args!("{}", message))
+///             .level(level)
+///             .target(target)
+///             .build(),
+///     );
+/// }
+/// # forward(Level::Info, "child", "started");
+/// ```
+///
+/// [`STATIC_MAX_LEVEL`]: constant.STATIC_MAX_LEVEL.html
+/// [`max_level`]: fn.max_level.html
+/// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+/// [`audit!`]: macro.audit.html
+/// [`log_record_skip_enabled`]: fn.log_record_skip_enabled.html
+pub fn log_record(record: &Record) {
+    #[cfg(feature = "std")]
+    let capturing = capture::is_capturing();
+    #[cfg(not(feature = "std"))]
+    let capturing = false;
+
+    if __private_api::metadata_allows(record.metadata())
+        && (capturing || logger().enabled(record.metadata()))
+    {
+        submit_record(record);
+    }
+}
+
+/// Log a record that wasn't created by the logging macros, without calling
+/// [`Log::enabled`].
+///
+/// This is the same as [`log_record`], but only checks the level of the
+/// record. Use it when the caller has already called [`Log::enabled`] with the
+/// record's metadata.
+///
+/// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+/// [`log_record`]: fn.log_record.html
+pub fn log_record_skip_enabled(record: &Record) {
+    if __private_api::metadata_allows(record.metadata()) {
+        submit_record(record);
+    }
+}
+
+fn submit_record(record: &Record) {
+    // Captured records never reach the logger, so they can still be captured
+    // after it's shut down
+    #[cfg(feature = "std")]
+    if capture::try_capture(record) {
+        return;
+    }
+
+    #[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+    if shutdown::is_shutdown() {
+        shutdown::discard_late_record();
+        return;
+    }
+
+    dispatch(logger(), record);
+}
+
+// Pass a record to `logger`, through the failure handler where it's available.
+#[inline]
+pub(crate) fn dispatch(logger: &dyn Log, record: &Record) {
+    #[cfg(all(feature = "std", target_has_atomic = "ptr"))]
+    failure::log(logger, record);
+    #[cfg(not(all(feature = "std", target_has_atomic = "ptr")))]
+    logger.log(record);
+}
+
+/// Returns a handle to the logger for a specific target.
+///
+/// The handle caches whether records at each level would be logged for `target`,
+/// so checking it in hot code is cheaper than calling [`log_enabled!`] each time.
+/// The cache is invalidated whenever the global logger or maximum log level is set.
+///
+/// Loggers that change their filtering at runtime should call [`set_max_level`]
+/// after doing so, even if the maximum level itself hasn't changed, so handles
+/// will observe the change.
+///
+/// # Examples
+///
+/// ```
+/// use log::{trace, Level};
+///
+/// # fn simulate(_: usize) -> f32 { 0.0 }
+/// let physics = log::logger_for("engine::physics");
+///
+/// for step in 0..1000 {
+///     let energy = simulate(step);
+///
+///     if physics.enabled(Level::Trace) {
+///         trace!(target: "engine::physics", "step {step}: energy {energy}");
+///     }
+/// }
+/// ```
+///
+/// [`log_enabled!`]: macro.log_enabled.html
+/// [`set_max_level`]: fn.set_max_level.html
+pub fn logger_for(target: &str) -> TargetLogger<'_> {
+    TargetLogger {
+        target,
+        cache: AtomicUsize::new(0),
+    }
+}
+
+/// A handle to the logger for a specific target.
 ///
-/// [`set_logger`]: fn.set_logger.html
-#[allow(missing_copy_implementations)]
-#[derive(Debug)]
-pub struct SetLoggerError(());
+/// This type is returned by [`logger_for`]. It forwards calls to its `Log`
+/// implementation to the global logger. Records passed to it are handled like
+/// those from [`log_record_skip_enabled`], so they can be captured, and they're
+/// discarded after shutdown.
+///
+/// [`logger_for`]: fn.logger_for.html
+/// [`log_record_skip_enabled`]: fn.log_record_skip_enabled.html
+pub struct TargetLogger<'a> {
+    target: &'a str,
+    // The low bits hold a pair of (checked, enabled) bits for each `Level`.
+    // The remaining high bits hold the `FILTER_EPOCH` they were computed in.
+    cache: AtomicUsize,
+}
 
-impl fmt::Display for SetLoggerError {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(SET_LOGGER_ERROR)
+const TARGET_LOGGER_EPOCH_SHIFT: u32 = 2 * 5;
+
+impl<'a> TargetLogger<'a> {
+    /// The target this handle is for.
+    #[inline]
+    pub fn target(&self) -> &'a str {
+        self.target
     }
-}
 
-// The Error trait is not available in libcore
-#[cfg(feature = "std")]
-impl error::Error for SetLoggerError {}
+    /// Determines if a message logged at `level` for this target would be logged.
+    ///
+    /// This checks the static and dynamic maximum log levels, as well as the
+    /// logger's [`Log::enabled`] method, caching the result.
+    ///
+    /// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+    pub fn enabled(&self, level: Level) -> bool {
+        if level > STATIC_MAX_LEVEL {
+            return false;
+        }
 
-/// The type returned by [`from_str`] when the string doesn't match any of the log levels.
-///
-/// [`from_str`]: https://doc.rust-lang.org/std/str/trait.FromStr.html#tymethod.from_str
-#[allow(missing_copy_implementations)]
-#[derive(Debug, PartialEq, Eq)]
-pub struct ParseLevelError(());
+        let epoch = FILTER_EPOCH.load(Ordering::Acquire) << TARGET_LOGGER_EPOCH_SHIFT;
 
-impl fmt::Display for ParseLevelError {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(LEVEL_PARSE_ERROR)
+        let checked = 1 << ((level as usize - 1) * 2);
+        let enabled = checked << 1;
+
+        let mut cache = self.cache.load(Ordering::Relaxed);
+        if cache & !((1 << TARGET_LOGGER_EPOCH_SHIFT) - 1) == epoch {
+            if cache & checked != 0 {
+                return cache & enabled != 0;
+            }
+        } else {
+            cache = epoch;
+        }
+
+        let is_enabled = level <= max_level()
+            && logger().enabled(&Metadata {
+                level,
+                target: self.target,
+                extras: None,
+            });
+
+        cache |= checked;
+        if is_enabled {
+            cache |= enabled;
+        }
+        self.cache.store(cache, Ordering::Relaxed);
+
+        is_enabled
     }
 }
 
-// The Error trait is not available in libcore
-#[cfg(feature = "std")]
-impl error::Error for ParseLevelError {}
+impl<'a> Log for TargetLogger<'a> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        logger().enabled(metadata)
+    }
 
-/// Returns a reference to the logger.
-///
-/// If a logger has not been set, a no-op implementation is returned.
-pub fn logger() -> &'static dyn Log {
-    // Acquire memory ordering guarantees that current thread would see any
-    // memory writes that happened before store of the value
-    // into `STATE` with memory ordering `Release` or stronger.
-    //
-    // Since the value `INITIALIZED` is written only after `LOGGER` was
-    // initialized, observing it after `Acquire` load here makes both
-    // write to the `LOGGER` static and initialization of the logger
-    // internal state synchronized with current thread.
-    if STATE.load(Ordering::Acquire) != INITIALIZED {
-        static NOP: NopLogger = NopLogger;
-        &NOP
-    } else {
-        unsafe { LOGGER }
+    fn log(&self, record: &Record) {
+        submit_record(record)
+    }
+
+    fn flush(&self) {
+        logger().flush()
+    }
+}
+
+impl<'a> fmt::Debug for TargetLogger<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TargetLogger")
+            .field("target", &self.target)
+            .finish()
     }
 }
 
@@ -1572,6 +3549,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verbosity_from_str() {
+        use super::Verbosity;
+
+        let tests = [
+            ("off", Ok(LevelFilter::Off)),
+            ("Debug", Ok(LevelFilter::Debug)),
+            ("0", Ok(LevelFilter::Off)),
+            ("3", Ok(LevelFilter::Info)),
+            ("5", Ok(LevelFilter::Trace)),
+            ("-v", Ok(LevelFilter::Error)),
+            ("-vvvv", Ok(LevelFilter::Debug)),
+            ("-vvvvvvvv", Ok(LevelFilter::Trace)),
+            ("6", Err(ParseLevelError(()))),
+            ("-1", Err(ParseLevelError(()))),
+            ("-", Err(ParseLevelError(()))),
+            ("-vx", Err(ParseLevelError(()))),
+            ("vvv", Err(ParseLevelError(()))),
+            ("", Err(ParseLevelError(()))),
+        ];
+        for &(s, ref expected) in &tests {
+            assert_eq!(
+                expected,
+                &s.parse().map(|v: Verbosity| v.level_filter()),
+                "{s:?}"
+            );
+        }
+
+        for filter in LevelFilter::iter() {
+            let verbosity = Verbosity::new(filter);
+            assert_eq!(Ok(verbosity), verbosity.to_string().parse());
+        }
+    }
+
     #[test]
     fn test_level_from_str() {
         let tests = [
@@ -1593,6 +3604,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_level_numeric() {
+        use super::TryFromLevelError;
+
+        let tests = [
+            (1, Level::Error),
+            (2, Level::Warn),
+            (3, Level::Info),
+            (4, Level::Debug),
+            (5, Level::Trace),
+        ];
+        for &(n, level) in &tests {
+            assert_eq!(n, u8::from(level));
+            assert_eq!(usize::from(n), usize::from(level));
+            assert_eq!(Ok(level), Level::try_from(n));
+            assert_eq!(Ok(level), Level::try_from(usize::from(n)));
+            assert_eq!(Ok(level.to_level_filter()), LevelFilter::try_from(n));
+            assert_eq!(n, u8::from(level.to_level_filter()));
+        }
+
+        assert_eq!(0, u8::from(LevelFilter::Off));
+        assert_eq!(Ok(LevelFilter::Off), LevelFilter::try_from(0u8));
+
+        assert_eq!(Err(TryFromLevelError(())), Level::try_from(0u8));
+        assert_eq!(Err(TryFromLevelError(())), Level::try_from(6u8));
+        assert_eq!(Err(TryFromLevelError(())), LevelFilter::try_from(6usize));
+    }
+
     #[test]
     fn test_level_as_str() {
         let tests = &[
@@ -1607,6 +3646,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_level_all() {
+        assert!(Level::ALL.iter().copied().eq(Level::iter()));
+        assert!(LevelFilter::ALL.iter().copied().eq(LevelFilter::iter()));
+
+        for level in LevelFilter::ALL {
+            assert_eq!(Ok(level), level.as_str().parse());
+        }
+    }
+
     #[test]
     fn test_level_show() {
         assert_eq!("INFO", Level::Info.to_string());
@@ -1745,6 +3794,291 @@ mod tests {
         assert_eq!(metadata_test.target(), "myApp");
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_register_target() {
+        use super::{register_target, MetadataBuilder, MetadataExtras};
+
+        let a = register_target("a");
+        let b = register_target(&String::from("a"));
+
+        assert_eq!("a", a.name());
+        assert_eq!("a", b.name());
+        assert_ne!(a.id(), b.id());
+
+        let mut extras = MetadataExtras::new();
+        extras.target_handle(Some(a));
+
+        let metadata = MetadataBuilder::new()
+            .target(a.name())
+            .extras(Some(&extras))
+            .build();
+        assert_eq!(Some(a), metadata.target_handle());
+        assert_eq!(None, MetadataBuilder::new().build().target_handle());
+
+        // The handle doesn't belong to other targets
+        let metadata = MetadataBuilder::new()
+            .target("b")
+            .extras(Some(&extras))
+            .build();
+        assert_eq!(None, metadata.target_handle());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_record_render() {
+        use super::RecordBuilder;
+        use std::cell::Cell;
+        use std::fmt;
+
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl<'a> fmt::Display for Counted<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+                f.write_str("counted")
+            }
+        }
+
+        let record = RecordBuilder::new().args(format_args!("literal")).build();
+        assert_eq!(record.render(), "literal");
+
+        fn check(args: fmt::Arguments, count: &Cell<usize>) {
+            let record = RecordBuilder::new().args(args).build();
+
+            assert_eq!(record.render(), "a counted message");
+            assert_eq!(record.render(), "a counted message");
+            assert_eq!(record.clone().render(), "a counted message");
+
+            // Without the cache, each call formats the message again
+            let expected = if cfg!(feature = "render_cache") { 1 } else { 3 };
+            assert_eq!(count.get(), expected);
+
+            // Records with extra key-values format the message again
+            #[cfg(feature = "kv")]
+            {
+                let extra = ("a", 1);
+
+                assert_eq!(record.with_extra_kvs(&extra).render(), "a counted message");
+                assert_eq!(count.get(), expected + 1);
+            }
+        }
+
+        let count = Cell::new(0);
+        check(format_args!("a {} message", Counted(&count)), &count);
+    }
+
+    #[test]
+    #[cfg(feature = "render_cache")]
+    fn test_record_render_reentrant() {
+        use super::{Record, RecordBuilder};
+        use std::borrow::Cow;
+        use std::cell::Cell;
+        use std::fmt;
+
+        // Renders the record it's part of while it's being rendered
+        struct Reentrant<'r> {
+            record: Cell<Option<&'r Record<'r>>>,
+            inner: Cell<Option<&'r str>>,
+        }
+
+        impl<'r> fmt::Display for Reentrant<'r> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if let Some(record) = self.record.take() {
+                    match record.render() {
+                        Cow::Borrowed(inner) => self.inner.set(Some(inner)),
+                        Cow::Owned(_) => panic!("the message wasn't cached"),
+                    }
+                }
+
+                f.write_str("re-entrant")
+            }
+        }
+
+        let reentrant = Reentrant {
+            record: Cell::new(None),
+            inner: Cell::new(None),
+        };
+
+        match format_args!("a {} message", reentrant) {
+            args => {
+                let record = RecordBuilder::new().args(args).build();
+                reentrant.record.set(Some(&record));
+
+                let outer = record.render();
+                let inner = reentrant
+                    .inner
+                    .get()
+                    .expect("the record wasn't rendered again");
+
+                // The message cached by the inner call is still valid
+                assert_eq!(inner, "a re-entrant message");
+                assert_eq!(outer, "a re-entrant message");
+                assert_eq!(inner.as_ptr(), outer.as_ptr());
+            }
+        }
+    }
+
+    #[test]
+    fn test_source_location() {
+        use super::{RecordBuilder, SourceLocation};
+
+        let record = RecordBuilder::new()
+            .module_path(Some("a::b"))
+            .file(Some("src/b.rs"))
+            .build();
+        assert_eq!(record.source_location(), None);
+
+        let record = RecordBuilder::new()
+            .module_path(Some("a::b"))
+            .file(Some("src/b.rs"))
+            .line(Some(7))
+            .build();
+        assert_eq!(
+            record.source_location(),
+            Some(SourceLocation::new("a::b", "src/b.rs", 7))
+        );
+
+        let location = SourceLocation::new("a::b", "src/b.rs", 7).with_column(3);
+        let record = RecordBuilder::new().source_location(&location).build();
+        assert_eq!(record.source_location(), Some(location));
+        assert_eq!(record.module_path(), Some("a::b"));
+        assert_eq!(record.file(), Some("src/b.rs"));
+        assert_eq!(record.line(), Some(7));
+        assert_eq!(record.column(), Some(3));
+        assert_eq!(record.module_path_static(), None);
+
+        static LOCATION: SourceLocation<'static> = SourceLocation::new("a::b", "src/b.rs", 7);
+        let record = RecordBuilder::new()
+            .source_location_static(&LOCATION)
+            .build();
+        assert_eq!(record.module_path_static(), Some("a::b"));
+        assert_eq!(record.file_static(), Some("src/b.rs"));
+        assert_eq!(record.column(), None);
+    }
+
+    #[test]
+    fn test_metadata_sample_rate() {
+        use super::{MetadataBuilder, MetadataExtras, RecordBuilder};
+
+        assert_eq!(MetadataBuilder::new().build().sample_rate(), None);
+
+        let mut extras = MetadataExtras::new();
+        extras.sample_rate(Some(0.1));
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(metadata.sample_rate(), Some(0.1));
+        assert_eq!(metadata.extras(), Some(&extras));
+
+        let record = RecordBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(record.metadata().sample_rate(), Some(0.1));
+    }
+
+    #[test]
+    fn test_metadata_dedup_key() {
+        use super::{MetadataBuilder, MetadataExtras, RecordBuilder};
+
+        assert_eq!(MetadataBuilder::new().build().dedup_key(), None);
+
+        let mut extras = MetadataExtras::new();
+        extras.dedup_key(Some("disk_full"));
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(metadata.dedup_key(), Some("disk_full"));
+
+        let record = RecordBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(record.metadata().dedup_key(), Some("disk_full"));
+    }
+
+    #[test]
+    fn test_metadata_msg_id() {
+        use super::{MetadataBuilder, MetadataExtras, RecordBuilder};
+
+        assert_eq!(MetadataBuilder::new().build().msg_id(), None);
+
+        let mut extras = MetadataExtras::new();
+        extras.msg_id(Some("user.login"));
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(metadata.msg_id(), Some("user.login"));
+
+        let record = RecordBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(record.metadata().msg_id(), Some("user.login"));
+    }
+
+    #[test]
+    fn test_metadata_verbosity() {
+        use super::{MetadataBuilder, MetadataExtras, RecordBuilder};
+
+        assert_eq!(MetadataBuilder::new().build().verbosity(), 0);
+
+        let mut extras = MetadataExtras::new();
+        extras.verbosity(2);
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(metadata.verbosity(), 2);
+
+        extras.verbosity(3);
+        let record = RecordBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(record.metadata().verbosity(), 3);
+    }
+
+    #[test]
+    fn test_sampler() {
+        use super::__private_api::Sampler;
+
+        let sampler = Sampler::new();
+
+        assert!((0..100).all(|_| sampler.sample(1.0)));
+        assert!((0..100).all(|_| !sampler.sample(0.0)));
+        assert!((0..100).all(|_| !sampler.sample(f64::NAN)));
+
+        let sampled = (0..100_000).filter(|_| sampler.sample(0.25)).count();
+        assert!((23_000..27_000).contains(&sampled), "{}", sampled);
+    }
+
+    #[test]
+    fn test_metadata_kind() {
+        use super::{MetadataBuilder, MetadataExtras, RecordBuilder, RecordKind};
+
+        assert_eq!(MetadataBuilder::new().build().kind(), RecordKind::Log);
+
+        let mut extras = MetadataExtras::new();
+        extras.kind(RecordKind::Progress);
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(metadata.kind(), RecordKind::Progress);
+
+        extras.kind(RecordKind::Metric);
+        let record = RecordBuilder::new().extras(Some(&extras)).build();
+        assert_eq!(record.metadata().kind(), RecordKind::Metric);
+    }
+
+    #[test]
+    fn test_metadata_audit() {
+        use super::{MetadataBuilder, MetadataExtras};
+        let mut extras = MetadataExtras::new();
+        extras.audit(Some("user.login"));
+        let metadata = MetadataBuilder::new().extras(Some(&extras)).build();
+        assert!(metadata.is_audit());
+        assert_eq!(metadata.audit_name(), Some("user.login"));
+
+        let metadata = MetadataBuilder::new().build();
+        assert!(!metadata.is_audit());
+        assert_eq!(metadata.audit_name(), None);
+    }
+
+    #[test]
+    fn test_metadata_new_static() {
+        use super::Metadata;
+        const METADATA: Metadata<'static> = Metadata::new_static(Level::Warn, "myApp");
+        static STATIC_METADATA: Metadata<'static> = METADATA;
+        assert_eq!(STATIC_METADATA.level(), Level::Warn);
+        assert_eq!(STATIC_METADATA.target(), "myApp");
+        assert_eq!(
+            METADATA,
+            Metadata::builder()
+                .level(Level::Warn)
+                .target("myApp")
+                .build()
+        );
+    }
+
     #[test]
     fn test_record_builder() {
         use super::{MetadataBuilder, RecordBuilder};
@@ -1764,6 +4098,45 @@ mod tests {
         assert_eq!(record_test.line(), Some(30));
     }
 
+    #[test]
+    fn test_record_builder_take() {
+        use super::{Level, Record};
+        let mut builder = Record::builder().level(Level::Warn).target("myApp").take();
+
+        let first = builder.line(Some(30)).build();
+        let second = builder.line(Some(31)).build();
+        assert_eq!(first.target(), "myApp");
+        assert_eq!(first.line(), Some(30));
+        assert_eq!(second.line(), Some(31));
+
+        let taken = builder.take();
+        assert_eq!(taken.build().level(), Level::Warn);
+        assert_eq!(builder.build().level(), Level::Info);
+    }
+
+    #[test]
+    fn test_clone_with_target() {
+        use super::{Level, Record};
+        let mut builder = Record::builder();
+        builder.level(Level::Error).target("db").line(Some(30));
+        #[cfg(feature = "std")]
+        let extras =
+            *super::MetadataExtras::new().target_handle(Some(super::register_target("db")));
+        #[cfg(feature = "std")]
+        builder.extras(Some(&extras));
+        let record = builder.build();
+        #[cfg(feature = "std")]
+        assert!(record.metadata().target_handle().is_some());
+
+        let target = String::from("tenant_a::db");
+        let renamed = record.clone_with_target(&target);
+        assert_eq!(renamed.target(), "tenant_a::db");
+        assert_eq!(renamed.metadata().target_handle(), None);
+        assert_eq!(renamed.level(), Level::Error);
+        assert_eq!(renamed.line(), Some(30));
+        assert_eq!(record.target(), "db");
+    }
+
     #[test]
     fn test_record_convenience_builder() {
         use super::{Metadata, Record};
@@ -1801,6 +4174,33 @@ mod tests {
         assert_eq!(record_test.line(), Some(30));
     }
 
+    #[test]
+    #[cfg(feature = "correlation")]
+    fn test_record_correlation_id_builder() {
+        use super::Record;
+        let record_test = Record::builder().correlation_id(Some(7)).build();
+        assert_eq!(record_test.correlation_id(), Some(7));
+        assert_eq!(Record::builder().build().correlation_id(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "seq")]
+    fn test_record_seq_builder() {
+        use super::Record;
+        let record_test = Record::builder().seq(Some(3)).build();
+        assert_eq!(record_test.seq(), Some(3));
+        assert_eq!(Record::builder().build().seq(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "timestamps")]
+    fn test_record_timestamp_builder() {
+        use super::Record;
+        let record_test = Record::builder().timestamp(Some(42)).build();
+        assert_eq!(record_test.timestamp(), Some(42));
+        assert_eq!(Record::builder().build().timestamp(), None);
+    }
+
     #[test]
     #[cfg(feature = "kv")]
     fn test_record_key_values_builder() {
@@ -1851,6 +4251,70 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "kv")]
+    fn test_record_with_extra_kvs() {
+        use super::Record;
+        use crate::kv::Source;
+
+        let kvs: &[(&str, i32)] = &[("a", 1), ("b", 2)];
+        let record = Record::builder().key_values(&kvs).build();
+
+        let extra: &[(&str, i32)] = &[("b", 3), ("c", 4)];
+        let record = record.with_extra_kvs(&extra);
+
+        let more = ("d", 5);
+        let record = record.with_extra_kvs(&more);
+
+        let kvs = record.key_values();
+
+        assert_eq!(5, kvs.count());
+        assert_eq!(Some(1), kvs.get("a".into()).and_then(|v| v.to_i64()));
+        assert_eq!(Some(2), kvs.get("b".into()).and_then(|v| v.to_i64()));
+        assert_eq!(Some(4), kvs.get("c".into()).and_then(|v| v.to_i64()));
+        assert_eq!(Some(5), kvs.get("d".into()).and_then(|v| v.to_i64()));
+        assert!(kvs.get("e".into()).is_none());
+    }
+
+    #[test]
+    fn test_log_batched() {
+        use super::{Log, Metadata, Record};
+        use std::sync::Mutex;
+
+        struct Count(Mutex<usize>);
+
+        impl Log for Count {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, _: &Record) {
+                *self.0.lock().unwrap() += 1;
+            }
+
+            fn flush(&self) {}
+        }
+
+        let a = Record::builder().build();
+        let b = Record::builder().build();
+
+        let logger = Count(Mutex::new(0));
+        (&&logger).log_batched(&[&a, &b]);
+
+        assert_eq!(2, *logger.0.lock().unwrap());
+    }
+
+    #[test]
+    fn test_reference_loggers() {
+        use super::{Log, Metadata, NopLogger};
+
+        let metadata = Metadata::builder().build();
+        assert!(!NopLogger.enabled(&metadata));
+
+        #[cfg(feature = "std")]
+        assert!(super::StderrLogger.enabled(&metadata));
+    }
+
     // Test that the `impl Log for Foo` blocks work
     // This test mostly operates on a type level, so failures will be compile errors
     #[test]
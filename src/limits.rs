@@ -0,0 +1,213 @@
+//! Limits on the size of records created by the logging macros.
+
+use std::fmt;
+
+use crate::{AtomicUsize, Ordering};
+
+// `usize::MAX` means there's no limit
+static MAX_MSG_LEN: AtomicUsize = AtomicUsize::new(usize::MAX);
+static MAX_KVS: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+// Whether either limit is set, so records don't check each of them
+static LIMITED: AtomicUsize = AtomicUsize::new(0);
+
+/// Limits on the size of records created by the logging macros.
+///
+/// Limits guard sinks against records that are unexpectedly large, like a
+/// message that formats a whole request body. Records that go over a limit are
+/// truncated rather than dropped. Truncated records carry a
+/// [`log.truncated`] key-value set to `true` when the `kv` feature is enabled,
+/// so sinks can tell them apart.
+///
+/// Limits are set globally with [`set_limits`]. There are no limits by
+/// default.
+///
+/// [`log.truncated`]: kv/keys/constant.TRUNCATED.html
+/// [`set_limits`]: fn.set_limits.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// The maximum length of a formatted message, in bytes.
+    ///
+    /// Longer messages are cut at the last character boundary before the
+    /// limit.
+    pub max_msg_len: Option<usize>,
+    /// The maximum number of key-values passed to a logging macro.
+    ///
+    /// Any more are dropped, starting with the last. Key-values added by
+    /// `log` itself, like [`log.backtrace`], don't count towards the limit.
+    ///
+    /// [`log.backtrace`]: kv/keys/constant.BACKTRACE.html
+    pub max_kvs: Option<usize>,
+}
+
+/// Set the limits on the size of records created by the logging macros.
+///
+/// Limits only apply to records created by the logging macros. Records built
+/// manually with [`RecordBuilder`] are passed to the logger as they are.
+///
+/// # Examples
+///
+/// ```
+/// use log::Limits;
+///
+/// log::set_limits(Limits {
+///     max_msg_len: Some(4096),
+///     ..Limits::default()
+/// });
+///
+/// assert_eq!(Some(4096), log::limits().max_msg_len);
+/// assert_eq!(None, log::limits().max_kvs);
+/// ```
+///
+/// [`RecordBuilder`]: struct.RecordBuilder.html
+pub fn set_limits(limits: Limits) {
+    MAX_MSG_LEN.store(limits.max_msg_len.unwrap_or(usize::MAX), Ordering::Relaxed);
+    MAX_KVS.store(limits.max_kvs.unwrap_or(usize::MAX), Ordering::Relaxed);
+
+    let limited = limits.max_msg_len.is_some() || limits.max_kvs.is_some();
+    LIMITED.store(limited as usize, Ordering::Relaxed);
+}
+
+/// Get the limits on the size of records created by the logging macros.
+///
+/// This returns the limits last passed to [`set_limits`], or no limits if it
+/// hasn't been called.
+///
+/// [`set_limits`]: fn.set_limits.html
+pub fn limits() -> Limits {
+    fn limit(max: usize) -> Option<usize> {
+        Some(max).filter(|max| *max != usize::MAX)
+    }
+
+    Limits {
+        max_msg_len: limit(MAX_MSG_LEN.load(Ordering::Relaxed)),
+        max_kvs: limit(MAX_KVS.load(Ordering::Relaxed)),
+    }
+}
+
+/// Whether any limits are set.
+#[inline]
+pub(crate) fn is_limited() -> bool {
+    LIMITED.load(Ordering::Relaxed) != 0
+}
+
+/// The number of key-values that fit in the limit, if `len` doesn't.
+pub(crate) fn truncate_kvs(len: usize) -> Option<usize> {
+    let max = MAX_KVS.load(Ordering::Relaxed);
+    Some(max).filter(|max| len > *max)
+}
+
+/// A message cut to fit in the limit, if `args` doesn't.
+pub(crate) fn truncate_message(args: fmt::Arguments<'_>) -> Option<Truncated<'_>> {
+    let max = MAX_MSG_LEN.load(Ordering::Relaxed);
+    if max == usize::MAX {
+        return None;
+    }
+
+    let over = match args.as_str() {
+        Some(msg) => msg.len() > max,
+        // Formatting stops as soon as the message goes over the limit
+        None => fmt::write(&mut Limited::new(&mut Discard, max), args).is_err(),
+    };
+
+    if over {
+        Some(Truncated { args, max })
+    } else {
+        None
+    }
+}
+
+/// A message that's formatted up to a maximum length.
+pub(crate) struct Truncated<'a> {
+    args: fmt::Arguments<'a>,
+    max: usize,
+}
+
+impl<'a> fmt::Display for Truncated<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limited = Limited::new(f, self.max);
+
+        match fmt::write(&mut limited, self.args) {
+            // Reaching the limit isn't an error for the caller
+            Err(_) if limited.full => Ok(()),
+            res => res,
+        }
+    }
+}
+
+// Writes up to `remaining` bytes, then fails.
+struct Limited<W> {
+    inner: W,
+    remaining: usize,
+    full: bool,
+}
+
+impl<W: fmt::Write> Limited<W> {
+    fn new(inner: W, remaining: usize) -> Self {
+        Limited {
+            inner,
+            remaining,
+            full: false,
+        }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for Limited<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() <= self.remaining {
+            self.remaining -= s.len();
+            return self.inner.write_str(s);
+        }
+
+        let mut end = self.remaining;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.inner.write_str(&s[..end])?;
+        self.remaining = 0;
+        self.full = true;
+
+        Err(fmt::Error)
+    }
+}
+
+struct Discard;
+
+impl fmt::Write for Discard {
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_message() {
+        let message = Truncated {
+            args: format_args!("{}-{}", "héllo", 42),
+            max: 2,
+        };
+        assert_eq!("h", message.to_string());
+
+        let message = Truncated {
+            args: format_args!("{}-{}", "héllo", 42),
+            max: 5,
+        };
+        assert_eq!("héll", message.to_string());
+
+        let message = Truncated {
+            args: format_args!("{}-{}", "héllo", 42),
+            max: 64,
+        };
+        assert_eq!("héllo-42", message.to_string());
+
+        let message = Truncated {
+            args: format_args!("{:>8}", "abc"),
+            max: 4,
+        };
+        assert_eq!("    ", message.to_string());
+    }
+}
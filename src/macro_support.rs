@@ -0,0 +1,135 @@
+//! Support for crates that define their own logging macros.
+//!
+//! The logging macros in this crate are implemented using a private API that
+//! may change in any release. Crates that build their own macros on top of
+//! `log` should use the functions in this module instead; they follow the
+//! same semver guarantees as the rest of the public API.
+//!
+//! A macro should check [`enabled`] before doing any work to produce its
+//! message, and then call [`log()`] (or [`log_kvs`] with the `kv` feature).
+//! Calling [`location()`] from inside the macro expansion captures the source
+//! location of the macro's caller.
+//!
+//! # Examples
+//!
+//! A macro that logs an expression along with its value, and then returns the value:
+//!
+//! ```
+//! use log::Level;
+//!
+//! macro_rules! logv {
+//!     ($lvl:expr, $e:expr) => {{
+//!         let lvl = $lvl;
+//!         let value = $e;
+//!
+//!         if log::macro_support::enabled(lvl, module_path!()) {
+//!             log::macro_support::log(
+//!                 format_args!("{} = {:?}", stringify!($e), value),
+//!                 lvl,
+//!                 module_path!(),
+//!                 module_path!(),
+//!                 log::macro_support::location(),
+//!             );
+//!         }
+//!
+//!         value
+//!     }};
+//! }
+//!
+//! # fn main() {
+//! let answer = logv!(Level::Debug, 6 * 7);
+//! assert_eq!(42, answer);
+//! # }
+//! ```
+//!
+//! [`log_kvs`]: fn.log_kvs.html
+
+use std::fmt;
+use std::panic::Location;
+
+use crate::{Level, SourceLocation};
+
+// The level filters macros need to check before calling `enabled`
+pub use crate::{max_level, STATIC_MAX_LEVEL};
+
+/// Whether a record at `level` for `target` would be logged.
+///
+/// This checks [`STATIC_MAX_LEVEL`], the current [`max_level`], and finally
+/// [`Log::enabled`] on the global logger, the same way the [`log_enabled!`]
+/// macro does.
+///
+/// [`STATIC_MAX_LEVEL`]: ../constant.STATIC_MAX_LEVEL.html
+/// [`max_level`]: ../fn.max_level.html
+/// [`Log::enabled`]: ../trait.Log.html#tymethod.enabled
+/// [`log_enabled!`]: ../macro.log_enabled.html
+#[inline]
+pub fn enabled(level: Level, target: &str) -> bool {
+    level <= STATIC_MAX_LEVEL
+        && crate::__private_api::max_level_allows(level)
+        && crate::__private_api::enabled(level, target, crate::RecordKind::Log.into())
+}
+
+/// Get the source location of the caller.
+///
+/// When called in a macro expansion, this is the location the macro was invoked at.
+#[track_caller]
+#[inline]
+pub fn location() -> &'static Location<'static> {
+    Location::caller()
+}
+
+/// Log a record created by a macro.
+///
+/// Records are passed through the same pipeline as those created by the logging
+/// macros in this crate. This function doesn't check whether the record is
+/// enabled; call [`enabled`] first.
+///
+/// [`enabled`]: fn.enabled.html
+pub fn log(
+    args: fmt::Arguments,
+    level: Level,
+    target: &str,
+    module_path: &'static str,
+    location: &'static Location<'static>,
+) {
+    crate::__private_api::log(
+        args,
+        level,
+        &(target, &source_location(module_path, location)),
+        (),
+        crate::RecordKind::Log.into(),
+    )
+}
+
+/// Log a record with key-values created by a macro.
+///
+/// This is the same as [`log`], but also attaches `kvs` to the record.
+///
+/// Requires the `kv` feature.
+///
+/// [`log`]: fn.log.html
+#[cfg(feature = "kv")]
+pub fn log_kvs(
+    args: fmt::Arguments,
+    level: Level,
+    target: &str,
+    module_path: &'static str,
+    location: &'static Location<'static>,
+    kvs: &[(&str, crate::kv::Value)],
+) {
+    crate::__private_api::log(
+        args,
+        level,
+        &(target, &source_location(module_path, location)),
+        kvs,
+        crate::RecordKind::Log.into(),
+    )
+}
+
+fn source_location(
+    module_path: &'static str,
+    location: &'static Location<'static>,
+) -> SourceLocation<'static> {
+    SourceLocation::new(module_path, location.file(), location.line())
+        .with_column(location.column())
+}
@@ -27,34 +27,372 @@
 ///     data.0, data.1, private_data);
 /// # }
 /// ```
+///
+/// # Key-values without a message
+///
+/// The message can be left out when a record is described entirely by its
+/// key-values. The key-values still end with a `;`, and the record's message
+/// is empty.
+///
+/// ```
+/// use log::{info, log, Level};
+///
+/// # fn main() {
+/// log!(Level::Info, user_id = 42, action = "login";);
+/// info!(target: "audit", user_id = 42, action = "logout";);
+/// # }
+/// ```
+///
+/// # Sampling
+///
+/// A sample rate between `0.0` and `1.0` can be given before the target, so
+/// only that fraction of the messages logged at the callsite are passed to the
+/// logger. The decision is made randomly for each message, after checking the
+/// maximum log level. The rate is recorded in [`Metadata::sample_rate`] so
+/// loggers can account for the messages that were left out.
+///
+/// ```
+/// use log::{info, log, Level};
+///
+/// # fn main() {
+/// # let id = 42;
+/// log!(sample: 0.01, Level::Debug, "cache miss for {}", id);
+/// info!(sample: 0.1, target: "requests", "handled request {}", id);
+/// # }
+/// ```
+///
+/// # De-duplication keys
+///
+/// A key identifying the event a message describes can be given with `dedup:`
+/// before the target. It's recorded in [`Metadata::dedup_key`] so downstream
+/// sinks, like alerting systems, can group repeats of the same event, even
+/// across processes. The `log` crate doesn't interpret the key itself. A key
+/// can be derived from a set of values with [`DedupKey`].
+///
+/// ```
+/// use log::{log, warn, Level};
+///
+/// # fn main() {
+/// # let device = "/dev/sda1";
+/// warn!(dedup: "disk_full:/dev/sda1", "disk {} is full", device);
+/// log!(dedup: "disk_full:/dev/sda1", target: "disks", Level::Error, "disk {} is full", device);
+/// # }
+/// ```
+///
+/// The `sample:`, `kind:`, `v:`, `dedup:`, and `id:` options can't be
+/// combined, only one of them can be given to each call. Metadata that needs
+/// more than one of them can be built with [`Metadata::builder`] and logged
+/// with `log!(metadata: ...)`.
+///
+/// # Message ids
+///
+/// A stable id for the message can be given with `id:` before the target. It's
+/// recorded in [`Metadata::msg_id`], so internationalized applications can
+/// look up a translation of the message, and monitoring dashboards can key on
+/// it, without depending on the message text. The id must be a
+/// `&'static str`. A key-value that happens to be named `id` can still be
+/// given first, like `info!(id:display, user; "logged in")`.
+///
+/// ```
+/// use log::{info, log, Level};
+///
+/// # fn main() {
+/// # let name = "chashu";
+/// info!(id: "user.login.success", "user {} logged in", name);
+/// log!(id: "user.login.failure", target: "auth", Level::Warn, "user {} failed to log in", name);
+/// # }
+/// ```
+///
+/// # Constant levels
+///
+/// A level that's known at compile time can be marked with `const`. It's then
+/// checked against [`STATIC_MAX_LEVEL`] during compilation, so a message at a
+/// level that's statically disabled costs nothing, just like with [`debug!`]
+/// and the other level macros. This is useful for helper macros that take the
+/// level as an argument. The level must be a constant expression.
+///
+/// ```
+/// use log::{log, Level};
+///
+/// macro_rules! log_timing {
+///     ($lvl:expr, $name:expr, $millis:expr) => {
+///         log!(target: "timing", const $lvl, "{} took {}ms", $name, $millis)
+///     };
+/// }
+///
+/// # fn main() {
+/// log_timing!(Level::Trace, "render", 12);
+/// # }
+/// ```
+///
+/// # Constant functions
+///
+/// With the `const_noop` feature, a message can be logged in a `const fn` by
+/// starting with `const fn;`. The level macros accept it too. The message and
+/// its arguments are still type checked, but nothing is ever logged, even when
+/// the function is called at runtime. This lets code that's sometimes evaluated
+/// at compile time keep its logging statements.
+///
+/// ```
+/// # #[cfg(feature = "const_noop")]
+/// # mod example {
+/// use log::{debug, log, Level};
+///
+/// const fn checked_div(a: u32, b: u32) -> Option<u32> {
+///     if b == 0 {
+///         log!(const fn; Level::Warn, "attempted to divide {} by zero", a);
+///         return None;
+///     }
+///
+///     debug!(const fn; "dividing {} by {}", a, b);
+///     Some(a / b)
+/// }
+///
+/// const HALF: Option<u32> = checked_div(42, 2);
+/// # }
+/// # fn main() {}
+/// ```
+///
+/// # Callsite
+///
+/// Crates that wrap these macros in their own can forward the module path,
+/// file, and line of their caller, so records point at the code that invoked
+/// the wrapper rather than the wrapper itself. They're given together before
+/// any other arguments. Unless a target is also given, the module path is used
+/// as the target too.
+///
+/// ```
+/// macro_rules! my_info {
+///     ($($arg:tt)+) => {
+///         log::info!(
+///             module_path: module_path!(),
+///             file: file!(),
+///             line: line!(),
+///             $($arg)+
+///         )
+///     };
+/// }
+///
+/// # fn main() {
+/// my_info!("logged from {}", module_path!());
+/// # }
+/// ```
+///
+/// # Records and metadata
+///
+/// Tools that forward records from elsewhere, like bridges and replay tools,
+/// can pass a pre-built [`Record`] with `record:`, or give their own [`Metadata`]
+/// with `metadata:` followed by a message. Either way, the record goes through
+/// the same maximum level checks as any other message. A record given with
+/// `record:` is passed to [`log_record`], so it's also checked with
+/// [`Log::enabled`].
+///
+/// ```
+/// use log::{log, Level, Metadata, Record};
+///
+/// # fn main() {
+/// let metadata = Metadata::builder()
+///     .level(Level::Warn)
+///     .target("child_process")
+///     .build();
+///
+/// log!(metadata: metadata, "exited with code {}", 1);
+///
+/// log!(record: Record::builder()
+///     .args(format_args!("exited with code {}", 1))
+///     .metadata(metadata)
+///     .build());
+/// # }
+/// ```
+///
+/// [`Metadata::sample_rate`]: struct.Metadata.html#method.sample_rate
+/// [`Metadata::dedup_key`]: struct.Metadata.html#method.dedup_key
+/// [`Metadata::builder`]: struct.Metadata.html#method.builder
+/// [`Metadata::msg_id`]: struct.Metadata.html#method.msg_id
+/// [`DedupKey`]: struct.DedupKey.html
+/// [`STATIC_MAX_LEVEL`]: constant.STATIC_MAX_LEVEL.html
+/// [`debug!`]: macro.debug.html
+/// [`Record`]: struct.Record.html
+/// [`Metadata`]: struct.Metadata.html
+/// [`log_record`]: fn.log_record.html
+/// [`Log::enabled`]: trait.Log.html#tymethod.enabled
 #[macro_export]
 macro_rules! log {
-    // log!(target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
-    (target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+; $($arg:tt)+) => ({
+    // log!(const fn; Level::Info, "a log event")
+    (const fn; $($arg:tt)+) => ($crate::__log_const_noop!($crate::log!($($arg)+)));
+
+    // log!(@location: loc, sample: 0.01, target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    (@location: $loc:expr, sample: $rate:expr, target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+; $($arg:tt)+) => ({
         let lvl = $lvl;
-        if lvl <= $crate::STATIC_MAX_LEVEL && lvl <= $crate::max_level() {
-            $crate::__private_api::log::<&_>(
+        if lvl <= $crate::STATIC_MAX_LEVEL
+            && (lvl <= $crate::max_level_relaxed()
+                || ($crate::__private_api::thread_max_level_used()
+                    && $crate::__private_api::thread_max_level_allows(lvl)))
+        {
+            static SAMPLER: $crate::__private_api::Sampler = $crate::__private_api::Sampler::new();
+            $crate::__log_check_keys!($($key),+);
+            $crate::__private_api::log_sampled::<&_, _>(
                 $crate::__private_api::format_args!($($arg)+),
                 lvl,
-                &($target, $crate::__private_api::module_path!(), $crate::__private_api::loc()),
-                &[$(($crate::__log_key!($key), $crate::__log_value!($key $(:$capture)* = $($value)*))),+]
+                &($target, $loc),
+                $crate::__log_kvs!($($key $(:$capture)* $(= $value)*),+),
+                $crate::__private_api::Kind::from($crate::RecordKind::Log),
+                &SAMPLER,
+                $rate,
             );
         }
     });
 
-    // log!(target: "my_target", Level::Info, "a {} event", "log");
-    (target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
+    // log!(@location: loc, sample: 0.01, target: "my_target", Level::Info, key1:? = 42, key2 = true;);
+    (@location: $loc:expr, sample: $rate:expr, target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+;) => ($crate::log!(@location: $loc, sample: $rate, target: $target, $lvl, $($key $(:$capture)* $(= $value)*),+; ""));
+
+    // log!(@location: loc, sample: 0.01, target: "my_target", Level::Info, "a {} event", "log");
+    (@location: $loc:expr, sample: $rate:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
         let lvl = $lvl;
-        if lvl <= $crate::STATIC_MAX_LEVEL && lvl <= $crate::max_level() {
+        if lvl <= $crate::STATIC_MAX_LEVEL
+            && (lvl <= $crate::max_level_relaxed()
+                || ($crate::__private_api::thread_max_level_used()
+                    && $crate::__private_api::thread_max_level_allows(lvl)))
+        {
+            static SAMPLER: $crate::__private_api::Sampler = $crate::__private_api::Sampler::new();
+            $crate::__private_api::log_sampled(
+                $crate::__private_api::format_args!($($arg)+),
+                lvl,
+                &($target, $loc),
+                (),
+                $crate::__private_api::Kind::from($crate::RecordKind::Log),
+                &SAMPLER,
+                $rate,
+            );
+        }
+    });
+
+    // log!(@location: loc, sample: 0.01, Level::Info, "a log event")
+    (@location: $loc:expr, sample: $rate:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $loc, sample: $rate, target: $loc.module_path(), $lvl, $($arg)+));
+
+    // log!(@location: loc, kind: RecordKind::Event, target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    (@location: $loc:expr, kind: $kind:expr, target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+; $($arg:tt)+) => ({
+        let lvl = $lvl;
+        if lvl <= $crate::STATIC_MAX_LEVEL
+            && (lvl <= $crate::max_level_relaxed()
+                || ($crate::__private_api::thread_max_level_used()
+                    && $crate::__private_api::thread_max_level_allows(lvl)))
+        {
+            $crate::__log_check_keys!($($key),+);
+            $crate::__private_api::log::<&_, _>(
+                $crate::__private_api::format_args!($($arg)+),
+                lvl,
+                &($target, $loc),
+                $crate::__log_kvs!($($key $(:$capture)* $(= $value)*),+),
+                $crate::__private_api::Kind::from($kind),
+            );
+        }
+    });
+
+    // log!(@location: loc, kind: RecordKind::Event, target: "my_target", Level::Info, key1:? = 42, key2 = true;);
+    (@location: $loc:expr, kind: $kind:expr, target: $target:expr, $lvl:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+;) => ($crate::log!(@location: $loc, kind: $kind, target: $target, $lvl, $($key $(:$capture)* $(= $value)*),+; ""));
+
+    // log!(@location: loc, kind: RecordKind::Event, target: "my_target", Level::Info, "a {} event", "log");
+    (@location: $loc:expr, kind: $kind:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ({
+        let lvl = $lvl;
+        if lvl <= $crate::STATIC_MAX_LEVEL
+            && (lvl <= $crate::max_level_relaxed()
+                || ($crate::__private_api::thread_max_level_used()
+                    && $crate::__private_api::thread_max_level_allows(lvl)))
+        {
             $crate::__private_api::log(
                 $crate::__private_api::format_args!($($arg)+),
                 lvl,
-                &($target, $crate::__private_api::module_path!(), $crate::__private_api::loc()),
+                &($target, $loc),
                 (),
+                $crate::__private_api::Kind::from($kind),
+            );
+        }
+    });
+
+    // log!(@location: loc, kind: RecordKind::Event, Level::Info, "a log event")
+    (@location: $loc:expr, kind: $kind:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $loc, kind: $kind, target: $loc.module_path(), $lvl, $($arg)+));
+
+    // log!(@location: loc, target: "my_target", Level::Info, "a log event")
+    (@location: $loc:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $loc, kind: $crate::RecordKind::Log, target: $target, $lvl, $($arg)+));
+
+    // log!(@location: loc, Level::Info, "a log event")
+    (@location: $loc:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $loc, target: $loc.module_path(), $lvl, $($arg)+));
+
+    // log!(target: "my_target", const Level::Info, "a log event")
+    ($(module_path: $module_path:expr, file: $file:expr, line: $line:expr,)? $(sample: $rate:expr,)? $(kind: $kind:expr,)? $(v: $v:expr,)? $(dedup: $dedup:expr,)? $(id: $id:expr,)? $(target: $target:expr,)? const $lvl:expr, $($arg:tt)+) => ({
+        if $crate::__private_api::StaticLevel::<{ $lvl as usize }>::ENABLED {
+            $crate::log!(
+                $(module_path: $module_path, file: $file, line: $line,)?
+                $(sample: $rate,)?
+                $(kind: $kind,)?
+                $(v: $v,)?
+                $(dedup: $dedup,)?
+                $(id: $id,)?
+                $(target: $target,)?
+                $lvl,
+                $($arg)+
+            );
+        }
+    });
+
+    // log!(record: Record::builder().build())
+    (record: $record:expr) => ({
+        $crate::log_record(&$record)
+    });
+
+    // log!(metadata: Metadata::builder().build(), "a {} event", "log")
+    (metadata: $metadata:expr, $($arg:tt)+) => ({
+        let metadata: &$crate::Metadata = &$metadata;
+        if $crate::__private_api::metadata_allows(metadata) {
+            $crate::__private_api::log_metadata(
+                $crate::__private_api::format_args!($($arg)+),
+                metadata,
+                $crate::__log_location!(),
             );
         }
     });
 
+    // log!(module_path: module_path!(), file: file!(), line: line!(), Level::Info, "a log event")
+    (module_path: $module_path:expr, file: $file:expr, line: $line:expr, $($arg:tt)+) => ({
+        let location = $crate::SourceLocation::new($module_path, $file, $line);
+        $crate::log!(@location: &location, $($arg)+)
+    });
+
+    // log!(sample: 0.01, target: "my_target", Level::Info, "a {} event", "log");
+    (sample: $rate:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $crate::__log_location!(), sample: $rate, target: $target, $lvl, $($arg)+));
+
+    // log!(sample: 0.01, Level::Info, "a log event")
+    (sample: $rate:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(sample: $rate, target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+
+    // log!(v: 3, target: "my_target", Level::Trace, "a {} event", "log");
+    (v: $v:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $crate::__log_location!(), kind: ($crate::RecordKind::Log, $v), target: $target, $lvl, $($arg)+));
+
+    // log!(v: 3, Level::Trace, "a log event")
+    (v: $v:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(v: $v, target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+
+    // log!(dedup: "my_key", target: "my_target", Level::Info, "a {} event", "log");
+    (dedup: $key:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $crate::__log_location!(), kind: ($crate::RecordKind::Log, $crate::__private_api::DedupKey($key)), target: $target, $lvl, $($arg)+));
+
+    // log!(dedup: "my_key", Level::Info, "a log event")
+    (dedup: $key:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(dedup: $key, target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+
+    // log!(id: "my_id", target: "my_target", Level::Info, "a {} event", "log");
+    (id: $id:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $crate::__log_location!(), kind: ($crate::RecordKind::Log, $crate::__private_api::MsgId($id)), target: $target, $lvl, $($arg)+));
+
+    // log!(id: "my_id", Level::Info, "a log event")
+    (id: $id:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(id: $id, target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+
+    // log!(kind: RecordKind::Event, target: "my_target", Level::Info, "a {} event", "log");
+    (kind: $kind:expr, target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(@location: $crate::__log_location!(), kind: $kind, target: $target, $lvl, $($arg)+));
+
+    // log!(kind: RecordKind::Event, Level::Info, "a log event")
+    (kind: $kind:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(kind: $kind, target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
+
+    // log!(target: "my_target", Level::Info, key1:? = 42, key2 = true; "a {} event", "log");
+    // log!(target: "my_target", Level::Info, "a {} event", "log");
+    (target: $target:expr, $lvl:expr, $($arg:tt)+) => ($crate::log!(kind: $crate::RecordKind::Log, target: $target, $lvl, $($arg)+));
+
     // log!(Level::Info, "a log event")
     ($lvl:expr, $($arg:tt)+) => ($crate::log!(target: $crate::__private_api::module_path!(), $lvl, $($arg)+));
 }
@@ -77,10 +415,8 @@ macro_rules! log {
 macro_rules! error {
     // error!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
     // error!(target: "my_target", "a {} event", "log")
-    (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Error, $($arg)+));
-
     // error!("a {} event", "log")
-    ($($arg:tt)+) => ($crate::log!($crate::Level::Error, $($arg)+))
+    ($($arg:tt)+) => ($crate::__log_level!(Error, $($arg)+))
 }
 
 /// Logs a message at the warn level.
@@ -101,10 +437,8 @@ macro_rules! error {
 macro_rules! warn {
     // warn!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
     // warn!(target: "my_target", "a {} event", "log")
-    (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Warn, $($arg)+));
-
     // warn!("a {} event", "log")
-    ($($arg:tt)+) => ($crate::log!($crate::Level::Warn, $($arg)+))
+    ($($arg:tt)+) => ($crate::__log_level!(Warn, $($arg)+))
 }
 
 /// Logs a message at the info level.
@@ -127,10 +461,8 @@ macro_rules! warn {
 macro_rules! info {
     // info!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
     // info!(target: "my_target", "a {} event", "log")
-    (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Info, $($arg)+));
-
     // info!("a {} event", "log")
-    ($($arg:tt)+) => ($crate::log!($crate::Level::Info, $($arg)+))
+    ($($arg:tt)+) => ($crate::__log_level!(Info, $($arg)+))
 }
 
 /// Logs a message at the debug level.
@@ -152,10 +484,8 @@ macro_rules! info {
 macro_rules! debug {
     // debug!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
     // debug!(target: "my_target", "a {} event", "log")
-    (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Debug, $($arg)+));
-
     // debug!("a {} event", "log")
-    ($($arg:tt)+) => ($crate::log!($crate::Level::Debug, $($arg)+))
+    ($($arg:tt)+) => ($crate::__log_level!(Debug, $($arg)+))
 }
 
 /// Logs a message at the trace level.
@@ -175,14 +505,355 @@ macro_rules! debug {
 ///        if pos.y >= 0.0 { "positive" } else { "negative" });
 /// # }
 /// ```
+///
+/// A verbosity can be given first, to split trace messages into finer levels.
+/// It's recorded in [`Metadata::verbosity`] for loggers to filter on:
+///
+/// ```
+/// use log::trace;
+///
+/// # fn main() {
+/// # let (id, bytes) = (1, [0u8; 4]);
+/// trace!(v: 1, "received packet {}", id);
+/// trace!(v: 3, target: "net", "packet {} bytes: {:?}", id, bytes);
+/// # }
+/// ```
+///
+/// [`Metadata::verbosity`]: struct.Metadata.html#method.verbosity
 #[macro_export]
 macro_rules! trace {
     // trace!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
     // trace!(target: "my_target", "a {} event", "log")
-    (target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, $crate::Level::Trace, $($arg)+));
-
     // trace!("a {} event", "log")
-    ($($arg:tt)+) => ($crate::log!($crate::Level::Trace, $($arg)+))
+    ($($arg:tt)+) => ($crate::__log_level!(Trace, $($arg)+))
+}
+
+/// Logs an audit record.
+///
+/// Audit records are for security-sensitive events, like logins or permission
+/// changes, that must not be sampled or dropped. They're logged at the info
+/// level, but aren't subject to the maximum log level. Loggers can recognize
+/// them with [`Metadata::is_audit`], and get their event name with
+/// [`Metadata::audit_name`].
+///
+/// An audit record requires a `name` identifying the kind of event, and at
+/// least one key-value pair. The key-values use the same syntax as the other
+/// logging macros.
+///
+/// Audit records are never de-duplicated.
+///
+/// Requires the `kv` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::audit;
+///
+/// # fn main() {
+/// let user = "ferris";
+///
+/// audit!(name: "user.login", user, outcome = "success"; "user logged in");
+/// audit!(target: "security", name: "user.logout", user; "user logged out");
+/// # }
+/// ```
+///
+/// [`Metadata::is_audit`]: struct.Metadata.html#method.is_audit
+/// [`Metadata::audit_name`]: struct.Metadata.html#method.audit_name
+#[macro_export]
+#[cfg(feature = "kv")]
+macro_rules! audit {
+    // audit!(target: "my_target", name: "my_event", key1 = 42, key2 = true; "a {} event", "log")
+    (target: $target:expr, name: $name:expr, $($key:tt $(:$capture:tt)? $(= $value:expr)?),+; $($arg:tt)+) => ({
+        $crate::__log_check_keys!($($key),+);
+        $crate::__private_api::audit(
+            $crate::__private_api::format_args!($($arg)+),
+            &($target, $crate::__log_location!()),
+            $name,
+            $crate::__log_kvs!($($key $(:$capture)* $(= $value)*),+),
+        );
+    });
+
+    // audit!(name: "my_event", key1 = 42, key2 = true; "a {} event", "log")
+    (name: $name:expr, $($arg:tt)+) => ($crate::audit!(target: $crate::__private_api::module_path!(), name: $name, $($arg)+));
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! audit {
+    ($($arg:tt)+) => {
+        compile_error!("logging audit records requires the `kv` feature of `log`")
+    };
+}
+
+/// Logs the error in a `Result`, and then returns the `Result` unchanged.
+///
+/// If the `Result` is an `Err`, a message is logged at the error level with the
+/// error captured as the [`keys::ERR`] key-value, so loggers can inspect it and its
+/// sources. The message defaults to the expression that produced the `Result`,
+/// followed by "failed", but a `format!` based message can be given instead.
+///
+/// The error type must implement `std::error::Error + 'static`, or be a boxed
+/// `dyn std::error::Error`, like `Box<dyn std::error::Error + Send + Sync>`.
+///
+/// Requires the `kv_std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::log_err;
+/// use std::fs::File;
+///
+/// # fn main() {
+/// let path = "config.toml";
+///
+/// // Logs "File::open(path) failed" if the file can't be opened
+/// let file = log_err!(File::open(path));
+///
+/// // Logs "couldn't open config.toml" if the file can't be opened
+/// let file = log_err!(target: "config", File::open(path), "couldn't open {}", path);
+/// # let _ = file;
+/// # }
+/// ```
+///
+/// [`keys::ERR`]: kv/keys/constant.ERR.html
+#[macro_export]
+#[cfg(feature = "kv_std")]
+macro_rules! log_err {
+    // log_err!(target: "my_target", result, "a {} event", "log")
+    (target: $target:expr, $result:expr, $($arg:tt)+) => ({
+        let result = $result;
+        if let $crate::__private_api::Err(ref err) = result {
+            use $crate::__private_api::AsDynError as _;
+            let err = (&$crate::__private_api::ErrRef(err)).as_dyn_error();
+            $crate::error!(target: $target, error:err = *err; $($arg)+);
+        }
+        result
+    });
+
+    // log_err!(target: "my_target", result)
+    (target: $target:expr, $result:expr $(,)?) => (
+        $crate::log_err!(target: $target, $result, "{} failed", $crate::__private_api::stringify!($result))
+    );
+
+    // log_err!(result, "a {} event", "log")
+    ($result:expr, $($arg:tt)+) => ($crate::log_err!(target: $crate::__private_api::module_path!(), $result, $($arg)+));
+
+    // log_err!(result)
+    ($result:expr $(,)?) => ($crate::log_err!(target: $crate::__private_api::module_path!(), $result));
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv_std"))]
+macro_rules! log_err {
+    ($($arg:tt)+) => {
+        compile_error!("logging errors with `log_err!` requires the `kv_std` feature of `log`")
+    };
+}
+
+/// Logs an expression and its value, and then returns the value.
+///
+/// This is like the standard library's `dbg!` macro, but logs through `log`
+/// at the given level instead of printing to stderr. The message defaults to
+/// the expression's source text and its `Debug` value, like `x * 2 = 84`. A
+/// custom `format!` string can be given before the expression; it's passed the
+/// source text and the value as its two arguments.
+///
+/// The expression is always evaluated, even if the level is disabled. It's
+/// moved into the macro and then returned, so pass a reference to log a value
+/// that shouldn't be moved.
+///
+/// See [`errorv!`], [`warnv!`], [`infov!`], [`debugv!`], and [`tracev!`] for
+/// macros that log at a fixed level.
+///
+/// # Examples
+///
+/// ```
+/// use log::{logv, Level};
+///
+/// # fn main() {
+/// let x = 42;
+///
+/// // Logs "x * 2 = 84"
+/// let doubled = logv!(Level::Debug, x * 2);
+/// assert_eq!(84, doubled);
+///
+/// // Logs "x is 0x2a"
+/// logv!(target: "app_events", Level::Info, "{} is {:#x}", x);
+/// # }
+/// ```
+///
+/// [`errorv!`]: macro.errorv.html
+/// [`warnv!`]: macro.warnv.html
+/// [`infov!`]: macro.infov.html
+/// [`debugv!`]: macro.debugv.html
+/// [`tracev!`]: macro.tracev.html
+#[macro_export]
+macro_rules! logv {
+    // logv!(target: "my_target", Level::Info, "{} is {:?}", expr)
+    (target: $target:expr, $lvl:expr, $fmt:literal, $e:expr $(,)?) => {
+        match $e {
+            value => {
+                $crate::log!(target: $target, $lvl, $fmt, $crate::__private_api::stringify!($e), &value);
+                value
+            }
+        }
+    };
+
+    // logv!(target: "my_target", Level::Info, expr)
+    (target: $target:expr, $lvl:expr, $e:expr $(,)?) => ($crate::logv!(target: $target, $lvl, "{} = {:?}", $e));
+
+    // logv!(Level::Info, "{} is {:?}", expr)
+    ($lvl:expr, $fmt:literal, $e:expr $(,)?) => ($crate::logv!(target: $crate::__private_api::module_path!(), $lvl, $fmt, $e));
+
+    // logv!(Level::Info, expr)
+    ($lvl:expr, $e:expr $(,)?) => ($crate::logv!(target: $crate::__private_api::module_path!(), $lvl, $e));
+}
+
+/// Logs an expression and its value at the error level, and then returns the value.
+///
+/// See [`logv!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use log::errorv;
+///
+/// # fn main() {
+/// let attempts = 3;
+///
+/// let delay = errorv!(attempts * 100);
+/// errorv!(target: "app_events", "{} is {:?}ms", delay);
+/// # }
+/// ```
+///
+/// [`logv!`]: macro.logv.html
+#[macro_export]
+macro_rules! errorv {
+    // errorv!(target: "my_target", "{} is {:?}", expr)
+    // errorv!(target: "my_target", expr)
+    (target: $target:expr, $($arg:tt)+) => ($crate::logv!(target: $target, $crate::Level::Error, $($arg)+));
+
+    // errorv!("{} is {:?}", expr)
+    // errorv!(expr)
+    ($($arg:tt)+) => ($crate::logv!($crate::Level::Error, $($arg)+));
+}
+
+/// Logs an expression and its value at the warn level, and then returns the value.
+///
+/// See [`logv!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use log::warnv;
+///
+/// # fn main() {
+/// let attempts = 3;
+///
+/// let delay = warnv!(attempts * 100);
+/// warnv!(target: "app_events", "{} is {:?}ms", delay);
+/// # }
+/// ```
+///
+/// [`logv!`]: macro.logv.html
+#[macro_export]
+macro_rules! warnv {
+    // warnv!(target: "my_target", "{} is {:?}", expr)
+    // warnv!(target: "my_target", expr)
+    (target: $target:expr, $($arg:tt)+) => ($crate::logv!(target: $target, $crate::Level::Warn, $($arg)+));
+
+    // warnv!("{} is {:?}", expr)
+    // warnv!(expr)
+    ($($arg:tt)+) => ($crate::logv!($crate::Level::Warn, $($arg)+));
+}
+
+/// Logs an expression and its value at the info level, and then returns the value.
+///
+/// See [`logv!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use log::infov;
+///
+/// # fn main() {
+/// let attempts = 3;
+///
+/// let delay = infov!(attempts * 100);
+/// infov!(target: "app_events", "{} is {:?}ms", delay);
+/// # }
+/// ```
+///
+/// [`logv!`]: macro.logv.html
+#[macro_export]
+macro_rules! infov {
+    // infov!(target: "my_target", "{} is {:?}", expr)
+    // infov!(target: "my_target", expr)
+    (target: $target:expr, $($arg:tt)+) => ($crate::logv!(target: $target, $crate::Level::Info, $($arg)+));
+
+    // infov!("{} is {:?}", expr)
+    // infov!(expr)
+    ($($arg:tt)+) => ($crate::logv!($crate::Level::Info, $($arg)+));
+}
+
+/// Logs an expression and its value at the debug level, and then returns the value.
+///
+/// See [`logv!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use log::debugv;
+///
+/// # fn main() {
+/// let attempts = 3;
+///
+/// let delay = debugv!(attempts * 100);
+/// debugv!(target: "app_events", "{} is {:?}ms", delay);
+/// # }
+/// ```
+///
+/// [`logv!`]: macro.logv.html
+#[macro_export]
+macro_rules! debugv {
+    // debugv!(target: "my_target", "{} is {:?}", expr)
+    // debugv!(target: "my_target", expr)
+    (target: $target:expr, $($arg:tt)+) => ($crate::logv!(target: $target, $crate::Level::Debug, $($arg)+));
+
+    // debugv!("{} is {:?}", expr)
+    // debugv!(expr)
+    ($($arg:tt)+) => ($crate::logv!($crate::Level::Debug, $($arg)+));
+}
+
+/// Logs an expression and its value at the trace level, and then returns the value.
+///
+/// See [`logv!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use log::tracev;
+///
+/// # fn main() {
+/// let attempts = 3;
+///
+/// let delay = tracev!(attempts * 100);
+/// tracev!(target: "app_events", "{} is {:?}ms", delay);
+/// # }
+/// ```
+///
+/// [`logv!`]: macro.logv.html
+#[macro_export]
+macro_rules! tracev {
+    // tracev!(target: "my_target", "{} is {:?}", expr)
+    // tracev!(target: "my_target", expr)
+    (target: $target:expr, $($arg:tt)+) => ($crate::logv!(target: $target, $crate::Level::Trace, $($arg)+));
+
+    // tracev!("{} is {:?}", expr)
+    // tracev!(expr)
+    ($($arg:tt)+) => ($crate::logv!($crate::Level::Trace, $($arg)+));
 }
 
 /// Determines if a message logged at the specified level in that module will
@@ -211,25 +882,276 @@ macro_rules! trace {
 /// # fn expensive_call() -> Data { Data { x: 0, y: 0 } }
 /// # fn main() {}
 /// ```
+///
+/// A [`RecordKind`] can be given before the target, like in the logging
+/// macros, so loggers that filter on the kind make the same decision for the
+/// check as for the message it guards:
+///
+/// ```
+/// use log::Level::Info;
+/// use log::{info, log_enabled, RecordKind};
+///
+/// # fn main() {
+/// if log_enabled!(kind: RecordKind::Progress, Info) {
+///     info!(kind: RecordKind::Progress, "downloaded {}%", 42);
+/// }
+/// # }
+/// ```
+///
+/// A verbosity can be given with `v:` the same way, for checks that guard
+/// messages logged with one:
+///
+/// ```
+/// use log::Level::Trace;
+/// use log::{log_enabled, trace};
+///
+/// # fn main() {
+/// # let bytes = [0u8; 4];
+/// if log_enabled!(v: 3, Trace) {
+///     trace!(v: 3, "packet bytes: {:?}", bytes);
+/// }
+/// # }
+/// ```
+///
+/// Key-values aren't part of a record's [`Metadata`], so they can't affect
+/// whether it's enabled and aren't accepted.
+///
+/// [`RecordKind`]: enum.RecordKind.html
+/// [`Metadata`]: struct.Metadata.html
 #[macro_export]
 macro_rules! log_enabled {
-    (target: $target:expr, $lvl:expr) => {{
+    // log_enabled!(kind: RecordKind::Event, target: "my_target", Level::Info)
+    (kind: $kind:expr, target: $target:expr, $lvl:expr) => {{
         let lvl = $lvl;
         lvl <= $crate::STATIC_MAX_LEVEL
-            && lvl <= $crate::max_level()
-            && $crate::__private_api::enabled(lvl, $target)
+            && $crate::__private_api::max_level_allows(lvl)
+            && $crate::__private_api::enabled(lvl, $target, $crate::__private_api::Kind::from($kind))
     }};
+
+    // log_enabled!(kind: RecordKind::Event, Level::Info)
+    (kind: $kind:expr, $lvl:expr) => {
+        $crate::log_enabled!(kind: $kind, target: $crate::__private_api::module_path!(), $lvl)
+    };
+
+    // log_enabled!(v: 3, target: "my_target", Level::Trace)
+    (v: $v:expr, target: $target:expr, $lvl:expr) => {
+        $crate::log_enabled!(kind: ($crate::RecordKind::Log, $v), target: $target, $lvl)
+    };
+
+    // log_enabled!(v: 3, Level::Trace)
+    (v: $v:expr, $lvl:expr) => {
+        $crate::log_enabled!(v: $v, target: $crate::__private_api::module_path!(), $lvl)
+    };
+
+    // log_enabled!(target: "my_target", Level::Info)
+    (target: $target:expr, $lvl:expr) => {
+        $crate::log_enabled!(kind: $crate::RecordKind::Log, target: $target, $lvl)
+    };
+
+    // log_enabled!(Level::Info)
     ($lvl:expr) => {
         $crate::log_enabled!(target: $crate::__private_api::module_path!(), $lvl)
     };
 }
 
+/// Get the source location the macro is invoked at.
+///
+/// This evaluates to a `&'static std::panic::Location<'static>`, which can be
+/// passed to [`RecordBuilder::location`](struct.RecordBuilder.html#method.location).
+///
+/// # Examples
+///
+/// ```
+/// use log::{location, Record};
+///
+/// let location = location!();
+///
+/// let record = Record::builder()
+///     .args(format_args!("Error!"))
+///     .location(location)
+///     .build();
+///
+/// assert_eq!(Some(location.file()), record.file());
+/// assert_eq!(Some(location.line()), record.line());
+/// ```
+#[macro_export]
+macro_rules! location {
+    () => {
+        $crate::__private_api::loc()
+    };
+}
+
+// The source location of a callsite, as a `&'static SourceLocation`.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "no_location"))]
+macro_rules! __log_location {
+    () => {{
+        static LOCATION: $crate::SourceLocation<'static> = $crate::SourceLocation::new(
+            $crate::__private_api::module_path!(),
+            $crate::__private_api::file!(),
+            $crate::__private_api::line!(),
+        )
+        .with_column($crate::__private_api::column!());
+
+        &LOCATION
+    }};
+}
+
+// The location is discarded, so a shared empty one is used in place of a
+// static per callsite.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "no_location")]
+macro_rules! __log_location {
+    () => {
+        &$crate::__private_api::NO_LOCATION
+    };
+}
+
+// The arguments of a level macro, like `info!`, with its level. Each level
+// macro forwards everything here, so their options are only defined once.
+//
+// A key-value captured with a sigil, like `id:display`, looks like an option
+// whose value starts with an identifier, so the identifier is checked against
+// every sigil first. `v:` is only accepted by `trace!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_level {
+    // info!(const fn; "a {} event", "log")
+    ($lvl:ident, const fn; $($arg:tt)+) => ($crate::__log_const_noop!($crate::__log_level!($lvl, $($arg)+)));
+
+    // info!(id:display, key2 = true; "a {} event", "log")
+    ($lvl:ident, $key:ident :value $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:value $($arg)*));
+    ($lvl:ident, $key:ident :display $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:display $($arg)*));
+    ($lvl:ident, $key:ident :debug $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:debug $($arg)*));
+    ($lvl:ident, $key:ident :err $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:err $($arg)*));
+    ($lvl:ident, $key:ident :sval $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:sval $($arg)*));
+    ($lvl:ident, $key:ident :serde $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:serde $($arg)*));
+    ($lvl:ident, $key:ident :x $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:x $($arg)*));
+    ($lvl:ident, $key:ident :X $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:X $($arg)*));
+    ($lvl:ident, $key:ident :o $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:o $($arg)*));
+    ($lvl:ident, $key:ident :b $($arg:tt)*) => ($crate::log!(const $crate::Level::$lvl, $key:b $($arg)*));
+
+    // info!(module_path: module_path!(), file: file!(), line: line!(), sample: 0.01, target: "my_target", "a {} event", "log")
+    ($lvl:ident, module_path: $module_path:expr, file: $file:expr, line: $line:expr, $(sample: $rate:expr,)? target: $target:expr, $($arg:tt)+) => ($crate::log!(module_path: $module_path, file: $file, line: $line, $(sample: $rate,)? target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(module_path: module_path!(), file: file!(), line: line!(), sample: 0.01, "a {} event", "log")
+    ($lvl:ident, module_path: $module_path:expr, file: $file:expr, line: $line:expr, sample: $rate:expr, $($arg:tt)+) => ($crate::log!(module_path: $module_path, file: $file, line: $line, sample: $rate, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(module_path: module_path!(), file: file!(), line: line!(), "a {} event", "log")
+    ($lvl:ident, module_path: $module_path:expr, file: $file:expr, line: $line:expr, $($arg:tt)+) => ($crate::log!(module_path: $module_path, file: $file, line: $line, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(sample: 0.01, target: "my_target", "a {} event", "log")
+    ($lvl:ident, sample: $rate:expr, target: $target:expr, $($arg:tt)+) => ($crate::log!(sample: $rate, target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(sample: 0.01, "a {} event", "log")
+    ($lvl:ident, sample: $rate:expr, $($arg:tt)+) => ($crate::log!(sample: $rate, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(kind: RecordKind::Event, target: "my_target", "a {} event", "log")
+    ($lvl:ident, kind: $kind:expr, target: $target:expr, $($arg:tt)+) => ($crate::log!(kind: $kind, target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(kind: RecordKind::Event, "a {} event", "log")
+    ($lvl:ident, kind: $kind:expr, $($arg:tt)+) => ($crate::log!(kind: $kind, const $crate::Level::$lvl, $($arg)+));
+
+    // trace!(v: 3, target: "my_target", "a {} event", "log")
+    (Trace, v: $v:expr, target: $target:expr, $($arg:tt)+) => ($crate::log!(v: $v, target: $target, const $crate::Level::Trace, $($arg)+));
+
+    // trace!(v: 3, "a {} event", "log")
+    (Trace, v: $v:expr, $($arg:tt)+) => ($crate::log!(v: $v, const $crate::Level::Trace, $($arg)+));
+
+    // info!(dedup: "my_key", target: "my_target", "a {} event", "log")
+    ($lvl:ident, dedup: $key:expr, target: $target:expr, $($arg:tt)+) => ($crate::log!(dedup: $key, target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(dedup: "my_key", "a {} event", "log")
+    ($lvl:ident, dedup: $key:expr, $($arg:tt)+) => ($crate::log!(dedup: $key, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(id: "my_id", target: "my_target", "a {} event", "log")
+    ($lvl:ident, id: $id:expr, target: $target:expr, $($arg:tt)+) => ($crate::log!(id: $id, target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(id: "my_id", "a {} event", "log")
+    ($lvl:ident, id: $id:expr, $($arg:tt)+) => ($crate::log!(id: $id, const $crate::Level::$lvl, $($arg)+));
+
+    // info!(target: "my_target", key1 = 42, key2 = true; "a {} event", "log")
+    // info!(target: "my_target", "a {} event", "log")
+    ($lvl:ident, target: $target:expr, $($arg:tt)+) => ($crate::log!(target: $target, const $crate::Level::$lvl, $($arg)+));
+
+    // info!("a {} event", "log")
+    ($lvl:ident, $($arg:tt)+) => ($crate::log!(const $crate::Level::$lvl, $($arg)+));
+}
+
+// Logging in a `const fn`. The regular expansion is wrapped in a closure
+// that's never called, so the arguments are still type checked and count as
+// used, but nothing that can't be evaluated at compile time is run.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "const_noop")]
+macro_rules! __log_const_noop {
+    ($log:expr) => {{
+        let _ = || $log;
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "const_noop"))]
+macro_rules! __log_const_noop {
+    ($log:expr) => {
+        compile_error!("logging in a `const fn` requires the `const_noop` feature of `log`")
+    };
+}
+
 // These macros use a pattern of #[cfg]s to produce nicer error
 // messages when log features aren't available
 
 #[doc(hidden)]
 #[macro_export]
 #[cfg(feature = "kv")]
+macro_rules! __log_kvs {
+    ($($key:tt $(:$capture:tt)? $(= $value:expr)?),+) => {
+        &[$(($crate::__log_key!($key), $crate::__log_value!($key $(:$capture)* = $($value)*))),+]
+    };
+}
+
+// Without the `kv` feature, key-values are stripped at compile time.
+// Their values are referenced in dead code so they still count as used,
+// but they're never evaluated.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_kvs {
+    ($($key:tt $(:$capture:tt)? $(= $value:expr)?),+) => {{
+        if false {
+            $($crate::__log_unused_value!($key $(= $value)*);)+
+        }
+        &[]
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "kv"))]
+macro_rules! __log_unused_value {
+    ($key:tt = $value:expr) => {
+        let _ = &$value;
+    };
+    ($key:ident) => {
+        let _ = &$key;
+    };
+}
+
+// Keys are checked even without the `kv` feature, so code that compiles
+// without it doesn't start failing when it's enabled.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_check_keys {
+    ($($key:tt),+) => {
+        const _: () = $crate::__private_api::check_keys(&[$($crate::__log_key!($key)),+]);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
 macro_rules! __log_key {
     // key1 = 42
     ($($args:ident)*) => {
@@ -241,15 +1163,6 @@ macro_rules! __log_key {
     };
 }
 
-#[doc(hidden)]
-#[macro_export]
-#[cfg(not(feature = "kv"))]
-macro_rules! __log_key {
-    ($($args:tt)*) => {
-        compile_error!("key value support requires the `kv` feature of `log`")
-    };
-}
-
 #[doc(hidden)]
 #[macro_export]
 #[cfg(feature = "kv")]
@@ -285,6 +1198,23 @@ macro_rules! __log_value {
     (($args:expr):display) => {
         $crate::__private_api::capture_display(&&$args)
     };
+    // Formatting hints
+    (($args:expr):x) => {
+        $crate::__private_api::capture_to_value(&&$args)
+            .with_format_hint($crate::kv::FormatHint::LowerHex)
+    };
+    (($args:expr):X) => {
+        $crate::__private_api::capture_to_value(&&$args)
+            .with_format_hint($crate::kv::FormatHint::UpperHex)
+    };
+    (($args:expr):o) => {
+        $crate::__private_api::capture_to_value(&&$args)
+            .with_format_hint($crate::kv::FormatHint::Octal)
+    };
+    (($args:expr):b) => {
+        $crate::__private_api::capture_to_value(&&$args)
+            .with_format_hint($crate::kv::FormatHint::Binary)
+    };
     //Error
     (($args:expr):err) => {
         $crate::__log_value_error!($args)
@@ -299,15 +1229,6 @@ macro_rules! __log_value {
     };
 }
 
-#[doc(hidden)]
-#[macro_export]
-#[cfg(not(feature = "kv"))]
-macro_rules! __log_value {
-    ($($args:tt)*) => {
-        compile_error!("key value support requires the `kv` feature of `log`")
-    };
-}
-
 #[doc(hidden)]
 #[macro_export]
 #[cfg(feature = "kv_sval")]
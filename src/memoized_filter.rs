@@ -0,0 +1,220 @@
+//! Caching the results of expensive filters.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::{Level, Metadata};
+
+/// The number of results cached by default.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// A filter that caches its results by target and level.
+///
+/// Loggers often decide whether a record is enabled by matching its target
+/// against patterns, like regular expressions or globs, which is too expensive
+/// to do for every record. A `MemoizedFilter` wraps a filter function and keeps
+/// the results for the most recently seen targets and levels, so the function
+/// only runs the first time a target is logged to at a level.
+///
+/// The cache is cleared whenever the global maximum log level or logger is
+/// set, as reported by [`max_level_epoch`], so a logger that reconfigures
+/// itself can call [`set_max_level`] to discard stale results. They can also
+/// be discarded with [`MemoizedFilter::clear`].
+///
+/// The filter is only given the level and target of a record, since those are
+/// all its results are cached by.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Level, Log, Metadata, MemoizedFilter, Record};
+///
+/// struct MyLogger {
+///     filter: MemoizedFilter<fn(Level, &str) -> bool>,
+/// }
+///
+/// fn expensive_filter(level: Level, target: &str) -> bool {
+///     level <= Level::Info || target.starts_with("my_app::db")
+/// }
+///
+/// impl Log for MyLogger {
+///     fn enabled(&self, metadata: &Metadata) -> bool {
+///         self.filter.enabled(metadata)
+///     }
+///
+///     fn log(&self, record: &Record) {
+///         if self.enabled(record.metadata()) {
+///             println!("{} - {}", record.level(), record.args());
+///         }
+///     }
+///
+///     fn flush(&self) {}
+/// }
+///
+/// let logger = MyLogger {
+///     filter: MemoizedFilter::new(expensive_filter),
+/// };
+/// # let _ = logger;
+/// ```
+///
+/// [`max_level_epoch`]: fn.max_level_epoch.html
+/// [`set_max_level`]: fn.set_max_level.html
+/// [`MemoizedFilter::clear`]: struct.MemoizedFilter.html#method.clear
+pub struct MemoizedFilter<F> {
+    filter: F,
+    capacity: usize,
+    cache: Mutex<Cache>,
+}
+
+struct Cache {
+    epoch: u64,
+    // Ordered from least to most recently used
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    level: Level,
+    target: Box<str>,
+    enabled: bool,
+}
+
+impl<F> MemoizedFilter<F>
+where
+    F: Fn(Level, &str) -> bool,
+{
+    /// Wrap a filter, caching its results for up to 64 targets and levels.
+    pub fn new(filter: F) -> Self {
+        MemoizedFilter::with_capacity(DEFAULT_CAPACITY, filter)
+    }
+
+    /// Wrap a filter, caching its results for up to `capacity` targets and levels.
+    ///
+    /// When the cache is full, the least recently used result is discarded. A
+    /// capacity of `0` disables caching.
+    pub fn with_capacity(capacity: usize, filter: F) -> Self {
+        MemoizedFilter {
+            filter,
+            capacity,
+            cache: Mutex::new(Cache {
+                epoch: crate::max_level_epoch().1,
+                entries: Vec::with_capacity(capacity),
+            }),
+        }
+    }
+
+    /// Determine if a record with the given metadata is enabled.
+    ///
+    /// The result is cached for the metadata's level and target.
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        let (level, target) = (metadata.level(), metadata.target());
+        let epoch = crate::max_level_epoch().1;
+
+        {
+            let mut cache = self.lock();
+            if cache.epoch != epoch {
+                cache.epoch = epoch;
+                cache.entries.clear();
+            }
+
+            if let Some(i) = cache
+                .entries
+                .iter()
+                .position(|entry| entry.level == level && &*entry.target == target)
+            {
+                let entry = cache.entries.remove(i);
+                let enabled = entry.enabled;
+                cache.entries.push(entry);
+
+                return enabled;
+            }
+        }
+
+        // Run the filter without holding the lock, in case it logs itself
+        let enabled = (self.filter)(level, target);
+
+        if self.capacity > 0 {
+            let mut cache = self.lock();
+            if cache.epoch == epoch {
+                if cache.entries.len() >= self.capacity {
+                    cache.entries.remove(0);
+                }
+
+                cache.entries.push(Entry {
+                    level,
+                    target: target.into(),
+                    enabled,
+                });
+            }
+        }
+
+        enabled
+    }
+
+    /// Discard all cached results.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<F> fmt::Debug for MemoizedFilter<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoizedFilter")
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn metadata(level: Level, target: &str) -> Metadata<'_> {
+        Metadata::builder().level(level).target(target).build()
+    }
+
+    #[test]
+    fn caches_results() {
+        let calls = AtomicUsize::new(0);
+        let filter = MemoizedFilter::with_capacity(2, |level, target: &str| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            level <= Level::Info || target == "verbose"
+        });
+
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert!(!filter.enabled(&metadata(Level::Debug, "a")));
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert!(!filter.enabled(&metadata(Level::Debug, "a")));
+        assert_eq!(2, calls.load(Ordering::Relaxed));
+
+        // The least recently used result is evicted
+        assert!(filter.enabled(&metadata(Level::Debug, "verbose")));
+        assert!(!filter.enabled(&metadata(Level::Debug, "a")));
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert_eq!(4, calls.load(Ordering::Relaxed));
+
+        filter.clear();
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert_eq!(5, calls.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let calls = AtomicUsize::new(0);
+        let filter = MemoizedFilter::with_capacity(0, |_, _: &str| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            true
+        });
+
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert!(filter.enabled(&metadata(Level::Info, "a")));
+        assert_eq!(2, calls.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,110 @@
+//! A thread-local nesting depth captured by the logging macros.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+thread_local! {
+    static CURRENT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Increase the current thread's nesting depth until the returned guard is
+/// dropped.
+///
+/// Records created by the logging macros carry the depth in
+/// [`Record::nesting`], so simple console loggers can indent records logged
+/// by nested operations without a full span system. Scopes can be nested; each
+/// one adds one to the depth.
+///
+/// The depth isn't propagated to other threads, so the guard can't be sent
+/// to another thread.
+///
+/// Requires the `nesting` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::info;
+///
+/// # fn main() {
+/// info!("compiling");
+/// {
+///     let _scope = log::indent_scope();
+///
+///     // This record has a nesting depth of 1
+///     info!("parsing");
+/// }
+/// # }
+/// ```
+///
+/// [`Record::nesting`]: struct.Record.html#method.nesting
+pub fn indent_scope() -> IndentScope {
+    CURRENT.with(|current| current.set(current.get().saturating_add(1)));
+
+    IndentScope {
+        _not_send: PhantomData,
+    }
+}
+
+/// Get the current thread's nesting depth.
+///
+/// This is the number of [`IndentScope`]s that are alive on this thread.
+///
+/// Requires the `nesting` feature.
+///
+/// [`IndentScope`]: struct.IndentScope.html
+#[inline]
+pub fn nesting() -> usize {
+    // The thread-local may already be destroyed if records are logged while
+    // the thread exits
+    CURRENT.try_with(|current| current.get()).unwrap_or(0)
+}
+
+/// A guard returned by [`indent_scope`] that restores the nesting depth when
+/// it's dropped.
+///
+/// [`indent_scope`]: fn.indent_scope.html
+#[must_use = "the nesting depth is restored as soon as the scope is dropped"]
+#[derive(Debug)]
+pub struct IndentScope {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for IndentScope {
+    fn drop(&mut self) {
+        let _ = CURRENT.try_with(|current| current.set(current.get().saturating_sub(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_scopes_are_restored() {
+        assert_eq!(0, nesting());
+
+        {
+            let _outer = indent_scope();
+            assert_eq!(1, nesting());
+
+            {
+                let _inner = indent_scope();
+                assert_eq!(2, nesting());
+            }
+
+            assert_eq!(1, nesting());
+        }
+
+        assert_eq!(0, nesting());
+    }
+
+    #[test]
+    fn scope_is_restored_on_panic() {
+        let _ = std::panic::catch_unwind(|| {
+            let _scope = indent_scope();
+            panic!()
+        });
+
+        assert_eq!(0, nesting());
+    }
+}
@@ -0,0 +1,225 @@
+//! What happens to records created by the logging macros before a logger is installed.
+
+use std::fmt;
+
+#[cfg(feature = "stderr_fallback")]
+use crate::LevelFilter;
+use crate::{AtomicUsize, Level, Ordering};
+
+// Whether to panic instead of dropping records in debug builds
+static PANIC: AtomicUsize = AtomicUsize::new(0);
+
+// The most verbose `LevelFilter` written to stderr instead of being dropped
+#[cfg(feature = "stderr_fallback")]
+static STDERR: AtomicUsize = AtomicUsize::new(LevelFilter::Error as usize);
+
+/// What to do with records created by the logging macros before a logger is installed.
+///
+/// See [`set_pre_init_policy`] for details.
+///
+/// [`set_pre_init_policy`]: fn.set_pre_init_policy.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PreInitPolicy {
+    /// Discard the records.
+    ///
+    /// This is the default without the `early_buffer` or `stderr_fallback`
+    /// features.
+    Drop,
+    /// Buffer up to the given number of records, and replay them to the logger
+    /// when it's installed.
+    ///
+    /// This is the default with the `early_buffer` feature, with a capacity of
+    /// [`early_buffer::DEFAULT_CAPACITY`]. It's equivalent to calling
+    /// [`early_buffer::set_capacity`].
+    ///
+    /// Requires the `early_buffer` feature.
+    ///
+    /// [`early_buffer::DEFAULT_CAPACITY`]: early_buffer/constant.DEFAULT_CAPACITY.html
+    /// [`early_buffer::set_capacity`]: early_buffer/fn.set_capacity.html
+    #[cfg(feature = "early_buffer")]
+    Buffer(usize),
+    /// Panic when debug assertions are enabled, and discard the records otherwise.
+    ///
+    /// This is useful in tests, to catch code that logs before the logger is
+    /// set up.
+    PanicInDebug,
+    /// Write records at or above the given level to stderr, and discard the
+    /// others.
+    ///
+    /// Records are written on a single line, like `[ERROR my_app] message`,
+    /// without their key-values. This is a minimal fallback so errors aren't
+    /// lost if the program fails before its logger is set up, or never sets
+    /// one up at all; it's not a replacement for a logger.
+    ///
+    /// This is the default with the `stderr_fallback` feature, with a level of
+    /// [`LevelFilter::Error`]. Records are still subject to the maximum log
+    /// level, which defaults to [`Off`] with or without the feature, so nothing
+    /// is written to stderr until it's raised with [`set_max_level`].
+    ///
+    /// Requires the `stderr_fallback` feature.
+    ///
+    /// [`LevelFilter::Error`]: enum.LevelFilter.html#variant.Error
+    /// [`Off`]: enum.LevelFilter.html#variant.Off
+    /// [`set_max_level`]: fn.set_max_level.html
+    #[cfg(feature = "stderr_fallback")]
+    Stderr(LevelFilter),
+}
+
+/// Set what to do with records created by the logging macros before a logger
+/// is installed.
+///
+/// Records are still subject to the maximum log level, which defaults to
+/// [`Off`], so only records created after it's raised are affected. Records
+/// created while the logger is being installed by another thread are treated
+/// the same way.
+///
+/// # Examples
+///
+/// ```should_panic
+/// use log::PreInitPolicy;
+///
+/// log::set_pre_init_policy(PreInitPolicy::PanicInDebug);
+/// log::set_max_level(log::LevelFilter::Info);
+///
+/// // Panics in debug builds, since no logger has been installed yet
+/// log::info!("starting up");
+/// # if !cfg!(debug_assertions) { panic!() }
+/// ```
+///
+/// [`Off`]: enum.LevelFilter.html#variant.Off
+pub fn set_pre_init_policy(policy: PreInitPolicy) {
+    #[cfg(feature = "stderr_fallback")]
+    let stderr = match policy {
+        PreInitPolicy::Stderr(level) => level,
+        _ => LevelFilter::Off,
+    };
+    #[cfg(feature = "stderr_fallback")]
+    STDERR.store(stderr as usize, Ordering::Relaxed);
+
+    let (panic, capacity) = match policy {
+        PreInitPolicy::Drop => (false, 0),
+        #[cfg(feature = "early_buffer")]
+        PreInitPolicy::Buffer(capacity) => (false, capacity),
+        PreInitPolicy::PanicInDebug => (true, 0),
+        #[cfg(feature = "stderr_fallback")]
+        PreInitPolicy::Stderr(_) => (false, 0),
+    };
+
+    PANIC.store(panic as usize, Ordering::Relaxed);
+
+    #[cfg(feature = "early_buffer")]
+    crate::early_buffer::set_capacity(capacity);
+    #[cfg(not(feature = "early_buffer"))]
+    let _ = capacity;
+}
+
+/// Get what's done with records created by the logging macros before a logger
+/// is installed.
+///
+/// # Examples
+///
+/// ```
+/// use log::PreInitPolicy;
+///
+/// log::set_pre_init_policy(PreInitPolicy::Drop);
+/// assert_eq!(PreInitPolicy::Drop, log::pre_init_policy());
+/// ```
+pub fn pre_init_policy() -> PreInitPolicy {
+    // Buffered records are never discarded, so buffering takes precedence
+    #[cfg(feature = "early_buffer")]
+    {
+        let capacity = crate::early_buffer::capacity();
+        if capacity > 0 {
+            return PreInitPolicy::Buffer(capacity);
+        }
+    }
+
+    if PANIC.load(Ordering::Relaxed) != 0 {
+        return PreInitPolicy::PanicInDebug;
+    }
+
+    #[cfg(feature = "stderr_fallback")]
+    {
+        let stderr = LevelFilter::from_usize(STDERR.load(Ordering::Relaxed)).unwrap();
+        if stderr != LevelFilter::Off {
+            return PreInitPolicy::Stderr(stderr);
+        }
+    }
+
+    PreInitPolicy::Drop
+}
+
+/// Called for records created by the logging macros before a logger is installed.
+///
+/// Returns whether the record should be buffered, or `None` if it was discarded.
+#[cold]
+pub(crate) fn before_init(
+    args: fmt::Arguments,
+    level: Level,
+    target: &str,
+    capturing: bool,
+) -> Option<bool> {
+    crate::diagnostics::record_before_init();
+
+    #[cfg(feature = "early_buffer")]
+    if crate::early_buffer::is_enabled() {
+        return Some(true);
+    }
+
+    if capturing {
+        return Some(false);
+    }
+
+    if !discard(args, level, target) {
+        crate::diagnostics::record_dropped();
+    }
+    None
+}
+
+/// Called when a record is discarded because no logger is installed.
+///
+/// Returns `true` if the record was written to stderr instead.
+fn discard(args: fmt::Arguments, level: Level, target: &str) -> bool {
+    if cfg!(debug_assertions) && PANIC.load(Ordering::Relaxed) != 0 {
+        panic!(
+            "a record for target `{}` was logged before a logger was installed: {}",
+            target, args
+        );
+    }
+
+    #[cfg(feature = "stderr_fallback")]
+    if level as usize <= STDERR.load(Ordering::Relaxed) {
+        use std::io::Write;
+
+        // There's nowhere to report a failure to write to stderr
+        let _ = writeln!(std::io::stderr().lock(), "[{} {}] {}", level, target, args);
+        return true;
+    }
+    #[cfg(not(feature = "stderr_fallback"))]
+    let _ = level;
+
+    false
+}
+
+#[cfg(all(test, feature = "stderr_fallback"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stderr_fallback() {
+        if cfg!(not(feature = "early_buffer")) {
+            assert_eq!(PreInitPolicy::Stderr(LevelFilter::Error), pre_init_policy());
+        }
+
+        set_pre_init_policy(PreInitPolicy::Stderr(LevelFilter::Warn));
+        assert_eq!(PreInitPolicy::Stderr(LevelFilter::Warn), pre_init_policy());
+
+        assert!(discard(format_args!("written"), Level::Warn, "pre_init"));
+        assert!(!discard(format_args!("dropped"), Level::Info, "pre_init"));
+
+        set_pre_init_policy(PreInitPolicy::Drop);
+        assert_eq!(PreInitPolicy::Drop, pre_init_policy());
+        assert!(!discard(format_args!("dropped"), Level::Error, "pre_init"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Shutting down the logging system.
+
+use std::error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::LevelFilter;
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+static LATE_RECORDS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(any(unix, windows))]
+static FLUSH_ON_EXIT: AtomicBool = AtomicBool::new(false);
+
+static SHUTDOWN_TIMEOUT_ERROR: &str = "timed out waiting for the logger to flush";
+
+/// Stop logging, and flush the logger, waiting at most `timeout` for it to finish.
+///
+/// This function:
+///
+/// 1. Sets the maximum log level to [`Off`], so no new records are created by the
+///    logging macros.
+/// 2. Discards any records created by the logging macros from this point on, like
+///    those on other threads that already passed the maximum log level check.
+///    These are counted as late records.
+/// 3. Calls [`Log::flush`] and then [`Log::on_shutdown`] on the global logger on a
+///    background thread, and waits for them to complete, or for `timeout` to elapse.
+///
+/// Because [`logger`] hands out `&'static` references, the logger itself is never
+/// dropped. Loggers that need to release resources should do so in their
+/// [`Log::on_shutdown`] implementation.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// An error is returned if the logger didn't finish flushing within `timeout`.
+/// The flush will continue on its background thread.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// # fn main() {
+/// match log::try_flush_and_shutdown(Duration::from_secs(1)) {
+///     Ok(report) => eprintln!("{} records were logged after shutdown", report.late_records()),
+///     Err(err) => eprintln!("{err}"),
+/// }
+/// # }
+/// ```
+///
+/// [`Off`]: enum.LevelFilter.html#variant.Off
+/// [`Log::flush`]: trait.Log.html#tymethod.flush
+/// [`Log::on_shutdown`]: trait.Log.html#method.on_shutdown
+/// [`logger`]: fn.logger.html
+pub fn try_flush_and_shutdown(timeout: Duration) -> Result<ShutdownReport, ShutdownError> {
+    SHUTDOWN.store(true, Ordering::Release);
+    crate::set_max_level(LevelFilter::Off);
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        crate::logger().flush();
+        crate::logger().on_shutdown();
+        let _ = tx.send(());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(()) => Ok(ShutdownReport {
+            late_records: LATE_RECORDS.load(Ordering::Acquire),
+        }),
+        Err(_) => Err(ShutdownError(())),
+    }
+}
+
+/// Flush the global logger when the process exits normally.
+///
+/// This registers a hook with the C runtime's `atexit`, which calls [`Log::flush`]
+/// on the global logger after `main` returns or [`std::process::exit`] is called.
+/// Loggers that buffer records would otherwise lose the last of them. The hook
+/// isn't run if the process aborts, panics with `panic = "abort"`, or is killed
+/// by a signal.
+///
+/// Calling this more than once only registers the hook once. It's registered
+/// with the logger that's installed when the process exits, so it can be called
+/// before the logger is installed.
+///
+/// Panics in [`Log::flush`] are caught and ignored, since they can't unwind out
+/// of the hook.
+///
+/// Requires the `std` feature. Only available on Unix and Windows.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() {
+/// log::flush_on_exit();
+///
+/// // Records logged from here on are flushed when `main` returns
+/// log::info!("starting up");
+/// # }
+/// ```
+///
+/// [`Log::flush`]: trait.Log.html#tymethod.flush
+#[cfg(any(unix, windows))]
+pub fn flush_on_exit() {
+    extern "C" {
+        fn atexit(hook: extern "C" fn()) -> std::os::raw::c_int;
+    }
+
+    extern "C" fn flush() {
+        let _ = std::panic::catch_unwind(|| crate::logger().flush());
+    }
+
+    if !FLUSH_ON_EXIT.swap(true, Ordering::AcqRel) {
+        // Registering can only fail if the C runtime runs out of space for
+        // hooks, and there's nothing more useful to do then than to not flush
+        let _ = unsafe { atexit(flush) };
+    }
+}
+
+/// Whether the logging system has been shut down.
+///
+/// Records created by the logging macros after shutdown are discarded.
+#[inline]
+pub(crate) fn is_shutdown() -> bool {
+    SHUTDOWN.load(Ordering::Relaxed)
+}
+
+/// Count a record that was discarded because it arrived after shutdown.
+pub(crate) fn discard_late_record() {
+    LATE_RECORDS.fetch_add(1, Ordering::Release);
+}
+
+/// The result of a successful call to [`try_flush_and_shutdown`].
+///
+/// [`try_flush_and_shutdown`]: fn.try_flush_and_shutdown.html
+#[derive(Debug)]
+pub struct ShutdownReport {
+    late_records: usize,
+}
+
+impl ShutdownReport {
+    /// The number of records created by the logging macros after shutdown began.
+    ///
+    /// These records were discarded instead of being passed to the logger.
+    pub fn late_records(&self) -> usize {
+        self.late_records
+    }
+}
+
+/// The type returned by [`try_flush_and_shutdown`] if the logger didn't
+/// finish flushing in time.
+///
+/// [`try_flush_and_shutdown`]: fn.try_flush_and_shutdown.html
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub struct ShutdownError(());
+
+impl fmt::Display for ShutdownError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(SHUTDOWN_TIMEOUT_ERROR)
+    }
+}
+
+impl error::Error for ShutdownError {}
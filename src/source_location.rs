@@ -0,0 +1,91 @@
+//! The location in the source code a record was created at.
+
+use std::fmt;
+
+/// The location in the source code a record was created at.
+///
+/// This bundles the module path, file, line, and column of a [`Record`], which
+/// can be retrieved together with [`Record::source_location`]. Records created
+/// by the logging macros always have a source location.
+///
+/// # Examples
+///
+/// ```
+/// use log::{Record, SourceLocation};
+///
+/// static LOCATION: SourceLocation<'static> =
+///     SourceLocation::new("my_app::server", "src/server.rs", 42).with_column(9);
+///
+/// let record = Record::builder().source_location(&LOCATION).build();
+///
+/// let location = record.source_location().unwrap();
+/// assert_eq!("my_app::server", location.module_path());
+/// assert_eq!("src/server.rs:42:9", location.to_string());
+/// ```
+///
+/// [`Record`]: struct.Record.html
+/// [`Record::source_location`]: struct.Record.html#method.source_location
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SourceLocation<'a> {
+    module_path: &'a str,
+    file: &'a str,
+    line: u32,
+    column: Option<u32>,
+}
+
+impl<'a> SourceLocation<'a> {
+    /// Create a source location without a column.
+    #[inline]
+    pub const fn new(module_path: &'a str, file: &'a str, line: u32) -> Self {
+        SourceLocation {
+            module_path,
+            file,
+            line,
+            column: None,
+        }
+    }
+
+    /// Set the column of the source location.
+    #[inline]
+    pub const fn with_column(mut self, column: u32) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// The module path of the source location.
+    #[inline]
+    pub fn module_path(&self) -> &'a str {
+        self.module_path
+    }
+
+    /// The source file of the source location.
+    #[inline]
+    pub fn file(&self) -> &'a str {
+        self.file
+    }
+
+    /// The line of the source location.
+    #[inline]
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column of the source location, if it's known.
+    #[inline]
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+}
+
+/// Formats the location as `file:line`, or `file:line:column` if the column is known.
+impl<'a> fmt::Display for SourceLocation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+
+        Ok(())
+    }
+}
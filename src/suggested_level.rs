@@ -0,0 +1,180 @@
+//! Default levels suggested by libraries for their own targets.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::lazy_global::LazyGlobal;
+use crate::LevelFilter;
+
+static SUGGESTED: LazyGlobal<Mutex<BTreeMap<String, LevelFilter>>> = LazyGlobal::new();
+
+/// Suggest a default maximum level for records logged to `target` and the
+/// targets under it.
+///
+/// Libraries that log a lot at some level, like per-connection debug records
+/// in a network library, can use this to suggest that applications filter
+/// them out by default. The suggestions don't filter anything themselves;
+/// loggers can use [`suggested_filter`] to pick up the suggestions from all
+/// libraries when they're initialized, instead of needing a hardcoded filter
+/// string that names each of them.
+///
+/// Suggestions should be made before the logger is initialized, such as when
+/// the library is first set up. Suggesting a level for a target that already
+/// has one replaces it.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::LevelFilter;
+///
+/// // In a library called `my_http`
+/// pub fn init() {
+///     log::suggest_level(module_path!(), LevelFilter::Warn);
+/// }
+/// # init();
+/// # assert_eq!(Some(LevelFilter::Warn), log::suggested_filter().level_for(module_path!()));
+/// ```
+///
+/// [`suggested_filter`]: fn.suggested_filter.html
+pub fn suggest_level(target: &str, level: LevelFilter) {
+    lock().insert(target.into(), level);
+}
+
+/// Get the default levels suggested by libraries with [`suggest_level`].
+///
+/// The returned filter is a snapshot; suggestions made afterwards aren't
+/// included in it.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::LevelFilter;
+///
+/// log::suggest_level("my_http", LevelFilter::Warn);
+/// log::suggest_level("my_http::pool", LevelFilter::Error);
+///
+/// let filter = log::suggested_filter();
+///
+/// assert_eq!(Some(LevelFilter::Warn), filter.level_for("my_http::client"));
+/// assert_eq!(Some(LevelFilter::Error), filter.level_for("my_http::pool"));
+/// assert_eq!(None, filter.level_for("my_app"));
+///
+/// // The filter can also be formatted like `RUST_LOG`
+/// assert_eq!("my_http=warn,my_http::pool=error", filter.to_string());
+/// ```
+///
+/// [`suggest_level`]: fn.suggest_level.html
+pub fn suggested_filter() -> SuggestedFilter {
+    SuggestedFilter {
+        levels: lock().clone(),
+    }
+}
+
+/// A snapshot of the default levels suggested by libraries.
+///
+/// See [`suggested_filter`] for details.
+///
+/// Formatting a filter with `Display` produces a comma-separated list of
+/// `target=level` directives, like `RUST_LOG`.
+///
+/// [`suggested_filter`]: fn.suggested_filter.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SuggestedFilter {
+    levels: BTreeMap<String, LevelFilter>,
+}
+
+impl SuggestedFilter {
+    /// Get the suggested level for `target`.
+    ///
+    /// This is the level suggested for the longest target that `target` is
+    /// equal to or nested under, where targets are nested using `::`. If no
+    /// level was suggested for any of them this returns `None`.
+    pub fn level_for(&self, target: &str) -> Option<LevelFilter> {
+        let mut prefix = target;
+        loop {
+            if let Some(level) = self.levels.get(prefix) {
+                return Some(*level);
+            }
+
+            prefix = &prefix[..prefix.rfind("::")?];
+        }
+    }
+
+    /// Iterate over the targets with suggested levels, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, LevelFilter)> {
+        self.levels
+            .iter()
+            .map(|(target, level)| (&**target, *level))
+    }
+
+    /// Whether no levels have been suggested.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+}
+
+impl fmt::Display for SuggestedFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (target, level)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{}={}", target, level.as_str().to_ascii_lowercase())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn lock() -> MutexGuard<'static, BTreeMap<String, LevelFilter>> {
+    suggested().lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn suggested() -> &'static Mutex<BTreeMap<String, LevelFilter>> {
+    SUGGESTED.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_for_matches_whole_segments() {
+        let mut filter = SuggestedFilter::default();
+        filter.levels.insert("hyper".into(), LevelFilter::Warn);
+        filter
+            .levels
+            .insert("hyper::proto".into(), LevelFilter::Error);
+
+        assert_eq!(Some(LevelFilter::Warn), filter.level_for("hyper"));
+        assert_eq!(
+            Some(LevelFilter::Warn),
+            filter.level_for("hyper::client::pool")
+        );
+        assert_eq!(
+            Some(LevelFilter::Error),
+            filter.level_for("hyper::proto::h1")
+        );
+        assert_eq!(None, filter.level_for("hyper_util"));
+        assert_eq!(None, filter.level_for("hyp"));
+
+        assert_eq!("hyper=warn,hyper::proto=error", filter.to_string());
+    }
+
+    #[test]
+    fn later_suggestions_replace_earlier() {
+        suggest_level("suggested_level::tests", LevelFilter::Info);
+        suggest_level("suggested_level::tests", LevelFilter::Off);
+
+        assert_eq!(
+            Some(LevelFilter::Off),
+            suggested_filter().level_for("suggested_level::tests::a")
+        );
+    }
+}
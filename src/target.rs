@@ -0,0 +1,79 @@
+//! Registered targets that loggers can identify without comparing strings.
+
+#[cfg(feature = "std")]
+use crate::{AtomicUsize, Ordering};
+
+#[cfg(feature = "std")]
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A handle to a target registered with [`register_target`].
+///
+/// Handles can be passed to the logging macros in place of a `target:` string.
+/// Records logged with a handle have its name as their [`Metadata::target`], and
+/// the handle itself as their [`Metadata::target_handle`]. Since each handle has
+/// a unique [`id`](#method.id), loggers can cache decisions about a target keyed
+/// on the id, instead of matching its name against their filters each time.
+///
+/// This is useful when targets are built at runtime, such as per-plugin names,
+/// where the macros would otherwise be passed a freshly formatted `String` for
+/// each record.
+///
+/// # Examples
+///
+/// ```
+/// use log::info;
+///
+/// # fn main() {}
+/// # #[cfg(feature = "std")]
+/// # fn run() {
+/// let plugin = "resize";
+/// let target = log::register_target(&format!("plugins::{}", plugin));
+///
+/// info!(target: target, "plugin loaded");
+/// # }
+/// ```
+///
+/// [`register_target`]: fn.register_target.html
+/// [`Metadata::target`]: struct.Metadata.html#method.target
+/// [`Metadata::target_handle`]: struct.Metadata.html#method.target_handle
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TargetHandle {
+    id: usize,
+    name: &'static str,
+}
+
+impl TargetHandle {
+    /// The unique id of the target.
+    ///
+    /// Ids are assigned in the order targets are registered, starting from 0.
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The name of the target.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Register a target, returning a handle to it that can be passed to the
+/// logging macros.
+///
+/// The name is leaked, and each call returns a new handle with a new id, even
+/// if the name was registered before. Targets should be registered once, when
+/// the component they belong to is created, and the handle stored.
+///
+/// See [`TargetHandle`] for details.
+///
+/// Requires the `std` feature.
+///
+/// [`TargetHandle`]: struct.TargetHandle.html
+#[cfg(feature = "std")]
+pub fn register_target(name: &str) -> TargetHandle {
+    TargetHandle {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        name: Box::leak(name.into()),
+    }
+}
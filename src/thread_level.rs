@@ -0,0 +1,117 @@
+//! A per-thread override of the maximum log level.
+
+use std::cell::Cell;
+
+use crate::{AtomicUsize, Level, LevelFilter, Ordering};
+
+thread_local! {
+    static CURRENT: Cell<Option<LevelFilter>> = const { Cell::new(None) };
+}
+
+// Set to 1 once any thread has overridden its maximum level, so the logging
+// macros don't need to check thread-local state until then
+static USED: AtomicUsize = AtomicUsize::new(0);
+
+/// Override the maximum log level for records created by the logging macros
+/// on the current thread.
+///
+/// The override applies after the [`STATIC_MAX_LEVEL`] check, and replaces the
+/// global [`max_level`] for this thread only. It can make a thread quieter or
+/// more verbose than the rest of the program, which is useful for test harnesses
+/// and for worker threads that are especially chatty. Loggers may still filter
+/// records themselves in [`Log::enabled`] and [`Log::log`].
+///
+/// Audit records aren't affected by the override. The [`log_enabled!`] macro
+/// takes the override into account, but [`TargetLogger::enabled`] doesn't.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use log::{debug, LevelFilter};
+///
+/// # fn main() {
+/// log::set_max_level(LevelFilter::Info);
+///
+/// std::thread::spawn(|| {
+///     log::set_thread_max_level(LevelFilter::Debug);
+///
+///     // Passed to the logger, even though the global maximum level is `Info`
+///     debug!("compacting");
+/// })
+/// .join()
+/// .unwrap();
+/// # }
+/// ```
+///
+/// [`STATIC_MAX_LEVEL`]: constant.STATIC_MAX_LEVEL.html
+/// [`max_level`]: fn.max_level.html
+/// [`Log::enabled`]: trait.Log.html#tymethod.enabled
+/// [`Log::log`]: trait.Log.html#tymethod.log
+/// [`log_enabled!`]: macro.log_enabled.html
+/// [`TargetLogger::enabled`]: struct.TargetLogger.html#method.enabled
+pub fn set_thread_max_level(level: LevelFilter) {
+    USED.store(1, Ordering::Relaxed);
+    CURRENT.with(|current| current.set(Some(level)));
+}
+
+/// Remove the current thread's override of the maximum log level, so the
+/// global [`max_level`] applies to it again.
+///
+/// Requires the `std` feature.
+///
+/// [`max_level`]: fn.max_level.html
+pub fn clear_thread_max_level() {
+    CURRENT.with(|current| current.set(None));
+}
+
+/// Get the current thread's override of the maximum log level, if it has one.
+///
+/// Requires the `std` feature.
+#[inline]
+pub fn thread_max_level() -> Option<LevelFilter> {
+    if !used() {
+        return None;
+    }
+
+    // The thread-local may already be destroyed if records are logged while
+    // the thread exits
+    CURRENT.try_with(|current| current.get()).unwrap_or(None)
+}
+
+/// Whether any thread has ever overridden its maximum level.
+#[inline]
+pub(crate) fn used() -> bool {
+    USED.load(Ordering::Relaxed) != 0
+}
+
+/// Whether the current thread's override allows a record at `level`.
+///
+/// Returns `None` if the thread doesn't have an override.
+#[inline]
+pub(crate) fn allows(level: Level) -> Option<bool> {
+    thread_max_level().map(|max| level <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_is_per_thread() {
+        set_thread_max_level(LevelFilter::Warn);
+        assert!(used());
+        assert_eq!(Some(LevelFilter::Warn), thread_max_level());
+        assert_eq!(Some(false), allows(Level::Info));
+        assert_eq!(Some(true), allows(Level::Error));
+
+        std::thread::spawn(|| assert_eq!(None, thread_max_level()))
+            .join()
+            .unwrap();
+
+        clear_thread_max_level();
+        assert_eq!(None, thread_max_level());
+        assert_eq!(None, allows(Level::Info));
+    }
+}
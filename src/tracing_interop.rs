@@ -0,0 +1,83 @@
+#![cfg(feature = "tracing_interop")]
+
+// Conversions between `Level` and `LevelFilter` and their counterparts in `tracing`.
+//
+// `tracing` re-exports these types from `tracing-core`, so depending on the
+// smaller crate is enough for the conversions to apply to both.
+
+use crate::{Level, LevelFilter};
+
+impl From<Level> for tracing_core::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => tracing_core::Level::ERROR,
+            Level::Warn => tracing_core::Level::WARN,
+            Level::Info => tracing_core::Level::INFO,
+            Level::Debug => tracing_core::Level::DEBUG,
+            Level::Trace => tracing_core::Level::TRACE,
+        }
+    }
+}
+
+impl From<tracing_core::Level> for Level {
+    fn from(level: tracing_core::Level) -> Self {
+        match level {
+            tracing_core::Level::ERROR => Level::Error,
+            tracing_core::Level::WARN => Level::Warn,
+            tracing_core::Level::INFO => Level::Info,
+            tracing_core::Level::DEBUG => Level::Debug,
+            tracing_core::Level::TRACE => Level::Trace,
+        }
+    }
+}
+
+impl From<LevelFilter> for tracing_core::LevelFilter {
+    fn from(filter: LevelFilter) -> Self {
+        match filter.to_level() {
+            Some(level) => tracing_core::LevelFilter::from_level(level.into()),
+            None => tracing_core::LevelFilter::OFF,
+        }
+    }
+}
+
+impl From<tracing_core::LevelFilter> for LevelFilter {
+    fn from(filter: tracing_core::LevelFilter) -> Self {
+        match filter.into_level() {
+            Some(level) => Level::from(level).to_level_filter(),
+            None => LevelFilter::Off,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_round_trip() {
+        for level in Level::iter() {
+            let converted = tracing_core::Level::from(level);
+
+            assert_eq!(level.as_str(), converted.as_str());
+            assert_eq!(level, Level::from(converted));
+        }
+    }
+
+    #[test]
+    fn level_filter_round_trip() {
+        for filter in LevelFilter::iter() {
+            let converted = tracing_core::LevelFilter::from(filter);
+
+            assert_eq!(filter, LevelFilter::from(converted));
+        }
+
+        assert_eq!(
+            tracing_core::LevelFilter::OFF,
+            tracing_core::LevelFilter::from(LevelFilter::Off)
+        );
+        assert_eq!(
+            tracing_core::LevelFilter::TRACE,
+            tracing_core::LevelFilter::from(LevelFilter::Trace)
+        );
+    }
+}
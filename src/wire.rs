@@ -0,0 +1,554 @@
+//! A compact binary encoding of records.
+//!
+//! Records can be encoded with [`Record::encode`] and decoded into an
+//! [`OwnedRecord`] with [`OwnedRecord::decode`], so they can be forwarded to
+//! another process, like a sidecar collector, without each application
+//! inventing its own serialization.
+//!
+//! Requires the `wire` feature.
+//!
+//! # Format
+//!
+//! Each record is encoded as:
+//!
+//! | Field | Encoding |
+//! | ----- | -------- |
+//! | version | `u8`, currently `1` |
+//! | level | `u8`, from `1` for `Error` to `5` for `Trace` |
+//! | target | string |
+//! | message | string |
+//! | module path | optional string |
+//! | file | optional string |
+//! | line | optional `u32` |
+//! | key-values | `u32` count, followed by a string key and a value for each |
+//!
+//! Integers are little-endian. Strings are a `u32` length in bytes followed by
+//! that many bytes of UTF-8. Optional fields are a `u8` that's `0` if the field
+//! is missing, or `1` followed by the field. Values are a `u8` tag followed by
+//! the value:
+//!
+//! | Tag | Value |
+//! | --- | ----- |
+//! | `0` | `null` |
+//! | `1` | `bool`, as a `u8` |
+//! | `2` | `char`, as a `u32` |
+//! | `3` | `u64` |
+//! | `4` | `i64` |
+//! | `5` | `u128` |
+//! | `6` | `i128` |
+//! | `7` | `f64`, as its bits in a `u64` |
+//! | `8` | string |
+//!
+//! Values that aren't primitives, like those captured using `Debug`, are
+//! encoded as their formatted string.
+//!
+//! Records are self-delimiting, so several can be written to the same stream
+//! one after the other.
+//!
+//! # Examples
+//!
+//! ```
+//! use log::kv::ToValue;
+//! use log::{wire::OwnedRecord, Level, Record};
+//!
+//! let kvs = [("attempt", 3)];
+//! let record = Record::builder()
+//!     .args(format_args!("connection refused"))
+//!     .level(Level::Warn)
+//!     .target("my_app::db")
+//!     .key_values(&kvs)
+//!     .build();
+//!
+//! let mut buf = Vec::new();
+//! record.encode(&mut buf)?;
+//!
+//! let decoded = OwnedRecord::decode(&mut &buf[..])?;
+//!
+//! assert_eq!(Level::Warn, decoded.level());
+//! assert_eq!("connection refused", decoded.message());
+//! assert_eq!(Some(3), decoded.key_values()[0].1.to_value().to_u64());
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [`Record::encode`]: ../struct.Record.html#method.encode
+//! [`OwnedRecord`]: struct.OwnedRecord.html
+//! [`OwnedRecord::decode`]: struct.OwnedRecord.html#method.decode
+
+use std::io::{self, Read, Write};
+
+use crate::kv::{self, OwnedValue, ToValue, Value, VisitSource, VisitValue};
+use crate::{Level, Record};
+
+const VERSION: u8 = 1;
+
+const NULL: u8 = 0;
+const BOOL: u8 = 1;
+const CHAR: u8 = 2;
+const U64: u8 = 3;
+const I64: u8 = 4;
+const U128: u8 = 5;
+const I128: u8 = 6;
+const F64: u8 = 7;
+const STR: u8 = 8;
+
+/// An owned record, such as one decoded from its binary encoding.
+///
+/// A [`Record`] can also be copied into an `OwnedRecord` with `From`, so it
+/// can be kept after the logger it was passed to returns. The record's message
+/// is formatted, and its key-values are copied like [`Source::to_owned_vec`]
+/// copies them.
+///
+/// See the [module documentation](index.html) for details.
+///
+/// [`Record`]: ../struct.Record.html
+/// [`Source::to_owned_vec`]: ../kv/trait.Source.html#method.to_owned_vec
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedRecord {
+    level: Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    key_values: Vec<(String, OwnedValue)>,
+}
+
+impl OwnedRecord {
+    /// Decode a record written by [`Record::encode`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if reading fails, or if the bytes read aren't a
+    /// record encoded with a supported version of the format. The error has a
+    /// kind of `io::ErrorKind::InvalidData` in the latter case.
+    ///
+    /// [`Record::encode`]: ../struct.Record.html#method.encode
+    pub fn decode(r: &mut impl Read) -> io::Result<Self> {
+        let version = read_u8(r)?;
+        if version != VERSION {
+            return Err(invalid_data("unsupported record version"));
+        }
+
+        let level =
+            Level::try_from(read_u8(r)?).map_err(|_| invalid_data("invalid record level"))?;
+        let target = read_str(r)?;
+        let message = read_str(r)?;
+        let module_path = read_opt(r, read_str)?;
+        let file = read_opt(r, read_str)?;
+        let line = read_opt(r, read_u32)?;
+
+        let count = read_u32(r)?;
+        // The count isn't trusted for preallocating, since it could be corrupt
+        let mut key_values = Vec::new();
+        for _ in 0..count {
+            let key = read_str(r)?;
+            let value = read_value(r)?;
+
+            key_values.push((key, value));
+        }
+
+        Ok(OwnedRecord {
+            level,
+            target,
+            message,
+            module_path,
+            file,
+            line,
+            key_values,
+        })
+    }
+
+    /// The level of the record.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The target of the record.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The formatted message of the record.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The module path the record was created in, if it's known.
+    pub fn module_path(&self) -> Option<&str> {
+        self.module_path.as_deref()
+    }
+
+    /// The source file the record was created in, if it's known.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// The line the record was created on, if it's known.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// The key-values of the record.
+    ///
+    /// The returned slice implements [`Source`], so values can be looked up
+    /// by their key.
+    ///
+    /// [`Source`]: ../kv/trait.Source.html
+    pub fn key_values(&self) -> &[(String, OwnedValue)] {
+        &self.key_values
+    }
+
+    /// Call `f` with a [`Record`] borrowing from this one.
+    ///
+    /// This can be used to pass a decoded record on to a logger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn decode() -> std::io::Result<log::wire::OwnedRecord> {
+    /// #     let mut buf = Vec::new();
+    /// #     log::Record::builder().build().encode(&mut buf)?;
+    /// #     log::wire::OwnedRecord::decode(&mut &buf[..])
+    /// # }
+    /// let record = decode()?;
+    ///
+    /// record.with_record(|record| log::log_record(record));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// [`Record`]: ../struct.Record.html
+    pub fn with_record<R>(&self, f: impl FnOnce(&Record) -> R) -> R {
+        f(&Record::builder()
+            .args(format_args!("{}", self.message))
+            .level(self.level)
+            .target(&self.target)
+            .module_path(self.module_path.as_deref())
+            .file(self.file.as_deref())
+            .line(self.line)
+            .key_values(&self.key_values)
+            .build())
+    }
+}
+
+impl<'a> From<&Record<'a>> for OwnedRecord {
+    fn from(record: &Record<'a>) -> Self {
+        OwnedRecord {
+            level: record.level(),
+            target: record.target().into(),
+            message: record.render().into_owned(),
+            module_path: record.module_path().map(Into::into),
+            file: record.file().map(Into::into),
+            line: record.line(),
+            key_values: record.key_values().to_owned_vec(),
+        }
+    }
+}
+
+/// The implementation of `Record::encode`.
+pub(crate) fn encode(record: &Record, w: &mut impl Write) -> io::Result<()> {
+    w.write_all(&[VERSION, u8::from(record.level())])?;
+    write_str(w, record.target())?;
+    write_str(w, &record.render())?;
+    write_opt(w, record.module_path(), write_str)?;
+    write_opt(w, record.file(), write_str)?;
+    write_opt(w, record.line(), write_u32)?;
+
+    // Key-values are encoded into a buffer first, since their count isn't
+    // known until they've been visited
+    let mut kvs = EncodeKvs {
+        count: 0,
+        buf: Vec::new(),
+    };
+    record
+        .key_values()
+        .visit(&mut kvs)
+        .map_err(kv::Error::into_io)?;
+
+    write_u32(w, kvs.count)?;
+    w.write_all(&kvs.buf)
+}
+
+struct EncodeKvs {
+    count: u32,
+    buf: Vec<u8>,
+}
+
+impl<'kvs> VisitSource<'kvs> for EncodeKvs {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        self.count = self
+            .count
+            .checked_add(1)
+            .ok_or_else(|| kv::Error::msg("too many key-values to encode"))?;
+
+        write_str(&mut self.buf, key.as_str())?;
+        write_value(&mut self.buf, &value)?;
+
+        Ok(())
+    }
+}
+
+fn write_value(w: &mut Vec<u8>, value: &Value) -> io::Result<()> {
+    struct Encode<'a>(&'a mut Vec<u8>);
+
+    impl<'a, 'v> VisitValue<'v> for Encode<'a> {
+        fn visit_any(&mut self, value: Value) -> Result<(), kv::Error> {
+            self.0.push(STR);
+            write_str(self.0, &value.to_string())?;
+            Ok(())
+        }
+
+        fn visit_null(&mut self) -> Result<(), kv::Error> {
+            self.0.push(NULL);
+            Ok(())
+        }
+
+        fn visit_bool(&mut self, value: bool) -> Result<(), kv::Error> {
+            self.0.extend_from_slice(&[BOOL, value as u8]);
+            Ok(())
+        }
+
+        fn visit_char(&mut self, value: char) -> Result<(), kv::Error> {
+            self.0.push(CHAR);
+            self.0.extend_from_slice(&(value as u32).to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_u64(&mut self, value: u64) -> Result<(), kv::Error> {
+            self.0.push(U64);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_i64(&mut self, value: i64) -> Result<(), kv::Error> {
+            self.0.push(I64);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_u128(&mut self, value: u128) -> Result<(), kv::Error> {
+            self.0.push(U128);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_i128(&mut self, value: i128) -> Result<(), kv::Error> {
+            self.0.push(I128);
+            self.0.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_f64(&mut self, value: f64) -> Result<(), kv::Error> {
+            self.0.push(F64);
+            self.0.extend_from_slice(&value.to_bits().to_le_bytes());
+            Ok(())
+        }
+
+        fn visit_str(&mut self, value: &str) -> Result<(), kv::Error> {
+            self.0.push(STR);
+            write_str(self.0, value)?;
+            Ok(())
+        }
+    }
+
+    value.visit(Encode(w)).map_err(kv::Error::into_io)
+}
+
+fn read_value(r: &mut impl Read) -> io::Result<OwnedValue> {
+    let value = match read_u8(r)? {
+        NULL => OwnedValue::from(Value::null()),
+        BOOL => OwnedValue::from(Value::from(read_u8(r)? != 0)),
+        CHAR => {
+            let value = char::from_u32(read_u32(r)?)
+                .ok_or_else(|| invalid_data("invalid char in record"))?;
+            OwnedValue::from(Value::from(value))
+        }
+        U64 => OwnedValue::from(Value::from(u64::from_le_bytes(read_array(r)?))),
+        I64 => OwnedValue::from(Value::from(i64::from_le_bytes(read_array(r)?))),
+        U128 => OwnedValue::from(Value::from(u128::from_le_bytes(read_array(r)?))),
+        I128 => OwnedValue::from(Value::from(i128::from_le_bytes(read_array(r)?))),
+        F64 => OwnedValue::from(Value::from(f64::from_bits(u64::from_le_bytes(read_array(
+            r,
+        )?)))),
+        STR => OwnedValue::from(read_str(r)?.to_value()),
+        _ => return Err(invalid_data("invalid value tag in record")),
+    };
+
+    Ok(value)
+}
+
+fn write_u32(w: &mut impl Write, value: u32) -> io::Result<()> {
+    w.write_all(&value.to_le_bytes())
+}
+
+fn write_str(w: &mut impl Write, value: &str) -> io::Result<()> {
+    let len = u32::try_from(value.len()).map_err(|_| invalid_input("string too long to encode"))?;
+
+    write_u32(w, len)?;
+    w.write_all(value.as_bytes())
+}
+
+fn write_opt<W: Write, T>(
+    w: &mut W,
+    value: Option<T>,
+    write: impl FnOnce(&mut W, T) -> io::Result<()>,
+) -> io::Result<()> {
+    match value {
+        Some(value) => {
+            w.write_all(&[1])?;
+            write(w, value)
+        }
+        None => w.write_all(&[0]),
+    }
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    Ok(read_array::<1>(r)?[0])
+}
+
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(r)?))
+}
+
+fn read_str(r: &mut impl Read) -> io::Result<String> {
+    let len = read_u32(r)? as u64;
+
+    // The length isn't trusted for preallocating, since it could be corrupt
+    let mut buf = Vec::new();
+    r.take(len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    String::from_utf8(buf).map_err(|_| invalid_data("invalid UTF-8 in record"))
+}
+
+fn read_opt<R: Read, T>(
+    r: &mut R,
+    read: impl FnOnce(&mut R) -> io::Result<T>,
+) -> io::Result<Option<T>> {
+    match read_u8(r)? {
+        0 => Ok(None),
+        1 => read(r).map(Some),
+        _ => Err(invalid_data("invalid optional field in record")),
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn invalid_input(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(record: &Record) -> OwnedRecord {
+        let mut buf = Vec::new();
+        record.encode(&mut buf).unwrap();
+
+        OwnedRecord::decode(&mut &buf[..]).unwrap()
+    }
+
+    #[test]
+    fn roundtrip_record() {
+        let kvs: [(&str, Value); 9] = [
+            ("null", Value::null()),
+            ("bool", Value::from(true)),
+            ("char", Value::from('a')),
+            ("u64", Value::from(u64::MAX)),
+            ("i64", Value::from(i64::MIN)),
+            ("u128", Value::from(u128::MAX)),
+            ("i128", Value::from(i128::MIN)),
+            ("f64", Value::from(1.5)),
+            ("debug", Value::from_debug(&Some(1))),
+        ];
+        let record = Record::builder()
+            .args(format_args!("hello {}", "world"))
+            .level(Level::Debug)
+            .target("wire")
+            .module_path_static(Some("my_app::wire"))
+            .file_static(Some("src/wire.rs"))
+            .line(Some(42))
+            .key_values(&kvs)
+            .build();
+
+        let decoded = roundtrip(&record);
+
+        assert_eq!(Level::Debug, decoded.level());
+        assert_eq!("wire", decoded.target());
+        assert_eq!("hello world", decoded.message());
+        assert_eq!(Some("my_app::wire"), decoded.module_path());
+        assert_eq!(Some("src/wire.rs"), decoded.file());
+        assert_eq!(Some(42), decoded.line());
+        assert_eq!(kv::Source::to_owned_vec(&kvs), decoded.key_values());
+
+        // Copying a record gives the same result as encoding and decoding it
+        assert_eq!(OwnedRecord::from(&record), decoded);
+
+        // Re-encoding a decoded record produces the same bytes
+        let mut original = Vec::new();
+        record.encode(&mut original).unwrap();
+        let mut reencoded = Vec::new();
+        decoded
+            .with_record(|record| record.encode(&mut reencoded))
+            .unwrap();
+        assert_eq!(original, reencoded);
+    }
+
+    #[test]
+    fn roundtrip_well_known_values() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let duration = Duration::from_millis(1250);
+        let timestamp = UNIX_EPOCH + Duration::new(1_714_566_600, 250_000_001);
+        let kvs = [
+            ("duration", duration.to_value()),
+            ("timestamp", timestamp.to_value()),
+        ];
+
+        // Durations and timestamps are encoded as strings, which can be
+        // converted back
+        let decoded = roundtrip(&Record::builder().key_values(&kvs).build());
+        let values = decoded.key_values();
+
+        assert_eq!(Some(duration), values[0].1.to_value().to_duration());
+        assert_eq!(Some(timestamp), values[1].1.to_value().to_timestamp());
+    }
+
+    #[test]
+    fn roundtrip_empty_record() {
+        let decoded = roundtrip(&Record::builder().build());
+
+        assert_eq!("", decoded.message());
+        assert_eq!(None, decoded.file());
+        assert!(decoded.key_values().is_empty());
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let mut buf = Vec::new();
+        Record::builder().build().encode(&mut buf).unwrap();
+
+        let mut version = buf.clone();
+        version[0] = 0;
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            OwnedRecord::decode(&mut &version[..]).unwrap_err().kind()
+        );
+
+        let truncated = &buf[..buf.len() - 1];
+        assert_eq!(
+            io::ErrorKind::UnexpectedEof,
+            OwnedRecord::decode(&mut &truncated[..]).unwrap_err().kind()
+        );
+    }
+}
@@ -0,0 +1,492 @@
+//! Assertions for the records logged by code under test.
+//!
+//! Records are collected with [`log::capture`], and then checked against a list of
+//! expected records with the [`expect_logs!`] macro. Each expected record gives a
+//! level, a message [pattern](Pattern), and optionally some key-values:
+//!
+//! ```
+//! use log::{error, warn};
+//! use log_test::expect_logs;
+//!
+//! fn connect() {
+//!     warn!(attempt = 1; "connection refused, retrying");
+//!     error!(err = "timed out", attempt = 2; "giving up after 2 attempts");
+//! }
+//!
+//! let records = log::capture(connect);
+//!
+//! expect_logs!(records, [
+//!     Warn "connection refused*",
+//!     Error "giving up *" { err: *, attempt: 2 },
+//! ]);
+//! ```
+//!
+//! The records must match the expectations one-to-one, in order. A record matches
+//! when:
+//!
+//! - its level is the expected level,
+//! - its message matches the expected pattern, where `*` matches any run of
+//!   characters, and
+//! - each expected key is present, with a value matching its expectation. `*`
+//!   matches any value, strings are patterns matched against the value's
+//!   `Display` output, and numbers and booleans are compared by value. Values
+//!   made of more than one token, like `-1`, need to be wrapped in parentheses.
+//!
+//! Key-values that aren't mentioned aren't checked. If the records don't match,
+//! the macro panics with a report of the expected and captured records.
+//!
+//! [`log::capture`]: https://docs.rs/log/*/log/fn.capture.html
+
+#![warn(missing_docs)]
+#![deny(missing_debug_implementations)]
+
+use std::fmt::{self, Write as _};
+
+use log::kv::{OwnedValue, ToValue};
+use log::{CapturedRecord, Level};
+
+/// Assert that captured records match a list of expected records.
+///
+/// The first argument is the captured records, as returned by [`log::capture`].
+/// See the [crate documentation](crate) for the syntax of the expected records.
+///
+/// [`log::capture`]: https://docs.rs/log/*/log/fn.capture.html
+#[macro_export]
+macro_rules! expect_logs {
+    ($records:expr, [$($level:ident $message:literal $({ $($key:ident : $value:tt),* $(,)? })?),* $(,)?]) => {
+        $crate::assert_logs(
+            &$records,
+            &[$(
+                $crate::Expected::new($crate::__private::Level::$level, $message)
+                    $($(.key_value(stringify!($key), $crate::__expect_value!($value)))*)?
+            ),*],
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __expect_value {
+    (*) => {
+        $crate::ValuePattern::Any
+    };
+    ($value:expr) => {
+        $crate::ValuePattern::from($value)
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use log::Level;
+}
+
+/// Assert that captured records match a list of expected records.
+///
+/// This is the function behind [`expect_logs!`], for building expectations in code.
+///
+/// # Panics
+///
+/// Panics with a report of the expected and captured records if they don't match.
+///
+/// # Examples
+///
+/// ```
+/// use log::Level;
+/// use log_test::{assert_logs, Expected};
+///
+/// let records = log::capture(|| log::info!(user = "ferris"; "logged in"));
+///
+/// assert_logs(
+///     &records,
+///     &[Expected::new(Level::Info, "logged in").key_value("user", "ferris")],
+/// );
+/// ```
+#[track_caller]
+pub fn assert_logs(records: &[CapturedRecord], expected: &[Expected]) {
+    if let Err(mismatch) = check_logs(records, expected) {
+        panic!("{}", mismatch);
+    }
+}
+
+/// Check that captured records match a list of expected records.
+///
+/// This is like [`assert_logs`], but returns the mismatch instead of panicking.
+pub fn check_logs(records: &[CapturedRecord], expected: &[Expected]) -> Result<(), Mismatch> {
+    let reason = if let Some((index, reason)) = records
+        .iter()
+        .zip(expected)
+        .enumerate()
+        .find_map(|(i, (record, expected))| expected.check(record).err().map(|r| (i, r)))
+    {
+        format!("record {} doesn't match: {}", index, reason)
+    } else if records.len() != expected.len() {
+        format!(
+            "expected {} records, but {} were captured",
+            expected.len(),
+            records.len()
+        )
+    } else {
+        return Ok(());
+    };
+
+    let mut report = String::new();
+    let _ = writeln!(report, "{}", reason);
+    let _ = writeln!(report, "expected:");
+    for expected in expected {
+        let _ = writeln!(report, "    {}", expected);
+    }
+    let _ = writeln!(report, "captured:");
+    for record in records {
+        let _ = writeln!(report, "    {}", record);
+    }
+
+    Err(Mismatch { report })
+}
+
+/// Captured records that didn't match the expected records.
+///
+/// Its `Display` output is a report of the expected and captured records.
+#[derive(Debug)]
+pub struct Mismatch {
+    report: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.report)
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// An expected record.
+#[derive(Clone, Debug)]
+pub struct Expected {
+    level: Level,
+    message: Pattern,
+    key_values: Vec<(String, ValuePattern)>,
+}
+
+impl Expected {
+    /// Expect a record with the given level and a message matching a pattern.
+    pub fn new(level: Level, message: impl Into<Pattern>) -> Self {
+        Expected {
+            level,
+            message: message.into(),
+            key_values: Vec::new(),
+        }
+    }
+
+    /// Expect the record to have a key-value matching `value`.
+    pub fn key_value(mut self, key: impl Into<String>, value: impl Into<ValuePattern>) -> Self {
+        self.key_values.push((key.into(), value.into()));
+        self
+    }
+
+    /// Whether a captured record matches this expectation.
+    pub fn matches(&self, record: &CapturedRecord) -> bool {
+        self.check(record).is_ok()
+    }
+
+    fn check(&self, record: &CapturedRecord) -> Result<(), String> {
+        if record.level() != self.level {
+            return Err(format!(
+                "expected level {}, found {}",
+                self.level,
+                record.level()
+            ));
+        }
+
+        if !self.message.matches(record.message()) {
+            return Err(format!(
+                "expected a message matching {:?}, found {:?}",
+                self.message.as_str(),
+                record.message()
+            ));
+        }
+
+        for (key, pattern) in &self.key_values {
+            match record.key_values().iter().find(|(k, _)| k == key) {
+                Some((_, value)) if pattern.matches(value) => {}
+                Some((_, value)) => {
+                    return Err(format!(
+                        "expected `{}` to match {}, found {}",
+                        key, pattern, value
+                    ))
+                }
+                None => return Err(format!("expected a `{}` key-value", key)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:?}", self.level, self.message.as_str())?;
+
+        for (i, (key, value)) in self.key_values.iter().enumerate() {
+            f.write_str(if i == 0 { " { " } else { ", " })?;
+            write!(f, "{}: {}", key, value)?;
+
+            if i == self.key_values.len() - 1 {
+                f.write_str(" }")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A pattern matched against a whole string, where `*` matches any run of
+/// characters, including an empty one.
+///
+/// A pattern without a `*` only matches the exact string.
+///
+/// # Examples
+///
+/// ```
+/// use log_test::Pattern;
+///
+/// let pattern = Pattern::new("retrying in *ms");
+///
+/// assert!(pattern.matches("retrying in 100ms"));
+/// assert!(!pattern.matches("retrying in 100ms (attempt 2)"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern(String);
+
+impl Pattern {
+    /// Create a pattern.
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Pattern(pattern.into())
+    }
+
+    /// Get the pattern as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the pattern matches all of `s`.
+    pub fn matches(&self, s: &str) -> bool {
+        let mut parts = self.0.split('*');
+
+        // There's always at least one part, which must be a prefix
+        let first = parts.next().unwrap_or("");
+        let mut rest = match s.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let mut parts = parts.peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                // The last part must be a suffix, after the previous parts
+                return rest.ends_with(part);
+            }
+
+            // Matching the earliest occurrence leaves the most room for the
+            // remaining parts
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+
+        // There was no `*`, so the pattern must match exactly
+        rest.is_empty()
+    }
+}
+
+impl From<&str> for Pattern {
+    fn from(pattern: &str) -> Self {
+        Pattern::new(pattern)
+    }
+}
+
+impl From<String> for Pattern {
+    fn from(pattern: String) -> Self {
+        Pattern(pattern)
+    }
+}
+
+/// An expectation for a key-value's value.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ValuePattern {
+    /// Any value.
+    Any,
+    /// A value whose `Display` output matches a pattern.
+    Display(Pattern),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl ValuePattern {
+    /// Whether a captured value matches this expectation.
+    pub fn matches(&self, value: &OwnedValue) -> bool {
+        let value = value.to_value();
+
+        match self {
+            ValuePattern::Any => true,
+            ValuePattern::Display(pattern) => pattern.matches(&value.to_string()),
+            ValuePattern::I64(expected) => value.to_i64() == Some(*expected),
+            ValuePattern::U64(expected) => value.to_u64() == Some(*expected),
+            ValuePattern::F64(expected) => value.to_f64() == Some(*expected),
+            ValuePattern::Bool(expected) => value.to_bool() == Some(*expected),
+        }
+    }
+}
+
+impl fmt::Display for ValuePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValuePattern::Any => f.write_str("*"),
+            ValuePattern::Display(pattern) => write!(f, "{:?}", pattern.as_str()),
+            ValuePattern::I64(value) => write!(f, "{}", value),
+            ValuePattern::U64(value) => write!(f, "{}", value),
+            ValuePattern::F64(value) => write!(f, "{}", value),
+            ValuePattern::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<Pattern> for ValuePattern {
+    fn from(pattern: Pattern) -> Self {
+        ValuePattern::Display(pattern)
+    }
+}
+
+impl From<&str> for ValuePattern {
+    fn from(pattern: &str) -> Self {
+        ValuePattern::Display(pattern.into())
+    }
+}
+
+impl From<String> for ValuePattern {
+    fn from(pattern: String) -> Self {
+        ValuePattern::Display(pattern.into())
+    }
+}
+
+impl From<bool> for ValuePattern {
+    fn from(value: bool) -> Self {
+        ValuePattern::Bool(value)
+    }
+}
+
+impl From<f64> for ValuePattern {
+    fn from(value: f64) -> Self {
+        ValuePattern::F64(value)
+    }
+}
+
+macro_rules! impl_from_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for ValuePattern {
+                fn from(value: $ty) -> Self {
+                    ValuePattern::I64(value.into())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for ValuePattern {
+                fn from(value: $ty) -> Self {
+                    ValuePattern::U64(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed![i8, i16, i32, i64];
+impl_from_unsigned![u8, u16, u32, u64];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_matches() {
+        let cases = [
+            ("", "", true),
+            ("", "a", false),
+            ("abc", "abc", true),
+            ("abc", "abcd", false),
+            ("*", "", true),
+            ("*", "anything", true),
+            ("a*", "abc", true),
+            ("*c", "abc", true),
+            ("*c", "abd", false),
+            ("a*c", "ac", true),
+            ("a*b*c", "a-b-b-c", true),
+            ("a*b*c", "a-c-b", false),
+            ("ab*ba", "aba", false),
+        ];
+
+        for (pattern, s, expected) in cases {
+            assert_eq!(
+                expected,
+                Pattern::new(pattern).matches(s),
+                "{:?} {:?}",
+                pattern,
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn mismatch_reports_records() {
+        let records = log::capture(|| {
+            log::warn!(attempt = 1; "retrying");
+        });
+
+        assert!(check_logs(&records, &[Expected::new(Level::Warn, "retry*")]).is_ok());
+
+        let mismatch = check_logs(
+            &records,
+            &[Expected::new(Level::Warn, "retrying").key_value("attempt", 2)],
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(mismatch.starts_with("record 0 doesn't match: expected `attempt` to match 2"));
+
+        let mismatch = check_logs(&records, &[]).unwrap_err().to_string();
+        assert!(mismatch.starts_with("expected 0 records, but 1 were captured"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected level ERROR, found WARN")]
+    fn expect_logs_panics() {
+        let records = log::capture(|| log::warn!("boom"));
+
+        expect_logs!(records, [Error "boom"]);
+    }
+
+    #[test]
+    fn expect_logs_syntax() {
+        let records = log::capture(|| {
+            log::error!(err = "boom", code = -1, ok = false, ratio = 0.5; "failed");
+            log::trace!("done");
+        });
+
+        expect_logs!(records, [
+            Error "fail*" { err: *, code: (-1), ok: false, ratio: 0.5, },
+            Trace "*",
+        ]);
+        expect_logs!(log::capture(|| {}), []);
+    }
+}
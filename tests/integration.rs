@@ -1,4 +1,7 @@
 #![allow(dead_code, unused_imports)]
+// `lib_build` is set by the build script of the `tests` crate, which runs
+// this file as a test instead of a binary
+#![allow(unknown_lints, unexpected_cfgs)]
 
 use log::{debug, error, info, trace, warn, Level, LevelFilter, Log, Metadata, Record};
 use std::sync::{Arc, Mutex};
@@ -14,21 +17,92 @@ fn set_boxed_logger(logger: Box<dyn Log>) -> Result<(), log::SetLoggerError> {
 struct State {
     last_log_level: Mutex<Option<Level>>,
     last_log_location: Mutex<Option<u32>>,
+    last_correlation_id: Mutex<Option<u64>>,
+    last_nesting: Mutex<Option<usize>>,
+    last_verbosity: Mutex<Option<u8>>,
+    last_audit_name: Mutex<Option<String>>,
+    last_dedup_key: Mutex<Option<String>>,
+    last_msg_id: Mutex<Option<&'static str>>,
+    last_message: Mutex<Option<String>>,
+    last_truncated: Mutex<bool>,
+    last_build_version: Mutex<Option<String>>,
+    last_target: Mutex<Option<(String, Option<log::TargetHandle>)>>,
+    flushed: Mutex<bool>,
+    installed: Mutex<bool>,
+    shut_down: Mutex<bool>,
 }
 
 struct Logger(Arc<State>);
 
 impl Log for Logger {
-    fn enabled(&self, _: &Metadata) -> bool {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        *self.0.last_verbosity.lock().unwrap() = Some(metadata.verbosity());
         true
     }
 
     fn log(&self, record: &Record) {
+        if record.target() == "panic" {
+            panic!("the logger failed");
+        }
+
         *self.0.last_log_level.lock().unwrap() = Some(record.level());
-        *self.0.last_log_location.lock().unwrap() = record.line();
+        *self.0.last_log_location.lock().unwrap() =
+            record.source_location().map(|location| location.line());
+        #[cfg(feature = "correlation")]
+        {
+            *self.0.last_correlation_id.lock().unwrap() = record.correlation_id();
+        }
+        #[cfg(feature = "nesting")]
+        {
+            *self.0.last_nesting.lock().unwrap() = Some(record.nesting());
+        }
+        *self.0.last_verbosity.lock().unwrap() = Some(record.metadata().verbosity());
+        *self.0.last_audit_name.lock().unwrap() = record.metadata().audit_name().map(Into::into);
+        *self.0.last_dedup_key.lock().unwrap() = record.metadata().dedup_key().map(Into::into);
+        *self.0.last_msg_id.lock().unwrap() = record.metadata().msg_id();
+        *self.0.last_message.lock().unwrap() = Some(record.args().to_string());
+        #[cfg(feature = "kv")]
+        {
+            *self.0.last_truncated.lock().unwrap() = record
+                .key_values()
+                .get(log::kv::keys::TRUNCATED)
+                .and_then(|v| v.to_bool())
+                .unwrap_or(false);
+            *self.0.last_build_version.lock().unwrap() = record
+                .key_values()
+                .get(log::kv::keys::BUILD_VERSION)
+                .map(|v| v.to_string());
+        }
+        *self.0.last_target.lock().unwrap() =
+            Some((record.target().into(), record.metadata().target_handle()));
+    }
+    fn flush(&self) {
+        *self.0.flushed.lock().unwrap() = true;
+    }
+    fn on_install(&self) {
+        // The logger is installed before it's passed any records
+        assert_eq!(None, *self.0.last_log_level.lock().unwrap());
+        *self.0.installed.lock().unwrap() = true;
+    }
+    fn on_shutdown(&self) {
+        assert!(*self.0.flushed.lock().unwrap());
+        *self.0.shut_down.lock().unwrap() = true;
+    }
+}
+
+struct PanicsOnInstall;
+
+impl Log for PanicsOnInstall {
+    fn enabled(&self, _: &Metadata) -> bool {
+        false
     }
+    fn log(&self, _: &Record) {}
     fn flush(&self) {}
+    fn on_install(&self) {
+        panic!("failed to install");
+    }
 }
+
 #[cfg_attr(lib_build, test)]
 fn main() {
     // These tests don't really make sense when static
@@ -51,9 +125,40 @@ fn main() {
         let me = Arc::new(State {
             last_log_level: Mutex::new(None),
             last_log_location: Mutex::new(None),
+            last_correlation_id: Mutex::new(None),
+            last_nesting: Mutex::new(None),
+            last_verbosity: Mutex::new(None),
+            last_audit_name: Mutex::new(None),
+            last_dedup_key: Mutex::new(None),
+            last_msg_id: Mutex::new(None),
+            last_message: Mutex::new(None),
+            last_truncated: Mutex::new(false),
+            last_build_version: Mutex::new(None),
+            last_target: Mutex::new(None),
+            flushed: Mutex::new(false),
+            installed: Mutex::new(false),
+            shut_down: Mutex::new(false),
         });
         let a = me.clone();
+
+        // Logging is off until the max level is raised, whatever features are enabled
+        assert_eq!(LevelFilter::Off, log::max_level());
+
+        test_diagnostics_before_init();
+
+        // A logger that panics while it's installed can be replaced
+        assert!(std::panic::catch_unwind(|| log::set_logger(&PanicsOnInstall)).is_err());
+        #[cfg(feature = "std")]
+        {
+            assert!(std::panic::catch_unwind(|| log::init_with(|| PanicsOnInstall)).is_err());
+            assert!(std::panic::catch_unwind(|| {
+                log::init_with(|| -> PanicsOnInstall { panic!("failed to make the logger") })
+            })
+            .is_err());
+        }
         set_boxed_logger(Box::new(Logger(me))).unwrap();
+        assert!(*a.installed.lock().unwrap());
+        test_diagnostics_after_init(&a);
 
         test_filter(&a, LevelFilter::Off);
         test_filter(&a, LevelFilter::Error);
@@ -62,10 +167,75 @@ fn main() {
         test_filter(&a, LevelFilter::Debug);
         test_filter(&a, LevelFilter::Trace);
 
+        test_max_level_epoch();
+        test_max_level_ordering();
+
+        #[cfg(not(feature = "no_location"))]
         test_line_numbers(&a);
+        #[cfg(not(feature = "no_location"))]
+        test_callsite(&a);
+        #[cfg(feature = "no_location")]
+        test_no_location(&a);
+        #[cfg(feature = "correlation")]
+        test_correlation_id(&a);
+        #[cfg(feature = "nesting")]
+        test_nesting(&a);
+        #[cfg(feature = "std")]
+        test_capture(&a);
+        #[cfg(feature = "std")]
+        test_failure_handler(&a);
+        test_verbosity(&a);
+        test_dedup_key(&a);
+        test_msg_id(&a);
+        test_limits(&a);
+        test_build_info(&a);
+        test_logger_for();
+        test_log_record(&a);
+        #[cfg(feature = "std")]
+        test_target_handle(&a);
+        #[cfg(feature = "kv")]
+        test_audit(&a);
+
+        // This test must run last, since it shuts down the logging system
+        #[cfg(feature = "std")]
+        test_shutdown(&a);
     }
 }
 
+// Records logged before initialization are buffered rather than dropped
+// when the `early_buffer` feature is enabled
+const EXPECTED_DROPPED: usize = if cfg!(feature = "early_buffer") { 0 } else { 2 };
+
+fn test_diagnostics_before_init() {
+    log::set_max_level(LevelFilter::Trace);
+
+    // Submitted records aren't counted until diagnostics are enabled
+    info!("uncounted");
+    assert_eq!(log::diagnostics().submitted(), 0);
+    log::enable_diagnostics();
+
+    info!("lost");
+
+    let diagnostics = log::diagnostics();
+    assert_eq!(diagnostics.submitted(), 1);
+    assert_eq!(diagnostics.dropped(), EXPECTED_DROPPED);
+    assert_eq!(diagnostics.before_init(), 2);
+}
+
+fn test_diagnostics_after_init(a: &State) {
+    // The buffered record is replayed when the logger is installed
+    #[cfg(feature = "early_buffer")]
+    assert_eq!(a.last_log_level.lock().unwrap().take(), Some(Level::Info));
+
+    info!("kept");
+    assert_eq!(a.last_log_level.lock().unwrap().take(), Some(Level::Info));
+
+    let diagnostics = log::diagnostics();
+    assert_eq!(diagnostics.submitted(), 2);
+    assert_eq!(diagnostics.dropped(), EXPECTED_DROPPED);
+    assert_eq!(diagnostics.before_init(), 2);
+}
+
 fn test_filter(a: &State, filter: LevelFilter) {
     // tests to ensure logs with a level beneath 'max_level' are filtered out
     log::set_max_level(filter);
@@ -93,6 +263,43 @@ fn test_filter(a: &State, filter: LevelFilter) {
     }
 }
 
+fn test_max_level_epoch() {
+    let (_, epoch) = log::max_level_epoch();
+
+    log::set_max_level(LevelFilter::Warn);
+    let (level, warn_epoch) = log::max_level_epoch();
+    assert_eq!(level, LevelFilter::Warn);
+    assert!(warn_epoch > epoch);
+
+    // The epoch only changes when the level is set
+    assert_eq!(log::max_level_epoch(), (LevelFilter::Warn, warn_epoch));
+}
+
+// A thread that sees a level set by another thread also sees what that thread
+// wrote before setting it
+fn test_max_level_ordering() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CONFIG: AtomicUsize = AtomicUsize::new(0);
+
+    for i in 1..100 {
+        log::set_max_level(LevelFilter::Off);
+
+        let setter = std::thread::spawn(move || {
+            CONFIG.store(i, Ordering::Relaxed);
+            log::set_max_level(LevelFilter::Debug);
+        });
+
+        while log::max_level() != LevelFilter::Debug {
+            std::hint::spin_loop();
+        }
+        assert_eq!(i, CONFIG.load(Ordering::Relaxed));
+        assert_eq!(LevelFilter::Debug, log::max_level_relaxed());
+
+        setter.join().unwrap();
+    }
+}
+
 fn test_line_numbers(state: &State) {
     log::set_max_level(LevelFilter::Trace);
 
@@ -106,3 +313,451 @@ fn test_line_numbers(state: &State) {
         assert_eq!(line_number, location - 1);
     }
 }
+
+fn test_callsite(state: &State) {
+    log::set_max_level(LevelFilter::Info);
+
+    info!(module_path: "my_app::server", file: "src/server.rs", line: 42, "wrapped");
+    assert_eq!(state.last_log_location.lock().unwrap().take(), Some(42));
+    assert_eq!(
+        state
+            .last_target
+            .lock()
+            .unwrap()
+            .take()
+            .map(|(target, _)| target),
+        Some("my_app::server".into())
+    );
+
+    info!(module_path: "my_app::server", file: "src/server.rs", line: 42, target: "requests", "wrapped");
+    assert_eq!(
+        state
+            .last_target
+            .lock()
+            .unwrap()
+            .take()
+            .map(|(target, _)| target),
+        Some("requests".into())
+    );
+}
+
+fn test_no_location(state: &State) {
+    log::set_max_level(LevelFilter::Info);
+
+    info!("no location");
+    assert_eq!(
+        state.last_log_level.lock().unwrap().take(),
+        Some(Level::Info)
+    );
+    assert_eq!(state.last_log_location.lock().unwrap().take(), None);
+}
+
+fn test_log_record(state: &State) {
+    log::set_max_level(LevelFilter::Info);
+
+    fn record(level: Level, audit: Option<&str>, log: fn(&Record)) {
+        let mut extras = log::MetadataExtras::new();
+        extras.audit(audit);
+
+        log(&Record::builder()
+            .args(format_args!("bridged"))
+            .level(level)
+            .target("bridge")
+            .extras(Some(&extras))
+            .build());
+    }
+
+    for log in [log::log_record, log::log_record_skip_enabled] {
+        record(Level::Warn, None, log);
+        assert_eq!(
+            state.last_log_level.lock().unwrap().take(),
+            Some(Level::Warn)
+        );
+
+        record(Level::Debug, None, log);
+        assert_eq!(state.last_log_level.lock().unwrap().take(), None);
+
+        // Audit records skip the level checks
+        record(Level::Debug, Some("bridge.audit"), log);
+        assert_eq!(
+            state.last_log_level.lock().unwrap().take(),
+            Some(Level::Debug)
+        );
+    }
+
+    for level in [Level::Warn, Level::Debug] {
+        let metadata = log::Metadata::builder()
+            .level(level)
+            .target("bridge")
+            .build();
+
+        log::log!(metadata: metadata, "bridged");
+        let expected = Some(level).filter(|level| *level <= Level::Info);
+        assert_eq!(state.last_log_level.lock().unwrap().take(), expected);
+
+        log::log!(record: Record::builder()
+            .args(format_args!("bridged"))
+            .metadata(metadata)
+            .build());
+        assert_eq!(state.last_log_level.lock().unwrap().take(), expected);
+    }
+}
+
+#[cfg(feature = "correlation")]
+fn test_correlation_id(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    log::with_correlation_id(42, || info!(""));
+    assert_eq!(state.last_correlation_id.lock().unwrap().take(), Some(42));
+
+    info!("");
+    assert_eq!(state.last_correlation_id.lock().unwrap().take(), None);
+
+    state.last_log_level.lock().unwrap().take();
+}
+
+#[cfg(feature = "std")]
+fn test_capture(state: &State) {
+    log::set_max_level(LevelFilter::Warn);
+    state.last_log_level.lock().unwrap().take();
+
+    let records = log::capture(|| {
+        debug!("captured");
+        log::log_record(
+            &Record::builder()
+                .args(format_args!("bridged"))
+                .level(Level::Trace)
+                .build(),
+        );
+    });
+
+    // Captured records aren't passed to the global logger
+    assert_eq!(state.last_log_level.lock().unwrap().take(), None);
+
+    let messages = records
+        .iter()
+        .map(|record| record.message())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["captured", "bridged"], messages);
+
+    debug!("");
+    assert_eq!(state.last_log_level.lock().unwrap().take(), None);
+}
+
+#[cfg(feature = "std")]
+fn test_failure_handler(state: &State) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+    fn handler(failure: &log::LoggerFailure) {
+        assert_eq!(Some("the logger failed"), failure.message());
+        assert_eq!(Level::Error, failure.level());
+        assert_eq!("panic", failure.target());
+
+        FAILURES.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // The panics are expected, so don't print them
+    std::panic::set_hook(Box::new(|_| {}));
+
+    log::set_max_level(LevelFilter::Trace);
+    log::set_failure_handler(handler);
+
+    let before = log::diagnostics().logger_panics();
+
+    error!(target: "panic", "");
+    log::log_record(
+        &Record::builder()
+            .level(Level::Error)
+            .target("panic")
+            .build(),
+    );
+
+    assert_eq!(2, FAILURES.load(Ordering::Relaxed));
+    assert_eq!(before + 2, log::diagnostics().logger_panics());
+
+    // De-duplicated records and their summaries are caught too
+    log::dedup::enable(std::time::Duration::from_secs(60));
+    error!(target: "panic", "");
+    error!(target: "panic", "");
+    log::dedup::disable();
+
+    assert_eq!(4, FAILURES.load(Ordering::Relaxed));
+
+    // Other records are still logged
+    error!("");
+    assert_eq!(
+        state.last_log_level.lock().unwrap().take(),
+        Some(Level::Error)
+    );
+
+    log::clear_failure_handler();
+    assert!(std::panic::catch_unwind(|| error!(target: "panic", "")).is_err());
+    assert_eq!(4, FAILURES.load(Ordering::Relaxed));
+
+    let _ = std::panic::take_hook();
+}
+
+fn test_verbosity(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    trace!("");
+    assert_eq!(state.last_verbosity.lock().unwrap().take(), Some(0));
+
+    trace!(v: 3, "");
+    assert_eq!(state.last_verbosity.lock().unwrap().take(), Some(3));
+
+    trace!(v: 2, target: "my_target", "");
+    assert_eq!(state.last_verbosity.lock().unwrap().take(), Some(2));
+
+    log::log!(v: 1, Level::Debug, "");
+    assert_eq!(state.last_verbosity.lock().unwrap().take(), Some(1));
+
+    // Loggers see the same verbosity when asked whether a message is enabled
+    assert!(log::log_enabled!(v: 3, Level::Trace));
+    assert_eq!(state.last_verbosity.lock().unwrap().take(), Some(3));
+}
+
+fn test_dedup_key(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    warn!("disk full");
+    assert_eq!(state.last_dedup_key.lock().unwrap().take(), None);
+
+    warn!(dedup: "disk_full:/dev/sda1", "disk full");
+    assert_eq!(
+        state.last_dedup_key.lock().unwrap().take().as_deref(),
+        Some("disk_full:/dev/sda1")
+    );
+
+    let device = String::from("/dev/sdb1");
+    let key = format!("disk_full:{}", device);
+    error!(dedup: &key, target: "disks", "disk {} full", device);
+    assert_eq!(
+        state.last_dedup_key.lock().unwrap().take().as_deref(),
+        Some("disk_full:/dev/sdb1")
+    );
+
+    log::log!(dedup: "disk_full", Level::Debug, "disk full");
+    assert_eq!(
+        state.last_dedup_key.lock().unwrap().take().as_deref(),
+        Some("disk_full")
+    );
+}
+
+fn test_msg_id(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    warn!("user logged in");
+    assert_eq!(state.last_msg_id.lock().unwrap().take(), None);
+
+    warn!(id: "user.login.success", "user {} logged in", "chashu");
+    assert_eq!(
+        state.last_msg_id.lock().unwrap().take(),
+        Some("user.login.success")
+    );
+
+    error!(id: "user.login.failure", target: "auth", "user {} failed to log in", "chashu");
+    assert_eq!(
+        state.last_msg_id.lock().unwrap().take(),
+        Some("user.login.failure")
+    );
+
+    log::log!(id: "user.logout", Level::Debug, "user logged out");
+    assert_eq!(
+        state.last_msg_id.lock().unwrap().take(),
+        Some("user.logout")
+    );
+}
+
+fn test_limits(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+    log::set_limits(log::Limits {
+        max_msg_len: Some(8),
+        max_kvs: Some(1),
+    });
+
+    info!("short");
+    assert_eq!(
+        state.last_message.lock().unwrap().take().as_deref(),
+        Some("short")
+    );
+    assert!(!*state.last_truncated.lock().unwrap());
+
+    info!("{} is too long", "this message");
+    assert_eq!(
+        state.last_message.lock().unwrap().take().as_deref(),
+        Some("this mes")
+    );
+    #[cfg(feature = "kv")]
+    assert!(*state.last_truncated.lock().unwrap());
+
+    #[cfg(feature = "kv")]
+    {
+        info!(a = 1; "short");
+        assert!(!*state.last_truncated.lock().unwrap());
+
+        info!(a = 1, b = 2; "short");
+        assert!(*state.last_truncated.lock().unwrap());
+    }
+
+    // Each limit applies without the other
+    log::set_limits(log::Limits {
+        max_kvs: Some(1),
+        ..log::Limits::default()
+    });
+
+    info!("{} isn't too long", "this message");
+    assert_eq!(
+        state.last_message.lock().unwrap().take().as_deref(),
+        Some("this message isn't too long")
+    );
+
+    #[cfg(feature = "kv")]
+    {
+        info!(a = 1, b = 2; "short");
+        assert!(*state.last_truncated.lock().unwrap());
+    }
+
+    log::set_limits(log::Limits::default());
+
+    info!("{} isn't too long anymore", "this message");
+    assert_eq!(
+        state.last_message.lock().unwrap().take().as_deref(),
+        Some("this message isn't too long anymore")
+    );
+    assert!(!*state.last_truncated.lock().unwrap());
+}
+
+fn test_build_info(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    info!("before");
+    assert_eq!(state.last_build_version.lock().unwrap().take(), None);
+
+    let build_info = log::BuildInfo {
+        version: "1.2.3",
+        git_sha: Some("0123abc"),
+        profile: Some("release"),
+    };
+    log::set_build_info(build_info).unwrap();
+    assert!(log::set_build_info(build_info).is_err());
+    assert_eq!(Some(build_info), log::build_info());
+
+    info!("after");
+    #[cfg(feature = "global-fields")]
+    assert_eq!(
+        state.last_build_version.lock().unwrap().take().as_deref(),
+        Some("1.2.3")
+    );
+    #[cfg(not(feature = "global-fields"))]
+    assert_eq!(state.last_build_version.lock().unwrap().take(), None);
+}
+
+#[cfg(feature = "nesting")]
+fn test_nesting(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    {
+        let _outer = log::indent_scope();
+        let _inner = log::indent_scope();
+        info!("");
+        assert_eq!(state.last_nesting.lock().unwrap().take(), Some(2));
+    }
+
+    info!("");
+    assert_eq!(state.last_nesting.lock().unwrap().take(), Some(0));
+
+    state.last_log_level.lock().unwrap().take();
+}
+
+#[cfg(feature = "kv")]
+fn test_audit(state: &State) {
+    // Audit records aren't subject to the maximum log level
+    log::set_max_level(LevelFilter::Off);
+
+    log::audit!(name: "user.login", user = "ferris"; "user logged in");
+    assert_eq!(
+        state.last_log_level.lock().unwrap().take(),
+        Some(Level::Info)
+    );
+    assert_eq!(
+        state.last_audit_name.lock().unwrap().take().as_deref(),
+        Some("user.login")
+    );
+}
+
+#[cfg(feature = "std")]
+fn test_target_handle(state: &State) {
+    log::set_max_level(LevelFilter::Trace);
+
+    let handle = log::register_target("plugins::resize");
+
+    info!(target: handle, "");
+    assert_eq!(
+        state.last_target.lock().unwrap().take(),
+        Some(("plugins::resize".into(), Some(handle)))
+    );
+
+    info!(target: "plugins::resize", "");
+    assert_eq!(
+        state.last_target.lock().unwrap().take(),
+        Some(("plugins::resize".into(), None))
+    );
+
+    state.last_log_level.lock().unwrap().take();
+}
+
+fn test_logger_for() {
+    let l = log::logger_for("my_target");
+    assert_eq!("my_target", l.target());
+
+    log::set_max_level(LevelFilter::Info);
+    assert!(l.enabled(Level::Info));
+    assert!(!l.enabled(Level::Debug));
+
+    // Changing the max level invalidates the cached results
+    log::set_max_level(LevelFilter::Warn);
+    assert!(!l.enabled(Level::Info));
+    assert!(l.enabled(Level::Warn));
+
+    log::set_max_level(LevelFilter::Trace);
+    assert!(l.enabled(Level::Trace));
+}
+
+#[cfg(feature = "std")]
+fn test_shutdown(a: &State) {
+    use std::time::Duration;
+
+    log::set_max_level(LevelFilter::Trace);
+    a.last_log_level.lock().unwrap().take();
+
+    let report = log::try_flush_and_shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(0, report.late_records());
+    assert!(*a.flushed.lock().unwrap());
+    assert!(*a.shut_down.lock().unwrap());
+    assert_eq!(LevelFilter::Off, log::max_level());
+
+    // Records aren't logged after shutdown, even if the max level is raised again
+    log::set_max_level(LevelFilter::Trace);
+    error!("");
+    log::logger_for("my_target").log(&Record::builder().level(Level::Error).build());
+    assert_eq!(None, a.last_log_level.lock().unwrap().take());
+
+    let report = log::try_flush_and_shutdown(Duration::from_secs(5)).unwrap();
+    assert_eq!(2, report.late_records());
+
+    // Records can still be captured
+    let records = log::capture(|| error!("captured"));
+    assert_eq!("captured", records[0].message());
+
+    let records =
+        log::capture(|| log::log_record(&Record::builder().args(format_args!("captured")).build()));
+    assert_eq!("captured", records[0].message());
+
+    let records = log::capture(|| {
+        log::logger_for("my_target").log(&Record::builder().args(format_args!("captured")).build())
+    });
+    assert_eq!("captured", records[0].message());
+}
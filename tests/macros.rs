@@ -10,6 +10,32 @@ macro_rules! all_log_macros {
     });
 }
 
+// Logs key-values named like one of the macros' options, captured with each
+// sigil, which mustn't be mistaken for the option.
+#[cfg(feature = "kv")]
+macro_rules! kv_named_option {
+    ($key:ident) => {{
+        let $key = 42;
+
+        all_log_macros!($key:value = $key, other = 1; "hello");
+        all_log_macros!($key:value, other = 1; "hello");
+        all_log_macros!($key:? = $key, other = 1; "hello");
+        all_log_macros!($key:debug = $key, other = 1; "hello");
+        all_log_macros!($key:% = $key, other = 1; "hello");
+        all_log_macros!($key:display = $key, other = 1; "hello");
+        all_log_macros!($key:x = $key, other = 1; "hello");
+        all_log_macros!($key:X = $key, other = 1; "hello");
+        all_log_macros!($key:o = $key, other = 1; "hello");
+        all_log_macros!($key:b = $key, other = 1; "hello");
+        #[cfg(feature = "kv_std")]
+        all_log_macros!($key:err = std::io::Error::new(std::io::ErrorKind::Other, "an error"), other = 1; "hello");
+        #[cfg(feature = "kv_sval")]
+        all_log_macros!($key:sval = $key, other = 1; "hello");
+        #[cfg(feature = "kv_serde")]
+        all_log_macros!($key:serde = $key, other = 1; "hello");
+    }};
+}
+
 #[test]
 fn no_args() {
     for lvl in log::Level::iter() {
@@ -92,10 +118,40 @@ fn inlined_args() {
     all_log_macros!(target: "my_target", "hello {world}",);
 }
 
+#[test]
+fn verbosity() {
+    let level = 4u8;
+
+    for lvl in log::Level::iter() {
+        log!(v: 1, lvl, "hello");
+        log!(v: level, target: "my_target", lvl, "hello {}", "world");
+    }
+
+    log::trace!(v: 2, "hello");
+    log::trace!(v: level, target: "my_target", "hello {}", "world");
+    log::trace!(v: level, "hello");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_named_v() {
+    let v = 4;
+
+    all_log_macros!(v:display, other = 1; "hello");
+    all_log_macros!(v:debug, other = 1; "hello");
+    all_log_macros!(v:display = v, other = 1; "hello");
+
+    kv_named_option!(v);
+}
+
 #[test]
 fn enabled() {
     for lvl in log::Level::iter() {
         let _enabled = log_enabled!(target: "my_target", lvl);
+        let _enabled = log_enabled!(kind: log::RecordKind::Event, lvl);
+        let _enabled = log_enabled!(kind: log::RecordKind::Event, target: "my_target", lvl);
+        let _enabled = log_enabled!(v: 3, lvl);
+        let _enabled = log_enabled!(v: 3, target: "my_target", lvl);
     }
 }
 
@@ -120,6 +176,38 @@ fn kv_no_args() {
     all_log_macros!(cat_1 = "chashu", cat_2 = "nori", cat_count = 2; "hello");
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn kv_no_message() {
+    for lvl in log::Level::iter() {
+        log!(target: "my_target", lvl, cat_1 = "chashu", cat_2 = "nori", cat_count = 2;);
+
+        log!(lvl, cat_1 = "chashu", cat_2 = "nori", cat_count = 2;);
+    }
+
+    all_log_macros!(target: "my_target", cat_1 = "chashu", cat_2 = "nori", cat_count = 2;);
+    all_log_macros!(cat_1 = "chashu", cat_2:? = "nori";);
+    all_log_macros!(sample: 1.0, cat_1 = "chashu";);
+}
+
+#[test]
+#[cfg(feature = "kv_std")]
+fn kv_no_message_record() {
+    use log::kv::Source;
+
+    let records = log::capture(|| {
+        log::info!(user_id = 42, action = "login";);
+    });
+
+    assert_eq!("", records[0].message());
+    let kvs = records[0].key_values();
+    assert_eq!(2, kvs.count());
+    assert_eq!(
+        Some(42),
+        Source::get(kvs, "user_id".into()).and_then(|v| v.to_i64())
+    );
+}
+
 #[test]
 #[cfg(feature = "kv")]
 fn kv_expr_args() {
@@ -184,6 +272,37 @@ fn kv_expr_context() {
     };
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn audit() {
+    let user = "ferris";
+
+    log::audit!(name: "user.login", user; "hello");
+    log::audit!(name: "user.login", user, outcome = "success"; "hello {}", "world");
+    log::audit!(target: "security", name: "user.login", user:%, "outcome" = "success"; "hello {world}", world = "world");
+}
+
+#[test]
+#[cfg(not(feature = "kv"))]
+fn kv_stripped() {
+    fn not_evaluated() -> i32 {
+        panic!("key-values must not be evaluated without the `kv` feature")
+    }
+
+    let cat_1 = "chashu";
+
+    // Make sure the macros would evaluate their arguments
+    log::set_max_level(log::LevelFilter::Trace);
+
+    for lvl in log::Level::iter() {
+        log!(target: "my_target", lvl, cat_1, cat_2:% = "nori", cat_count = not_evaluated(); "hello");
+        log!(lvl, "also dogs" = "Fílos", cat_count = not_evaluated(); "hello {}", "world");
+    }
+
+    all_log_macros!(target: "my_target", cat_1, cat_count = not_evaluated(); "hello");
+    all_log_macros!(cat_1:?, cat_count = not_evaluated(); "hello {world}", world = "world");
+}
+
 #[test]
 fn implicit_named_args() {
     let world = "world";
@@ -279,6 +398,25 @@ fn kv_display() {
     );
 }
 
+#[test]
+#[cfg(feature = "kv")]
+fn kv_format_hint() {
+    all_log_macros!(
+        a:x = 42,
+        b:X = 42,
+        c:o = 42,
+        d:b = 42;
+        "hello world"
+    );
+
+    // Key-values named like a macro option are still key-values
+    all_log_macros!(id:x = 42, other = 1; "hello world");
+    all_log_macros!(kind:X = 42, other = 1; "hello world");
+    all_log_macros!(sample:o = 42, other = 1; "hello world");
+    all_log_macros!(dedup:b = 42, other = 1; "hello world");
+    log::trace!(v:x = 42, other = 1; "hello world");
+}
+
 #[test]
 #[cfg(feature = "kv_std")]
 fn kv_error() {
@@ -288,6 +426,103 @@ fn kv_error() {
     );
 }
 
+#[test]
+#[cfg(feature = "kv_std")]
+fn log_err() {
+    use log::log_err;
+
+    fn fails() -> Result<(), std::io::Error> {
+        Err(std::io::Error::new(std::io::ErrorKind::Other, "an error"))
+    }
+
+    assert!(log_err!(fails()).is_err());
+    assert!(log_err!(fails(),).is_err());
+    assert!(log_err!(fails(), "context {}", 1).is_err());
+    assert!(log_err!(target: "my_target", fails()).is_err());
+    assert!(log_err!(target: "my_target", fails(), "context").is_err());
+
+    let ok: Result<i32, std::io::Error> = Ok(42);
+    assert_eq!(42, log_err!(ok).unwrap());
+
+    fn fails_boxed() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("an error".into())
+    }
+
+    assert!(log_err!(fails_boxed()).is_err());
+    assert!(log_err!(fails_boxed(), "context {}", 1).is_err());
+
+    let boxed: Result<(), Box<dyn std::error::Error>> = Err("an error".into());
+    assert!(log_err!(boxed).is_err());
+}
+
+#[test]
+fn logv() {
+    use log::{debugv, errorv, infov, logv, tracev, warnv};
+
+    let x = 21;
+
+    for lvl in log::Level::iter() {
+        assert_eq!(42, logv!(lvl, x * 2));
+        assert_eq!(42, logv!(lvl, x * 2,));
+        assert_eq!(42, logv!(lvl, "{} is {:x}", x * 2));
+        assert_eq!(42, logv!(target: "my_target", lvl, x * 2));
+        assert_eq!(42, logv!(target: "my_target", lvl, "{} is {:x}", x * 2));
+    }
+
+    assert_eq!(42, errorv!(x * 2));
+    assert_eq!(42, warnv!(x * 2));
+    assert_eq!(42, infov!("{}: {:?}", x * 2));
+    assert_eq!(42, debugv!(target: "my_target", x * 2));
+    assert_eq!(42, tracev!(target: "my_target", "{}: {:?}", x * 2));
+
+    // The value is moved through, not copied
+    let s = debugv!(String::from("a string"));
+    assert_eq!("a string", s);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn logv_message() {
+    let records = log::capture(|| {
+        let x = 21;
+
+        log::infov!(x * 2);
+        log::infov!("{} is {:#x}", x * 2);
+    });
+
+    assert_eq!("x * 2 = 42", records[0].message());
+    assert_eq!("x * 2 is 0x2a", records[1].message());
+}
+
+#[test]
+#[cfg(feature = "backtrace")]
+fn backtrace() {
+    use log::kv::{keys, Source};
+    use std::backtrace::{Backtrace, BacktraceStatus};
+
+    // Whether backtraces are enabled is cached by the first capture, which
+    // may have happened in another test already
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    let enabled = Backtrace::capture().status() == BacktraceStatus::Captured;
+
+    let records = log::capture(|| {
+        log::error!(a = 1; "failed");
+        log::warn!("retrying");
+    });
+
+    let backtrace = Source::get(records[0].key_values(), keys::BACKTRACE);
+    assert_eq!(enabled, backtrace.is_some());
+    if let Some(backtrace) = backtrace {
+        assert!(backtrace.to_string().contains("backtrace"));
+    }
+    assert_eq!(
+        Some(1),
+        Source::get(records[0].key_values(), "a".into()).and_then(|v| v.to_i64())
+    );
+
+    assert!(Source::get(records[1].key_values(), keys::BACKTRACE).is_none());
+}
+
 #[test]
 #[cfg(feature = "kv_sval")]
 fn kv_sval() {
@@ -318,3 +553,285 @@ fn regression_issue_494() {
     use self::Type::*;
     all_log_macros!("some message: {:?}, {:?}", None, Some);
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn target_handle() {
+    let handle = log::register_target("plugins::resize");
+    let owned = String::from("plugins::resize");
+
+    all_log_macros!(target: handle, "hello");
+    all_log_macros!(target: &owned, "hello");
+    all_log_macros!(target: handle, key = 1; "hello");
+
+    let _ = log_enabled!(target: handle, log::Level::Info);
+    let _ = log_enabled!(target: &owned, log::Level::Info);
+}
+
+#[test]
+fn kind() {
+    use log::RecordKind;
+
+    for lvl in log::Level::iter() {
+        log!(kind: RecordKind::Progress, lvl, "hello");
+        log!(kind: RecordKind::Progress, target: "my_target", lvl, "hello");
+        log!(kind: RecordKind::Metric, target: "my_target", lvl, value = 1; "hello");
+    }
+
+    all_log_macros!(kind: RecordKind::Event, "hello");
+    all_log_macros!(kind: RecordKind::Event, "hello {}", 1);
+    all_log_macros!(kind: RecordKind::Event, target: "my_target", "hello");
+    all_log_macros!(kind: RecordKind::Metric, target: "my_target", value = 1; "hello");
+
+    const KIND: RecordKind = RecordKind::Event;
+    all_log_macros!(kind: KIND, "hello");
+    all_log_macros!(kind: KIND, target: "my_target", value = 1; "hello");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_named_kind() {
+    let kind = "login";
+
+    all_log_macros!(kind:debug, other = 1; "hello");
+    all_log_macros!(kind:display, other = 1; "hello");
+    all_log_macros!(kind:debug = kind, other = 1; "hello");
+
+    kv_named_option!(kind);
+}
+
+#[test]
+fn sample() {
+    for lvl in log::Level::iter() {
+        log!(sample: 0.5, lvl, "hello");
+        log!(sample: 0.5, target: "my_target", lvl, "hello");
+        log!(sample: 0.5, target: "my_target", lvl, value = 1; "hello");
+    }
+
+    all_log_macros!(sample: 0.5, "hello");
+    all_log_macros!(sample: 0.5, "hello {}", 1);
+    all_log_macros!(sample: 0.5, target: "my_target", "hello");
+    all_log_macros!(sample: 0.5, target: "my_target", value = 1; "hello");
+
+    let rate = 0.5;
+    all_log_macros!(sample: rate, "hello");
+    all_log_macros!(sample: rate, target: "my_target", value = 1; "hello");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_named_sample() {
+    let sample = 0.5;
+
+    all_log_macros!(sample:display, other = 1; "hello");
+    all_log_macros!(sample:debug, other = 1; "hello");
+    all_log_macros!(sample:display = sample, other = 1; "hello");
+
+    kv_named_option!(sample);
+}
+
+#[test]
+fn dedup() {
+    for lvl in log::Level::iter() {
+        log!(dedup: "my_key", lvl, "hello");
+        log!(dedup: "my_key", target: "my_target", lvl, "hello");
+        log!(dedup: "my_key", target: "my_target", lvl, value = 1; "hello");
+    }
+
+    all_log_macros!(dedup: "my_key", "hello");
+    all_log_macros!(dedup: "my_key", "hello {}", 1);
+    all_log_macros!(dedup: "my_key", target: "my_target", "hello");
+    all_log_macros!(dedup: "my_key", target: "my_target", value = 1; "hello");
+
+    let key = "my_key";
+    all_log_macros!(dedup: key, "hello");
+    all_log_macros!(dedup: key, target: "my_target", value = 1; "hello");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_named_dedup() {
+    let dedup = "my_key";
+
+    all_log_macros!(dedup:display, other = 1; "hello");
+    all_log_macros!(dedup:debug, other = 1; "hello");
+    all_log_macros!(dedup:display = dedup, other = 1; "hello");
+
+    kv_named_option!(dedup);
+}
+
+#[test]
+fn id() {
+    for lvl in log::Level::iter() {
+        log!(id: "my_id", lvl, "hello");
+        log!(id: "my_id", target: "my_target", lvl, "hello");
+        log!(id: "my_id", target: "my_target", lvl, value = 1; "hello");
+    }
+
+    all_log_macros!(id: "my_id", "hello");
+    all_log_macros!(id: "my_id", "hello {}", 1);
+    all_log_macros!(id: "my_id", target: "my_target", "hello");
+    all_log_macros!(id: "my_id", target: "my_target", value = 1; "hello");
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn kv_named_id() {
+    let id = 1;
+
+    all_log_macros!(id = 1; "hello");
+    all_log_macros!(id:? = id; "hello");
+    all_log_macros!(id:% = id; "hello");
+    all_log_macros!(id; "hello");
+    all_log_macros!(id:display, other = 1; "hello");
+    all_log_macros!(id:debug, other = 1; "hello");
+    all_log_macros!(id:display = id, other = 1; "hello");
+    all_log_macros!(id:value = 1; "hello");
+
+    kv_named_option!(id);
+}
+
+#[test]
+#[cfg(feature = "kv_std")]
+fn kv_named_id_record() {
+    use log::kv::Source;
+
+    let id = 42;
+    let x = "chashu";
+
+    let records = log::capture(|| {
+        log::info!(id:display, x; "hello");
+    });
+
+    let kvs = records[0].key_values();
+    assert_eq!(2, kvs.count());
+    assert_eq!(
+        Some("42".to_owned()),
+        Source::get(kvs, "id".into()).map(|v| v.to_string())
+    );
+}
+
+#[test]
+fn id_ident() {
+    const ID: &str = "my_id";
+
+    all_log_macros!(id: ID, "hello");
+    all_log_macros!(id: ID, target: "my_target", "hello {}", 1);
+}
+
+#[test]
+fn callsite() {
+    for lvl in log::Level::iter() {
+        log!(module_path: "my_app", file: "src/main.rs", line: 1, lvl, "hello");
+        log!(module_path: "my_app", file: "src/main.rs", line: 1, target: "my_target", lvl, value = 1; "hello");
+        log!(module_path: "my_app", file: "src/main.rs", line: 1, sample: 0.5, lvl, "hello");
+        log!(module_path: "my_app", file: "src/main.rs", line: 1, kind: log::RecordKind::Event, lvl, "hello");
+    }
+
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), "hello");
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), "hello {}", 1);
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), value = 1; "hello");
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), target: "my_target", "hello");
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), sample: 0.5, "hello");
+    all_log_macros!(module_path: module_path!(), file: file!(), line: line!(), sample: 0.5, target: "my_target", value = 1; "hello");
+}
+
+#[test]
+fn const_level() {
+    const LEVEL: log::Level = log::Level::Debug;
+
+    log!(const log::Level::Info, "hello");
+    log!(const LEVEL, "hello {}", 1);
+    log!(target: "my_target", const LEVEL, value = 1; "hello");
+    log!(sample: 0.5, target: "my_target", const LEVEL, "hello");
+    log!(kind: log::RecordKind::Event, const LEVEL, "hello");
+    log!(module_path: "my_app", file: "src/main.rs", line: 1, const LEVEL, "hello");
+}
+
+#[test]
+fn record_and_metadata() {
+    let metadata = log::Metadata::builder()
+        .level(log::Level::Warn)
+        .target("my_target")
+        .build();
+
+    log!(metadata: metadata, "hello");
+    log!(metadata: &metadata, "hello {}", 1);
+    log!(metadata: log::Metadata::builder().build(), "hello",);
+
+    let record = log::Record::builder()
+        .args(format_args!("hello"))
+        .metadata(metadata.clone())
+        .build();
+
+    log!(record: record);
+    log!(record: &record);
+}
+
+#[test]
+fn location() {
+    let location = log::location!();
+
+    assert_eq!(file!(), location.file());
+    assert_eq!(line!() - 3, location.line());
+}
+
+#[test]
+fn macro_support() {
+    macro_rules! logv {
+        ($lvl:expr, $e:expr) => {{
+            let lvl = $lvl;
+            let value = $e;
+
+            if log::macro_support::enabled(lvl, module_path!()) {
+                log::macro_support::log(
+                    format_args!("{} = {:?}", stringify!($e), value),
+                    lvl,
+                    module_path!(),
+                    module_path!(),
+                    log::macro_support::location(),
+                );
+            }
+
+            value
+        }};
+    }
+
+    for lvl in log::Level::iter() {
+        assert_eq!(42, logv!(lvl, 6 * 7));
+    }
+}
+
+#[test]
+#[cfg(feature = "kv")]
+fn macro_support_kvs() {
+    log::macro_support::log_kvs(
+        format_args!("hello"),
+        log::Level::Info,
+        "my_target",
+        module_path!(),
+        log::macro_support::location(),
+        &[("cat_1", "chashu".into())],
+    );
+}
+
+#[test]
+#[cfg(feature = "const_noop")]
+fn const_fn() {
+    const fn double(n: u32) -> u32 {
+        log!(const fn; log::Level::Info, "doubling {}", n);
+        log!(const fn; target: "my_target", log::Level::Info, n = n; "doubling");
+        log::error!(const fn; "doubling {n}");
+        log::warn!(const fn; target: "my_target", "doubling {}", n);
+        log::info!(const fn; "doubling {}", n);
+        log::debug!(const fn; "doubling {}", n);
+        log::trace!(const fn; "doubling {}", n);
+
+        n * 2
+    }
+
+    const DOUBLED: u32 = double(21);
+
+    assert_eq!(42, DOUBLED);
+    assert_eq!(42, double(21));
+}